@@ -0,0 +1,72 @@
+#!/usr/bin/env node
+// Minimal MCP stdio server for exercising probe_mcp_server; tests/mcp_probe_fixture.rs
+// runs it with each flag below, and it can be added to a config by hand:
+//
+//   { "command": "node", "args": ["src-tauri/fixtures/mcp-stdio-server.mjs"] }
+//
+// Flags simulate broken servers:
+//   --crash   write to stderr and exit 1 before answering initialize
+//   --hang    never answer initialize (hits the probe timeout)
+//   --noisy   log a non-JSON line to stdout before each reply
+//
+// Tools are split over two pages to exercise nextCursor pagination.
+import { createInterface } from 'node:readline';
+
+const flags = new Set(process.argv.slice(2));
+
+if (flags.has('--crash')) {
+  process.stderr.write('fixture: missing API_KEY, refusing to start\n');
+  process.exit(1);
+}
+
+const tools = [
+  {
+    name: 'echo',
+    description: 'Echo the input text',
+    inputSchema: { type: 'object', properties: { text: { type: 'string' } }, required: ['text'] },
+  },
+  {
+    name: 'add',
+    description: 'Add two numbers',
+    inputSchema: { type: 'object', properties: { a: { type: 'number' }, b: { type: 'number' } } },
+  },
+];
+
+const handlers = {
+  initialize: (params) => ({
+    protocolVersion: params.protocolVersion,
+    capabilities: { tools: {}, prompts: {}, resources: {} },
+    serverInfo: { name: 'agent-studio-fixture', version: '1.0.0' },
+    instructions: 'Fixture server for probe testing',
+  }),
+  ping: () => ({}),
+  'tools/list': (params) =>
+    params?.cursor === 'page-2' ? { tools: tools.slice(1) } : { tools: tools.slice(0, 1), nextCursor: 'page-2' },
+  'prompts/list': () => ({
+    prompts: [{ name: 'review', description: 'Review a file', arguments: [{ name: 'path', required: true }] }],
+  }),
+  'resources/list': () => ({
+    resources: [{ uri: 'file:///fixture/readme.md', name: 'readme', mimeType: 'text/markdown' }],
+  }),
+};
+
+function send(message) {
+  if (flags.has('--noisy')) process.stdout.write('fixture: handling request\n');
+  process.stdout.write(JSON.stringify(message) + '\n');
+}
+
+process.stderr.write('fixture: started\n');
+
+createInterface({ input: process.stdin }).on('line', (line) => {
+  if (!line.trim()) return;
+  const message = JSON.parse(line);
+  if (message.id === undefined) return; // Notifications need no reply
+  if (message.method === 'initialize' && flags.has('--hang')) return;
+
+  const handler = handlers[message.method];
+  if (handler) {
+    send({ jsonrpc: '2.0', id: message.id, result: handler(message.params) });
+  } else {
+    send({ jsonrpc: '2.0', id: message.id, error: { code: -32601, message: `Method not found: ${message.method}` } });
+  }
+});
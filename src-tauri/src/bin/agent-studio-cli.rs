@@ -0,0 +1,387 @@
+// ============================================================================
+// Agent Studio - Headless CLI
+// Discovery, health checks and fixes without the GUI, for CI, dev containers
+// and SSH sessions. Calls the same backend functions as the Tauri commands.
+// ============================================================================
+
+use std::process::ExitCode;
+
+use agent_studio_lib::commands::{self, ConfigStateType, Severity};
+use agent_studio_lib::mcp_server::{self, ServeOptions};
+use agent_studio_lib::skills::{self, AgentId, InstallRequest, InstallResult, InstallScope};
+
+const USAGE: &str = "\
+Usage: agent-studio-cli <command> [options]
+
+Commands:
+  scan [DIR...]                  Find projects with Claude Code / OpenCode config under DIR (default: .)
+  discover [--json]              Discover every agent, skill, command, hook, plugin and MCP server
+  doctor [--json] [--strict]     Report health issues; exits 1 on errors (or warnings with --strict)
+  fix-config [--dry-run]         Make CLAUDE.md a symlink to AGENTS.md where it can be done safely
+  skills search QUERY [--limit N] [--json]
+  skills list [--json]           Skills installed through skills.sh
+  skills install SOURCE [--global] [--agent ID]...
+  skills remove NAME [--global]
+  mcp list [--json]              MCP servers from every Claude Code and OpenCode config
+  mcp serve [--read-only]        Run as an MCP stdio server exposing discovery, skills and entity creation
+
+Options:
+  --project PATH                 Project to include (repeatable; default: the current directory)
+  -h, --help                     Show this help
+  -V, --version                  Show the version
+";
+
+/// Options that take a value; everything else starting with `-` is a flag
+const VALUE_OPTIONS: &[&str] = &["--project", "--limit", "--agent"];
+
+// ============================================================================
+// Argument Parsing
+// ============================================================================
+
+struct Args {
+    positional: Vec<String>,
+    flags: Vec<String>,
+    options: Vec<(String, String)>,
+}
+
+impl Args {
+    fn parse(raw: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut args = Args { positional: Vec::new(), flags: Vec::new(), options: Vec::new() };
+        let mut raw = raw.into_iter();
+        while let Some(arg) = raw.next() {
+            if arg == "--" {
+                args.positional.extend(raw.by_ref());
+            } else if let Some((name, value)) = arg.split_once('=').filter(|(n, _)| n.starts_with("--")) {
+                if !VALUE_OPTIONS.contains(&name) {
+                    return Err(format!("{} does not take a value", name));
+                }
+                args.options.push((name.to_string(), value.to_string()));
+            } else if VALUE_OPTIONS.contains(&arg.as_str()) {
+                let value = raw.next().ok_or_else(|| format!("{} needs a value", arg))?;
+                args.options.push((arg, value));
+            } else if arg.starts_with('-') && arg.len() > 1 {
+                args.flags.push(arg);
+            } else {
+                args.positional.push(arg);
+            }
+        }
+        Ok(args)
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|f| f == name)
+    }
+
+    fn values(&self, name: &str) -> Vec<String> {
+        self.options.iter().filter(|(n, _)| n == name).map(|(_, v)| v.clone()).collect()
+    }
+
+    /// Fail on flags the subcommand doesn't understand instead of ignoring them
+    fn expect_flags(&self, known: &[&str]) -> Result<(), String> {
+        match self.flags.iter().find(|f| !known.contains(&f.as_str())) {
+            Some(flag) => Err(format!("Unknown option {}", flag)),
+            None => Ok(()),
+        }
+    }
+
+    /// `--project` values, or the current directory
+    fn projects(&self) -> Result<Vec<String>, String> {
+        let projects = self.values("--project");
+        if !projects.is_empty() {
+            return Ok(projects.iter().map(|p| absolute(p)).collect());
+        }
+        Ok(vec![absolute(".")])
+    }
+}
+
+fn absolute(path: &str) -> String {
+    std::fs::canonicalize(path)
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| path.to_string())
+}
+
+// ============================================================================
+// Output
+// ============================================================================
+
+fn print_json<T: serde::Serialize>(value: &T) -> Result<(), String> {
+    println!("{}", serde_json::to_string_pretty(value).map_err(|e| e.to_string())?);
+    Ok(())
+}
+
+/// Left-aligned columns sized to their widest cell
+fn print_table(header: &[&str], rows: &[Vec<String>]) {
+    let mut widths: Vec<usize> = header.iter().map(|h| h.len()).collect();
+    for row in rows {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.chars().count());
+        }
+    }
+    let line = |cells: Vec<&str>| {
+        let padded: Vec<String> = cells.iter().enumerate().map(|(i, c)| format!("{:<width$}", c, width = widths[i])).collect();
+        println!("{}", padded.join("  ").trim_end());
+    };
+    line(header.to_vec());
+    for row in rows {
+        line(row.iter().map(String::as_str).collect());
+    }
+}
+
+fn severity_label(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Info => "info",
+    }
+}
+
+// ============================================================================
+// Subcommands
+// ============================================================================
+
+fn cmd_scan(args: &Args) -> Result<ExitCode, String> {
+    args.expect_flags(&["--json"])?;
+    let dirs = if args.positional.is_empty() { vec![absolute(".")] } else { args.positional.iter().map(|d| absolute(d)).collect() };
+    let projects = commands::scan_projects(dirs)?;
+    if args.flag("--json") {
+        print_json(&projects)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+    let rows: Vec<Vec<String>> = projects.iter().map(|p| {
+        let c = &p.entity_counts;
+        vec![
+            p.name.clone(),
+            format!("{} agents, {} skills, {} commands, {} hooks, {} mcp", c.agents, c.skills, c.commands, c.hooks, c.mcp),
+            p.path.clone(),
+        ]
+    }).collect();
+    print_table(&["PROJECT", "ENTITIES", "PATH"], &rows);
+    Ok(ExitCode::SUCCESS)
+}
+
+fn cmd_discover(args: &Args) -> Result<ExitCode, String> {
+    args.expect_flags(&["--json"])?;
+    let result = commands::discover_all(Some(args.projects()?))?;
+    if args.flag("--json") {
+        print_json(&result)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+    let rows = vec![
+        vec!["settings".to_string(), result.settings.len().to_string()],
+        vec!["memory".to_string(), result.memory.len().to_string()],
+        vec!["agents".to_string(), result.agents.len().to_string()],
+        vec!["skills".to_string(), result.skills.len().to_string()],
+        vec!["commands".to_string(), result.commands.len().to_string()],
+        vec!["hooks".to_string(), result.hooks.len().to_string()],
+        vec!["plugins".to_string(), result.plugins.len().to_string()],
+        vec!["mcp servers".to_string(), result.mcp_servers.len().to_string()],
+        vec!["lsp servers".to_string(), result.lsp_servers.len().to_string()],
+    ];
+    print_table(&["ENTITY", "COUNT"], &rows);
+    Ok(ExitCode::SUCCESS)
+}
+
+fn cmd_doctor(args: &Args) -> Result<ExitCode, String> {
+    args.expect_flags(&["--json", "--strict"])?;
+    let report = commands::discover_all(Some(args.projects()?))?.health;
+    let issues = &report.issues;
+    for error in &report.config_errors {
+        eprintln!("warning: {}", error);
+    }
+
+    if args.flag("--json") {
+        print_json(&report)?;
+    } else if issues.is_empty() {
+        println!("No issues found");
+    } else {
+        for issue in issues {
+            println!("{:<7}  [{}] {}", severity_label(issue.severity), issue.check_id, issue.title);
+            println!("         {}", issue.description);
+            if let Some(path) = &issue.path {
+                println!("         {}", path);
+            }
+        }
+    }
+
+    let errors = issues.iter().filter(|i| i.severity == Severity::Error).count();
+    let warnings = issues.iter().filter(|i| i.severity == Severity::Warning).count();
+    if !args.flag("--json") && !issues.is_empty() {
+        println!("\n{} errors, {} warnings, {} info", errors, warnings, issues.len() - errors - warnings);
+    }
+    if !args.flag("--json") && !report.suppressed.is_empty() {
+        println!("{} suppressed by {}", report.suppressed.len(), commands::health::PROJECT_CONFIG_FILE);
+    }
+    let failed = errors > 0 || (args.flag("--strict") && warnings > 0);
+    Ok(if failed { ExitCode::FAILURE } else { ExitCode::SUCCESS })
+}
+
+fn cmd_fix_config(args: &Args) -> Result<ExitCode, String> {
+    args.expect_flags(&["--dry-run"])?;
+    let mut failed = false;
+    for project in args.projects()? {
+        let state = commands::get_project_config_state(project.clone())?;
+        match state.config_state {
+            ConfigStateType::Correct => println!("{}: already correct", project),
+            ConfigStateType::Conflict => {
+                println!("{}: AGENTS.md and CLAUDE.md both have content; merge them by hand", project);
+                failed = true;
+            }
+            ConfigStateType::MissingSymlink if args.flag("--dry-run") => println!("{}: would create CLAUDE.md → AGENTS.md symlink", project),
+            ConfigStateType::NeedsMigration if args.flag("--dry-run") => println!("{}: would move CLAUDE.md to AGENTS.md and symlink it", project),
+            ConfigStateType::Empty if args.flag("--dry-run") => println!("{}: would create AGENTS.md and a CLAUDE.md symlink", project),
+            _ => println!("{}: {}", project, commands::fix_project_config(project.clone())?),
+        }
+    }
+    Ok(if failed { ExitCode::FAILURE } else { ExitCode::SUCCESS })
+}
+
+fn print_install_result(result: &InstallResult, done: &str) -> ExitCode {
+    if result.success {
+        println!("{} {}", done, result.skill_name);
+        ExitCode::SUCCESS
+    } else {
+        eprintln!("{}", result.error.as_deref().unwrap_or("npx skills failed").trim());
+        ExitCode::FAILURE
+    }
+}
+
+fn cmd_skills(args: &Args) -> Result<ExitCode, String> {
+    let runtime = tokio::runtime::Runtime::new().map_err(|e| e.to_string())?;
+    let action = args.positional.get(1).map(String::as_str);
+    let target = args.positional.get(2).cloned();
+    match action {
+        Some("search") => {
+            args.expect_flags(&["--json"])?;
+            let query = target.ok_or("skills search needs a QUERY")?;
+            let limit = args.values("--limit").last().map(|l| l.parse::<u32>().map_err(|_| format!("Invalid --limit: {}", l))).transpose()?;
+            let response = runtime.block_on(skills::api::search_skills(&query, limit, None))?;
+            if args.flag("--json") {
+                print_json(&response)?;
+            } else {
+                let rows: Vec<Vec<String>> = response.skills.iter().map(|s| vec![
+                    s.top_source.as_ref().map(|src| format!("{}/{}", src, s.name)).unwrap_or_else(|| s.name.clone()),
+                    s.installs.to_string(),
+                    s.description.clone().unwrap_or_default(),
+                ]).collect();
+                print_table(&["SKILL", "INSTALLS", "DESCRIPTION"], &rows);
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Some("list") => {
+            args.expect_flags(&["--json"])?;
+            let installed = skills::get_installed_skills()?;
+            if args.flag("--json") {
+                print_json(&installed)?;
+            } else {
+                let rows: Vec<Vec<String>> = installed.iter().map(|s| vec![
+                    s.name.clone(),
+                    s.source.clone(),
+                    if s.has_update { "update available".to_string() } else { String::new() },
+                ]).collect();
+                print_table(&["SKILL", "SOURCE", ""], &rows);
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Some("install") => {
+            args.expect_flags(&["--global"])?;
+            let source = target.ok_or("skills install needs a SOURCE (owner/repo or owner/repo/skill)")?;
+            let agents = args.values("--agent").iter().map(|name| {
+                AgentId::all().into_iter().find(|a| a.cli_name() == name).ok_or_else(|| format!("Unknown agent: {}", name))
+            }).collect::<Result<Vec<_>, _>>()?;
+            let global = args.flag("--global");
+            let request = InstallRequest {
+                skill_source: source,
+                scope: if global { InstallScope::Global } else { InstallScope::Project },
+                project_path: if global { None } else { args.projects()?.into_iter().next() },
+                agents,
+            };
+            let result = runtime.block_on(skills::install_skill(request))?;
+            Ok(print_install_result(&result, "Installed"))
+        }
+        Some("remove") => {
+            args.expect_flags(&["--global"])?;
+            let name = target.ok_or("skills remove needs a NAME")?;
+            let global = args.flag("--global");
+            let project_path = if global { None } else { args.projects()?.into_iter().next() };
+            let result = runtime.block_on(skills::remove_skill(name, global, project_path))?;
+            Ok(print_install_result(&result, "Removed"))
+        }
+        Some(other) => Err(format!("Unknown skills command: {}", other)),
+        None => Err("skills needs a command: search, list, install or remove".to_string()),
+    }
+}
+
+fn cmd_mcp(args: &Args) -> Result<ExitCode, String> {
+    match args.positional.get(1).map(String::as_str) {
+        Some("list") => {
+            args.expect_flags(&["--json"])?;
+            let result = commands::discover_all(Some(args.projects()?))?;
+            if args.flag("--json") {
+                print_json(&result.mcp_servers)?;
+                return Ok(ExitCode::SUCCESS);
+            }
+            let rows: Vec<Vec<String>> = result.mcp_servers.iter().map(|s| vec![
+                s.name.clone(),
+                s.tool.clone(),
+                s.plugin_name.as_ref().map(|p| format!("plugin:{}", p)).unwrap_or_else(|| s.scope.clone()),
+                s.transport.clone(),
+                s.approval.clone().unwrap_or_default(),
+                s.source_path.clone(),
+            ]).collect();
+            print_table(&["NAME", "TOOL", "SCOPE", "TRANSPORT", "APPROVAL", "SOURCE"], &rows);
+            Ok(ExitCode::SUCCESS)
+        }
+        Some("serve") => {
+            args.expect_flags(&["--read-only"])?;
+            mcp_server::serve_stdio(ServeOptions { read_only: args.flag("--read-only") })?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Some(other) => Err(format!("Unknown mcp command: {}", other)),
+        None => Err("mcp needs a command: list or serve".to_string()),
+    }
+}
+
+// ============================================================================
+// Entry Point
+// ============================================================================
+
+fn run(args: &Args) -> Result<ExitCode, String> {
+    match args.positional.first().map(String::as_str) {
+        Some("scan") => cmd_scan(args),
+        Some("discover") => cmd_discover(args),
+        Some("doctor") => cmd_doctor(args),
+        Some("fix-config") => cmd_fix_config(args),
+        Some("skills") => cmd_skills(args),
+        Some("mcp") => cmd_mcp(args),
+        Some(other) => Err(format!("Unknown command: {} (see --help)", other)),
+        None => {
+            eprint!("{}", USAGE);
+            Ok(ExitCode::from(2))
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::from(2);
+        }
+    };
+    if args.flag("--help") || args.flag("-h") {
+        print!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+    if args.flag("--version") || args.flag("-V") {
+        println!("agent-studio-cli {}", env!("CARGO_PKG_VERSION"));
+        return ExitCode::SUCCESS;
+    }
+    match run(&args) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,493 @@
+// ============================================================================
+// Agent Studio - Fix Plans
+// File operations attached to health issues: previewed as a diff against an
+// in-memory copy of the affected files, applied all-or-nothing
+// ============================================================================
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::frontmatter;
+use super::health::HealthFix;
+use super::json_edit;
+use super::permissions::{apply_patch_op, JsonPatchOp};
+use super::secret_scan::{plan_secret_fix, SecretFinding};
+
+/// Symlink hops followed when an edit targets a link
+const MAX_LINK_DEPTH: usize = 8;
+
+/// Unchanged lines shown around each diff hunk
+const DIFF_CONTEXT: usize = 3;
+
+/// Largest LCS table built for a diff (about 32 MB); bigger changes are shown as a plain replacement
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// A single step of a fix plan
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum FixOp {
+    /// Create a symlink at `path`; `target` is stored as given (relative to the link's directory)
+    CreateSymlink { path: String, target: String },
+    /// Remove the symlink at `path`; refuses regular files
+    DeleteLink { path: String },
+    /// Move a file or directory; fails if `to` exists
+    Rename { from: String, to: String },
+    /// Patch a JSON settings file in place, keeping its formatting. A missing file starts as `{}`.
+    JsonPatch { file: String, ops: Vec<JsonPatchOp> },
+    /// Append a line to a text file (.gitignore, .env) unless it's already there
+    AppendLine { path: String, line: String },
+    /// Set (or with no value, remove) one top-level frontmatter key of a markdown
+    /// file, leaving the other keys and the body untouched
+    SetFrontmatterKey { path: String, key: String, value: Option<serde_json::Value> },
+    /// Move a literal secret into local config; expanded into the edits above
+    /// when previewed or applied, so the secret itself never travels in a plan
+    ExtractSecret { finding: Box<SecretFinding>, var_name: Option<String> },
+}
+
+/// How one path changes when a fix is applied
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FileDiff {
+    pub path: String,
+    pub before: String,  // "file", "symlink", "directory", "missing"
+    pub after: String,
+    /// Unified diff; a symlink reads as a one-line file holding its target, as in git
+    pub diff: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FixPreview {
+    pub description: String,
+    pub files: Vec<FileDiff>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ApplyFixesResult {
+    pub applied: usize,
+    pub files: Vec<String>,
+}
+
+/// State of a path on disk, or as a fix plan would leave it
+#[derive(Debug, Clone, PartialEq)]
+enum Entry {
+    Missing,
+    File(String),
+    Symlink(String),
+    /// Only ever moved whole, so its contents aren't read
+    Dir,
+}
+
+impl Entry {
+    fn kind(&self) -> &'static str {
+        match self {
+            Entry::Missing => "missing",
+            Entry::File(_) => "file",
+            Entry::Symlink(_) => "symlink",
+            Entry::Dir => "directory",
+        }
+    }
+
+    fn text(&self) -> String {
+        match self {
+            Entry::Missing | Entry::Dir => String::new(),
+            Entry::File(content) => content.clone(),
+            Entry::Symlink(target) => format!("{}\n", target),
+        }
+    }
+}
+
+// ============================================================================
+// Simulation
+// ============================================================================
+
+fn read_entry(path: &Path) -> Result<Entry, String> {
+    match fs::symlink_metadata(path) {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Entry::Missing),
+        Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+        Ok(meta) if meta.file_type().is_symlink() => fs::read_link(path)
+            .map(|t| Entry::Symlink(t.to_string_lossy().to_string()))
+            .map_err(|e| format!("Failed to read link {}: {}", path.display(), e)),
+        Ok(meta) if meta.is_dir() => Ok(Entry::Dir),
+        Ok(_) => fs::read_to_string(path)
+            .map(Entry::File)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e)),
+    }
+}
+
+/// The files a set of fix plans touches, as they are on disk and as the plans would leave them
+#[derive(Default)]
+struct Overlay {
+    original: BTreeMap<PathBuf, Entry>,
+    current: BTreeMap<PathBuf, Entry>,
+    /// Disk after only the renames, which are applied first with `fs::rename`
+    moved: BTreeMap<PathBuf, Entry>,
+    renames: Vec<(PathBuf, PathBuf)>,
+}
+
+impl Overlay {
+    fn get(&mut self, path: &Path) -> Result<Entry, String> {
+        if let Some(entry) = self.current.get(path) {
+            return Ok(entry.clone());
+        }
+        let entry = read_entry(path)?;
+        self.original.insert(path.to_path_buf(), entry.clone());
+        self.moved.insert(path.to_path_buf(), entry.clone());
+        self.current.insert(path.to_path_buf(), entry.clone());
+        Ok(entry)
+    }
+
+    fn set(&mut self, path: &Path, entry: Entry) -> Result<(), String> {
+        self.get(path)?;
+        self.current.insert(path.to_path_buf(), entry);
+        Ok(())
+    }
+
+    /// Follow symlinks so content edits land in the link target
+    fn resolve(&mut self, path: &Path) -> Result<PathBuf, String> {
+        let mut path = path.to_path_buf();
+        for _ in 0..MAX_LINK_DEPTH {
+            match self.get(&path)? {
+                Entry::Symlink(target) => {
+                    let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+                    path = dir.join(target);
+                }
+                _ => return Ok(path),
+            }
+        }
+        Err(format!("Too many levels of symlinks at {}", path.display()))
+    }
+
+    fn apply(&mut self, op: &FixOp) -> Result<(), String> {
+        match op {
+            FixOp::CreateSymlink { path, target } => {
+                let path = Path::new(path);
+                if self.get(path)? != Entry::Missing {
+                    return Err(format!("{} already exists", path.display()));
+                }
+                self.set(path, Entry::Symlink(target.clone()))
+            }
+            FixOp::DeleteLink { path } => {
+                let path = Path::new(path);
+                match self.get(path)? {
+                    Entry::Symlink(_) => self.set(path, Entry::Missing),
+                    Entry::Missing => Err(format!("{} no longer exists", path.display())),
+                    Entry::File(_) | Entry::Dir => Err(format!("{} is not a symlink", path.display())),
+                }
+            }
+            FixOp::Rename { from, to } => {
+                let (from, to) = (Path::new(from), Path::new(to));
+                let entry = self.get(from)?;
+                if entry == Entry::Missing {
+                    return Err(format!("{} no longer exists", from.display()));
+                }
+                if self.get(to)? != Entry::Missing {
+                    return Err(format!("{} already exists", to.display()));
+                }
+                // Renames run before content writes, so the source must still be as it is on disk
+                if self.moved[from] != entry {
+                    return Err(format!("{} is edited before it's moved; move it first", from.display()));
+                }
+                self.moved.insert(from.to_path_buf(), Entry::Missing);
+                self.moved.insert(to.to_path_buf(), entry.clone());
+                self.renames.push((from.to_path_buf(), to.to_path_buf()));
+                self.set(from, Entry::Missing)?;
+                self.set(to, entry)
+            }
+            FixOp::JsonPatch { file, ops } => {
+                let target = self.resolve(Path::new(file))?;
+                let source = match self.get(&target)? {
+                    Entry::Dir => return Err(format!("{} is a directory", target.display())),
+                    Entry::File(content) if !content.trim().is_empty() => content,
+                    _ => "{}\n".to_string(),
+                };
+                let updated = ops.iter()
+                    .try_fold(source, |text, op| apply_patch_op(&text, op))
+                    .map_err(|e| format!("{}: {}", file, e))?;
+                json_edit::to_value(&updated).map_err(|e| format!("Edit produced invalid JSON in {}: {}", file, e))?;
+                self.set(&target, Entry::File(updated))
+            }
+            FixOp::AppendLine { path, line } => {
+                let target = self.resolve(Path::new(path))?;
+                let mut content = match self.get(&target)? {
+                    Entry::Dir => return Err(format!("{} is a directory", target.display())),
+                    Entry::File(content) => content,
+                    _ => String::new(),
+                };
+                if content.lines().any(|l| l.trim() == line.trim()) {
+                    return Ok(());
+                }
+                if !content.is_empty() && !content.ends_with('\n') {
+                    content.push('\n');
+                }
+                content.push_str(line);
+                content.push('\n');
+                self.set(&target, Entry::File(content))
+            }
+            FixOp::SetFrontmatterKey { path, key, value } => {
+                let target = self.resolve(Path::new(path))?;
+                let content = match self.get(&target)? {
+                    Entry::File(content) => content,
+                    Entry::Dir => return Err(format!("{} is a directory", path)),
+                    _ => return Err(format!("{} no longer exists", path)),
+                };
+                let updated = match value {
+                    Some(value) => frontmatter::set(&content, key, value),
+                    None => frontmatter::remove(&content, key),
+                }.map_err(|e| format!("{}: {}", path, e))?;
+                self.set(&target, Entry::File(updated))
+            }
+            FixOp::ExtractSecret { finding, var_name } => {
+                for op in plan_secret_fix(finding, var_name.clone())?.ops {
+                    self.apply(&op)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Paths whose final state differs from disk, in path order
+    fn changes(&self) -> Vec<(&PathBuf, &Entry, &Entry)> {
+        self.current.iter()
+            .filter_map(|(path, after)| {
+                let before = &self.original[path];
+                (before != after).then_some((path, before, after))
+            })
+            .collect()
+    }
+}
+
+fn simulate<'a>(fixes: impl IntoIterator<Item = &'a HealthFix>) -> Result<Overlay, String> {
+    let mut overlay = Overlay::default();
+    for fix in fixes {
+        for op in &fix.ops {
+            overlay.apply(op).map_err(|e| format!("{}: {}", fix.description, e))?;
+        }
+    }
+    Ok(overlay)
+}
+
+// ============================================================================
+// Diff
+// ============================================================================
+
+/// Line-based unified diff (longest common subsequence; config files are small)
+fn unified_diff(path: &str, before: &Entry, after: &Entry) -> String {
+    let old_text = before.text();
+    let new_text = after.text();
+    let old: Vec<&str> = old_text.lines().collect();
+    let new: Vec<&str> = new_text.lines().collect();
+
+    // Lines shared at both ends are context as they stand, so only the changed
+    // middle needs the quadratic LCS table
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..].iter().rev().zip(new[prefix..].iter().rev()).take_while(|(a, b)| a == b).count();
+    let (old_mid, new_mid) = (&old[prefix..old.len() - suffix], &new[prefix..new.len() - suffix]);
+
+    // Edit script as (tag, old index, new index)
+    let mut script: Vec<(char, usize, usize)> = (0..prefix).map(|k| (' ', k, k)).collect();
+    if (old_mid.len() + 1).saturating_mul(new_mid.len() + 1) <= MAX_LCS_CELLS {
+        // lcs[i][j] = length of the common subsequence of old_mid[i..] and new_mid[j..]
+        let mut lcs = vec![vec![0usize; new_mid.len() + 1]; old_mid.len() + 1];
+        for i in (0..old_mid.len()).rev() {
+            for j in (0..new_mid.len()).rev() {
+                lcs[i][j] = if old_mid[i] == new_mid[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+            }
+        }
+
+        let (mut i, mut j) = (0, 0);
+        while i < old_mid.len() || j < new_mid.len() {
+            if i < old_mid.len() && j < new_mid.len() && old_mid[i] == new_mid[j] {
+                script.push((' ', prefix + i, prefix + j));
+                i += 1;
+                j += 1;
+            } else if i < old_mid.len() && (j == new_mid.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+                script.push(('-', prefix + i, prefix + j));
+                i += 1;
+            } else {
+                script.push(('+', prefix + i, prefix + j));
+                j += 1;
+            }
+        }
+    } else {
+        // Too large to align line by line: show the whole middle as replaced
+        script.extend((0..old_mid.len()).map(|i| ('-', prefix + i, prefix)));
+        script.extend((0..new_mid.len()).map(|j| ('+', prefix + old_mid.len(), prefix + j)));
+    }
+    script.extend((0..suffix).map(|k| (' ', old.len() - suffix + k, new.len() - suffix + k)));
+
+    let from = if *before == Entry::Missing { "/dev/null".to_string() } else { format!("a{}", path) };
+    let to = if *after == Entry::Missing { "/dev/null".to_string() } else { format!("b{}", path) };
+    let mut out = format!("--- {}\n+++ {}\n", from, to);
+
+    let changed: Vec<usize> = script.iter().enumerate().filter(|(_, s)| s.0 != ' ').map(|(k, _)| k).collect();
+    let mut k = 0;
+    while k < changed.len() {
+        // Grow the hunk while the next change is within two contexts of the last
+        let start = changed[k].saturating_sub(DIFF_CONTEXT);
+        let mut last = changed[k];
+        while k + 1 < changed.len() && changed[k + 1] - last <= 2 * DIFF_CONTEXT {
+            k += 1;
+            last = changed[k];
+        }
+        let end = (last + DIFF_CONTEXT + 1).min(script.len());
+        let hunk = &script[start..end];
+
+        let old_count = hunk.iter().filter(|s| s.0 != '+').count();
+        let new_count = hunk.iter().filter(|s| s.0 != '-').count();
+        let old_start = if old_count == 0 { hunk[0].1 } else { hunk[0].1 + 1 };
+        let new_start = if new_count == 0 { hunk[0].2 } else { hunk[0].2 + 1 };
+        out.push_str(&format!("@@ -{},{} +{},{} @@\n", old_start, old_count, new_start, new_count));
+        for &(tag, oi, nj) in hunk {
+            let line = if tag == '+' { new[nj] } else { old[oi] };
+            out.push_str(&format!("{}{}\n", tag, line));
+        }
+        k += 1;
+    }
+    out
+}
+
+// ============================================================================
+// Applying
+// ============================================================================
+
+fn write_entry(path: &Path, entry: &Entry) -> Result<(), String> {
+    // Clear what's there unless a file is being rewritten in place; a link must not be followed
+    let is_link = fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_symlink());
+    if is_link || !matches!(entry, Entry::File(_)) {
+        match fs::remove_file(path) {
+            // Nothing there to remove, even if the parent isn't a directory
+            Err(_) if fs::symlink_metadata(path).is_err() => {}
+            Err(e) => return Err(format!("Failed to remove {}: {}", path.display(), e)),
+            Ok(()) => {}
+        }
+    }
+    if *entry == Entry::Missing {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    match entry {
+        Entry::Missing => Ok(()),
+        Entry::Dir => fs::create_dir_all(path).map_err(|e| format!("Failed to create {}: {}", path.display(), e)),
+        Entry::File(content) => {
+            let current = fs::read_to_string(path).ok();
+            json_edit::write_if_unchanged(path, current.as_deref(), content)
+        }
+        Entry::Symlink(target) => {
+            #[cfg(unix)]
+            let linked = std::os::unix::fs::symlink(target, path);
+            #[cfg(windows)]
+            let linked = std::os::windows::fs::symlink_file(target, path);
+            linked.map_err(|e| format!("Failed to create symlink {}: {}", path.display(), e))
+        }
+    }
+}
+
+/// A step already taken on disk
+enum Done<'a> {
+    Renamed(&'a Path, &'a Path),
+    Wrote(&'a Path, &'a Entry),
+}
+
+/// Undo the steps taken so far, newest first, and add whatever couldn't be
+/// restored to the error so the caller knows the files aren't as they were
+fn roll_back(error: String, done: &[Done]) -> String {
+    let failures: Vec<String> = done.iter().rev()
+        .filter_map(|step| match step {
+            Done::Renamed(from, to) => fs::rename(to, from).err()
+                .map(|e| format!("Failed to move {} back to {}: {}", to.display(), from.display(), e)),
+            Done::Wrote(path, before) => write_entry(path, before).err(),
+        })
+        .collect();
+    if failures.is_empty() {
+        error
+    } else {
+        format!("{}. Rolling back also failed, so these paths may be left changed: {}", error, failures.join("; "))
+    }
+}
+
+/// Carry out the overlay's renames, then write every other change, undoing
+/// what was already done if a step fails
+fn commit(overlay: &Overlay) -> Result<Vec<String>, String> {
+    let changes = overlay.changes();
+
+    // Refuse if anything moved on disk since the plans were simulated
+    for (path, before, _) in &changes {
+        if read_entry(path)? != **before {
+            return Err(format!("{} was modified by another process; reload and try again", path.display()));
+        }
+    }
+
+    // A real rename keeps file modes and works for directories
+    let mut done = Vec::new();
+    for (from, to) in &overlay.renames {
+        let renamed = match to.parent() {
+            Some(parent) => fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e)),
+            None => Ok(()),
+        }.and_then(|_| {
+            fs::rename(from, to).map_err(|e| format!("Failed to move {} to {}: {}", from.display(), to.display(), e))
+        });
+        if let Err(e) = renamed {
+            return Err(roll_back(e, &done));
+        }
+        done.push(Done::Renamed(from, to));
+    }
+
+    for (path, after) in &overlay.current {
+        let before = &overlay.moved[path];
+        if before == after {
+            continue;
+        }
+        // The failed write may have removed a link before failing, so it's restored too
+        let written = write_entry(path, after);
+        done.push(Done::Wrote(path, before));
+        if let Err(e) = written {
+            return Err(roll_back(e, &done));
+        }
+    }
+    Ok(changes.iter().map(|(path, _, _)| path.to_string_lossy().to_string()).collect())
+}
+
+/// Apply fix operations all-or-nothing; used by commands that build their own plans
+pub(crate) fn apply_ops(ops: &[FixOp]) -> Result<Vec<String>, String> {
+    let mut overlay = Overlay::default();
+    for op in ops {
+        overlay.apply(op)?;
+    }
+    commit(&overlay)
+}
+
+// ============================================================================
+// Public Tauri Commands
+// ============================================================================
+
+/// Show exactly what a fix would change, without touching disk
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn preview_fix(fix: HealthFix) -> Result<FixPreview, String> {
+    let overlay = simulate([&fix])?;
+    let files = overlay.changes().into_iter().map(|(path, before, after)| {
+        let path = path.to_string_lossy().to_string();
+        FileDiff {
+            diff: unified_diff(&path, before, after),
+            before: before.kind().to_string(),
+            after: after.kind().to_string(),
+            path,
+        }
+    }).collect();
+    Ok(FixPreview { description: fix.description, files })
+}
+
+/// Apply several fixes as one transaction: every plan is checked against the
+/// files first, and if any write fails the files already written are restored
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn apply_fixes(fixes: Vec<HealthFix>) -> Result<ApplyFixesResult, String> {
+    let overlay = simulate(&fixes)?;
+    let files = commit(&overlay)?;
+    Ok(ApplyFixesResult { applied: fixes.len(), files })
+}
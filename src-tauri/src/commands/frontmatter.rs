@@ -0,0 +1,256 @@
+// ============================================================================
+// Agent Studio - Markdown Frontmatter
+// Locate the YAML block at the top of agent, skill and command files and
+// edit individual keys while leaving the rest of the file byte-for-byte intact
+// ============================================================================
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+const BOM: &str = "\u{feff}";
+
+// ============================================================================
+// Splitting
+// ============================================================================
+
+/// The frontmatter block of a markdown file. Offsets are byte offsets into
+/// the whole file, including any BOM.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frontmatter<'a> {
+    pub content: &'a str,
+    /// The YAML between the fences; empty for `---\n---`
+    pub yaml: Range<usize>,
+    /// Where the body starts: just past the closing fence's line break, nothing trimmed
+    pub body_offset: usize,
+    /// The closing fence as written: "---" or "..."
+    pub closer: &'static str,
+    /// Line break used by the opening fence ("\n" or "\r\n")
+    pub newline: &'static str,
+}
+
+impl<'a> Frontmatter<'a> {
+    pub fn yaml_str(&self) -> &'a str {
+        &self.content[self.yaml.clone()]
+    }
+
+    pub fn body(&self) -> &'a str {
+        &self.content[self.body_offset..]
+    }
+
+    /// File line (1-based) of a line in the YAML text; the YAML always starts on line 2
+    pub fn file_line(&self, yaml_line: usize) -> usize {
+        yaml_line + 1
+    }
+
+    /// File line (1-based) where a top-level key is defined
+    pub fn key_line(&self, key: &str) -> Option<usize> {
+        let span = key_span(self.yaml_str(), key)?;
+        Some(LineIndex::new(self.content).line_column(self.yaml.start + span.start).0)
+    }
+}
+
+/// Lines ending in "\n" or "\r\n", with the break returned separately
+fn split_lines(text: &str) -> impl Iterator<Item = (usize, &str, &str)> {
+    let mut offset = 0;
+    std::iter::from_fn(move || {
+        if offset >= text.len() {
+            return None;
+        }
+        let rest = &text[offset..];
+        let (line, newline) = match rest.find('\n') {
+            Some(i) if i > 0 && rest.as_bytes()[i - 1] == b'\r' => (&rest[..i - 1], "\r\n"),
+            Some(i) => (&rest[..i], "\n"),
+            None => (rest, ""),
+        };
+        let start = offset;
+        offset += line.len() + newline.len();
+        Some((start, line, newline))
+    })
+}
+
+/// A fence line: the marker alone, trailing spaces and tabs allowed
+fn is_fence(line: &str, marker: &str) -> bool {
+    line.strip_prefix(marker).is_some_and(|rest| rest.trim_end_matches([' ', '\t']).is_empty())
+}
+
+/// Find the frontmatter block. Ok(None) when the file has none, Err when the
+/// opening `---` is never closed.
+///
+/// Only a line that is exactly `---` or `...` closes the block, so `---`
+/// inside a value (`description: before --- after`) or an indented block
+/// scalar doesn't end it early.
+pub fn split(content: &str) -> Result<Option<Frontmatter<'_>>, String> {
+    let start = if content.starts_with(BOM) { BOM.len() } else { 0 };
+    let mut lines = split_lines(&content[start..]).map(|(offset, line, newline)| (offset + start, line, newline));
+
+    let newline = match lines.next() {
+        Some((_, line, newline)) if is_fence(line, "---") => match newline {
+            "" => return Err("The opening --- has no closing --- or ...".to_string()),
+            "\r\n" => "\r\n",
+            _ => "\n",
+        },
+        _ => return Ok(None),
+    };
+
+    let yaml_start = start + content[start..].find('\n').map_or(0, |i| i + 1);
+    for (offset, line, line_newline) in lines {
+        let closer = if is_fence(line, "---") {
+            "---"
+        } else if is_fence(line, "...") {
+            "..."
+        } else {
+            continue;
+        };
+        return Ok(Some(Frontmatter {
+            content,
+            yaml: yaml_start..offset,
+            body_offset: offset + line.len() + line_newline.len(),
+            closer,
+            newline,
+        }));
+    }
+    Err("The opening --- has no closing --- or ...".to_string())
+}
+
+/// Frontmatter keys and the body. Files without frontmatter, or whose YAML
+/// doesn't parse, come back whole as the body so nothing is lost on save.
+pub fn parse_frontmatter(content: &str) -> (Option<HashMap<String, serde_json::Value>>, String) {
+    if let Ok(Some(fm)) = split(content) {
+        let yaml = fm.yaml_str();
+        if yaml.trim().is_empty() {
+            return (Some(HashMap::new()), fm.body().to_string());
+        }
+        if let Ok(map) = serde_yaml::from_str::<HashMap<String, serde_json::Value>>(yaml) {
+            return (Some(map), fm.body().to_string());
+        }
+    }
+    let body = content.strip_prefix(BOM).unwrap_or(content);
+    (None, body.to_string())
+}
+
+// ============================================================================
+// Line Map
+// ============================================================================
+
+/// Byte offset <-> line/column lookups for a file
+pub struct LineIndex {
+    starts: Vec<usize>,
+}
+
+impl LineIndex {
+    pub fn new(content: &str) -> Self {
+        let mut starts = vec![0];
+        starts.extend(content.match_indices('\n').map(|(i, _)| i + 1));
+        LineIndex { starts }
+    }
+
+    /// 1-based line and byte column of an offset
+    pub fn line_column(&self, offset: usize) -> (usize, usize) {
+        let line = self.starts.partition_point(|&start| start <= offset);
+        (line, offset - self.starts[line - 1] + 1)
+    }
+}
+
+// ============================================================================
+// Key Editing
+// ============================================================================
+
+/// The top-level key defined on a YAML line, if any (`name:`, `"name":`, `'name':`)
+fn line_key(line: &str) -> Option<String> {
+    let (key, rest) = match line.chars().next()? {
+        quote @ ('"' | '\'') => {
+            let end = line[1..].find(quote)? + 1;
+            (line[1..end].to_string(), &line[end + 1..])
+        }
+        ' ' | '\t' | '#' | '-' | '?' | '{' | '[' => return None,
+        _ => {
+            let colon = line.match_indices(':')
+                .find(|(i, _)| line[i + 1..].is_empty() || line[i + 1..].starts_with([' ', '\t']))?.0;
+            (line[..colon].trim_end().to_string(), &line[colon..])
+        }
+    };
+    let after = rest.trim_start_matches([' ', '\t']).strip_prefix(':')?;
+    (after.is_empty() || after.starts_with([' ', '\t'])).then_some(key)
+}
+
+/// Byte span of a top-level key's entry in `yaml`: its line plus the indented
+/// (or `- ` list) lines that continue it, up to and including the last line break
+fn key_span(yaml: &str, key: &str) -> Option<Range<usize>> {
+    let lines: Vec<_> = split_lines(yaml).collect();
+    let index = lines.iter().position(|(_, line, _)| line_key(line).as_deref() == Some(key))?;
+    let mut end = index;
+    for (i, (_, line, _)) in lines.iter().enumerate().skip(index + 1) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if line.starts_with([' ', '\t']) || line.starts_with("- ") || *line == "-" {
+            end = i;
+        } else {
+            break;
+        }
+    }
+    let (start, _, _) = lines[index];
+    let (last, line, newline) = lines[end];
+    Some(start..last + line.len() + newline.len())
+}
+
+/// `key: value` as YAML, ending with a line break
+fn render_entry(key: &str, value: &serde_json::Value, newline: &str) -> Result<String, String> {
+    let mut map = serde_yaml::Mapping::new();
+    let yaml_value = serde_yaml::to_value(value).map_err(|e| e.to_string())?;
+    map.insert(serde_yaml::Value::String(key.to_string()), yaml_value);
+    let text = serde_yaml::to_string(&map).map_err(|e| e.to_string())?;
+    Ok(text.replace('\n', newline))
+}
+
+/// Value of a top-level key, None if missing or there's no frontmatter
+pub fn get(content: &str, key: &str) -> Result<Option<serde_json::Value>, String> {
+    let Some(fm) = split(content)? else { return Ok(None) };
+    if fm.yaml_str().trim().is_empty() {
+        return Ok(None);
+    }
+    let map: HashMap<String, serde_json::Value> = serde_yaml::from_str(fm.yaml_str())
+        .map_err(|e| format!("Invalid frontmatter: {}", e))?;
+    Ok(map.get(key).cloned())
+}
+
+/// Set a top-level key, replacing only that key's lines. A missing key is
+/// appended before the closing fence; a file without frontmatter gets a new block.
+pub fn set(content: &str, key: &str, value: &serde_json::Value) -> Result<String, String> {
+    let updated = match split(content)? {
+        Some(fm) => {
+            let entry = render_entry(key, value, fm.newline)?;
+            let yaml = fm.yaml_str();
+            match key_span(yaml, key) {
+                Some(span) => splice(content, fm.yaml.start + span.start, fm.yaml.start + span.end, &entry),
+                // The YAML always ends with a line break (or is empty), so this starts a new line
+                None => splice(content, fm.yaml.end, fm.yaml.end, &entry),
+            }
+        }
+        None => {
+            let newline = if content.contains("\r\n") { "\r\n" } else { "\n" };
+            let entry = render_entry(key, value, newline)?;
+            let start = if content.starts_with(BOM) { BOM.len() } else { 0 };
+            splice(content, start, start, &format!("---{}{}---{}", newline, entry, newline))
+        }
+    };
+    if get(&updated, key)?.as_ref() != Some(value) {
+        return Err(format!("Couldn't set `{}` without rewriting the frontmatter", key));
+    }
+    Ok(updated)
+}
+
+/// Remove a top-level key and its continuation lines; a no-op when it's absent
+pub fn remove(content: &str, key: &str) -> Result<String, String> {
+    let Some(fm) = split(content)? else { return Ok(content.to_string()) };
+    let Some(span) = key_span(fm.yaml_str(), key) else { return Ok(content.to_string()) };
+    let updated = splice(content, fm.yaml.start + span.start, fm.yaml.start + span.end, "");
+    if get(&updated, key)?.is_some() {
+        return Err(format!("Couldn't remove `{}` without rewriting the frontmatter", key));
+    }
+    Ok(updated)
+}
+
+fn splice(text: &str, start: usize, end: usize, replacement: &str) -> String {
+    format!("{}{}{}", &text[..start], replacement, &text[end..])
+}
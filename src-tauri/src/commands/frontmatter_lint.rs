@@ -0,0 +1,396 @@
+// ============================================================================
+// Agent Studio - Frontmatter Linter
+// Report YAML errors, missing or unknown keys, and tools and models that
+// Claude Code / OpenCode won't recognize in agent, skill and command files
+// ============================================================================
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+
+use super::frontmatter;
+use super::permissions::KNOWN_TOOLS;
+use super::{generate_id, AgentEntity, CommandEntity, McpServerEntity, Severity, SkillEntity};
+
+const CLAUDE_AGENT_KEYS: &[&str] = &[
+    "name", "description", "tools", "disallowedTools", "model", "permissionMode", "skills", "hooks", "color",
+    "mcpServers", "maxTurns", "memory",
+];
+const OPENCODE_AGENT_KEYS: &[&str] = &[
+    "description", "mode", "model", "temperature", "top_p", "tools", "permission", "prompt", "disable", "maxSteps",
+    "color", "hidden",
+];
+const CLAUDE_SKILL_KEYS: &[&str] = &[
+    "name", "description", "allowed-tools", "license", "metadata", "model", "version", "disable-model-invocation",
+    "user-invocable", "context", "agent", "hooks", "argument-hint",
+];
+const OPENCODE_SKILL_KEYS: &[&str] = &["name", "description", "license", "compatibility", "metadata", "allowed-tools"];
+const CLAUDE_COMMAND_KEYS: &[&str] = &[
+    "description", "allowed-tools", "argument-hint", "model", "disable-model-invocation", "hooks", "name",
+];
+const OPENCODE_COMMAND_KEYS: &[&str] = &["description", "agent", "model", "subtask", "template"];
+
+/// OpenCode built-in tool ids, as used in an agent's `tools` map
+const OPENCODE_TOOLS: &[&str] = &[
+    "bash", "edit", "write", "read", "grep", "glob", "list", "patch", "multiedit", "todowrite", "todoread",
+    "webfetch", "websearch", "codesearch", "skill", "task", "lsp", "question",
+];
+
+/// Model aliases Claude Code accepts besides full `claude-*` model ids
+const CLAUDE_MODEL_ALIASES: &[&str] = &["inherit", "default", "sonnet", "opus", "haiku", "opusplan", "sonnet[1m]"];
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FrontmatterFinding {
+    pub id: String,
+    pub kind: String,  // "yaml_error", "unclosed_frontmatter", "not_a_mapping", "missing_key", "name_mismatch", "unknown_key", "unknown_tool", "unknown_model"
+    pub severity: Severity,
+    pub title: String,
+    pub description: String,
+    pub file: String,
+    pub entity_type: String,  // "agent", "skill", "command"
+    pub entity_id: String,
+    pub entity_name: String,
+    pub tool: String,
+    pub key: Option<String>,
+    pub line: Option<usize>,  // 1-based, in the file
+    pub column: Option<usize>,
+    pub plugin_name: Option<String>,
+}
+
+/// The entity being linted
+struct LintTarget<'a> {
+    entity_type: &'static str,
+    entity_id: &'a str,
+    name: &'a str,
+    file: &'a str,
+    tool: &'a str,
+    content: &'a str,
+    plugin_name: Option<&'a str>,
+    /// Directory a skill lives in, which its `name` must match
+    skill_dir: Option<&'a str>,
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+/// Split a tools string on commas / whitespace outside parentheses:
+/// `Bash(git add:*), Read Grep` -> ["Bash(git add:*)", "Read", "Grep"]
+fn split_tool_list(list: &str) -> Vec<String> {
+    let mut tools = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+    for c in list.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' | ' ' | '\t' if depth == 0 => {
+                if !current.is_empty() {
+                    tools.push(std::mem::take(&mut current));
+                }
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    if !current.is_empty() {
+        tools.push(current);
+    }
+    tools
+}
+
+/// Tool names from a Claude `tools` / `allowed-tools` value (string or list)
+fn tool_names(value: &serde_yaml::Value) -> Vec<String> {
+    match value {
+        serde_yaml::Value::String(s) => split_tool_list(s),
+        serde_yaml::Value::Sequence(items) => items.iter()
+            .filter_map(|i| i.as_str())
+            .flat_map(split_tool_list)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// How Claude Code spells a server in `mcp__<server>__<tool>`
+fn normalize_server_name(name: &str) -> String {
+    name.chars().map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '-' { c } else { '_' }).collect()
+}
+
+/// Why a Claude tool name won't resolve, if it doesn't
+fn check_claude_tool(entry: &str, mcp_servers: &HashSet<String>) -> Option<String> {
+    let name = entry.split('(').next().unwrap_or(entry).trim();
+    if let Some(rest) = name.strip_prefix("mcp__") {
+        let server = rest.split("__").next().unwrap_or(rest);
+        if mcp_servers.contains(server) {
+            return None;
+        }
+        return Some(format!("No configured MCP server is named \"{}\"", server));
+    }
+    if KNOWN_TOOLS.contains(&name) {
+        return None;
+    }
+    match KNOWN_TOOLS.iter().find(|t| t.eq_ignore_ascii_case(name)) {
+        Some(known) => Some(format!("Tool names are case-sensitive; did you mean \"{}\"?", known)),
+        None => Some(format!("\"{}\" is not a Claude Code tool", name)),
+    }
+}
+
+/// Why an OpenCode `tools` key won't resolve, if it doesn't
+fn check_opencode_tool(name: &str, mcp_servers: &HashSet<String>) -> Option<String> {
+    if name.contains('*') || OPENCODE_TOOLS.contains(&name) {
+        return None;
+    }
+    // MCP tools are exposed as <server>_<tool>
+    if mcp_servers.iter().any(|s| name.strip_prefix(s.as_str()).is_some_and(|rest| rest.starts_with('_'))) {
+        return None;
+    }
+    Some(format!("\"{}\" is not an OpenCode tool or a tool of a configured MCP server", name))
+}
+
+fn check_model(tool: &str, model: &str) -> Option<String> {
+    if tool == "opencode" {
+        return (!model.contains('/')).then(|| format!("OpenCode models are written provider/model, e.g. anthropic/{}", model));
+    }
+    if CLAUDE_MODEL_ALIASES.contains(&model) || model.starts_with("claude-") {
+        return None;
+    }
+    Some(format!("\"{}\" is not a model alias ({}) or a claude-* model id", model, CLAUDE_MODEL_ALIASES.join(", ")))
+}
+
+// ============================================================================
+// Linting
+// ============================================================================
+
+fn lint_target(target: &LintTarget, mcp_servers: &HashSet<String>) -> Vec<FrontmatterFinding> {
+    let mut findings = Vec::new();
+    let mut report = |kind: &str, severity: Severity, title: String, description: String, key: Option<&str>, line: Option<usize>, column: Option<usize>| {
+        findings.push(FrontmatterFinding {
+            id: generate_id("frontmatter", &format!("{}#{}#{}#{}", target.file, kind, key.unwrap_or_default(), description)),
+            kind: kind.to_string(),
+            severity,
+            title,
+            description,
+            file: target.file.to_string(),
+            entity_type: target.entity_type.to_string(),
+            entity_id: target.entity_id.to_string(),
+            entity_name: target.name.to_string(),
+            tool: target.tool.to_string(),
+            key: key.map(String::from),
+            line,
+            column,
+            plugin_name: target.plugin_name.map(String::from),
+        });
+    };
+    let label = format!("{} \"{}\"", capitalize(target.entity_type), target.name);
+
+    let fm = match frontmatter::split(target.content) {
+        Ok(fm) => fm,
+        Err(_) => {
+            report(
+                "unclosed_frontmatter", Severity::Error,
+                format!("{} has unclosed frontmatter", label),
+                "The opening --- has no closing --- or ..., so the whole file is read as frontmatter".to_string(),
+                None, Some(1), None,
+            );
+            return findings;
+        }
+    };
+    let yaml = fm.as_ref().map_or("", |fm| fm.yaml_str());
+    let key_line = |key: &str| fm.as_ref().and_then(|fm| fm.key_line(key));
+
+    let map = if yaml.trim().is_empty() {
+        serde_yaml::Mapping::new()
+    } else {
+        match serde_yaml::from_str::<serde_yaml::Value>(yaml) {
+            Ok(serde_yaml::Value::Mapping(map)) => map,
+            Ok(serde_yaml::Value::Null) => serde_yaml::Mapping::new(),
+            Ok(_) => {
+                report(
+                    "not_a_mapping", Severity::Error,
+                    format!("{} frontmatter is not a set of keys", label),
+                    "Frontmatter must be YAML key: value pairs".to_string(),
+                    None, Some(2), None,
+                );
+                return findings;
+            }
+            Err(e) => {
+                let location = e.location();
+                let message = e.to_string();
+                // serde_yaml appends " at line N column M"; the location is reported separately
+                let message = message.split(" at line ").next().unwrap_or(&message).to_string();
+                report(
+                    "yaml_error", Severity::Error,
+                    format!("{} has invalid YAML frontmatter", label),
+                    format!("{}; the file's metadata is ignored until this is fixed", message),
+                    None,
+                    location.as_ref().zip(fm.as_ref()).map(|(l, fm)| fm.file_line(l.line())),
+                    location.as_ref().map(|l| l.column()),
+                );
+                return findings;
+            }
+        }
+    };
+    let get = |key: &str| map.get(serde_yaml::Value::String(key.to_string()));
+    let opencode = target.tool == "opencode";
+
+    // Required keys
+    let required: &[&str] = match (target.entity_type, opencode) {
+        ("agent", false) | ("skill", _) => &["name", "description"],
+        ("agent", true) => &["description"],
+        _ => &[],
+    };
+    for key in required {
+        let present = get(key).is_some_and(|v| v.as_str().map_or(!v.is_null(), |s| !s.trim().is_empty()));
+        if !present {
+            report(
+                "missing_key", Severity::Warning,
+                format!("{} has no {}", label, key),
+                format!("`{}` is required in {} frontmatter", key, target.entity_type),
+                Some(key), None, None,
+            );
+        }
+    }
+
+    // A skill is looked up by its directory; a different `name` is confusing at best
+    if let (Some(dir), Some(name)) = (target.skill_dir, get("name").and_then(|v| v.as_str())) {
+        let dir_name = Path::new(dir).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        if !dir_name.is_empty() && name != dir_name {
+            report(
+                "name_mismatch", Severity::Warning,
+                format!("Skill name \"{}\" doesn't match its directory", name),
+                format!("The skill lives in {}/ but its frontmatter says name: {}", dir_name, name),
+                Some("name"), key_line("name"), None,
+            );
+        }
+    }
+
+    // Unknown keys (likely typos)
+    let known: &[&str] = match (target.entity_type, opencode) {
+        ("agent", false) => CLAUDE_AGENT_KEYS,
+        ("agent", true) => OPENCODE_AGENT_KEYS,
+        ("skill", false) => CLAUDE_SKILL_KEYS,
+        ("skill", true) => OPENCODE_SKILL_KEYS,
+        ("command", false) => CLAUDE_COMMAND_KEYS,
+        _ => OPENCODE_COMMAND_KEYS,
+    };
+    for key in map.keys().filter_map(|k| k.as_str()) {
+        if !known.contains(&key) {
+            let hint = known.iter().find(|k| k.eq_ignore_ascii_case(key) || k.replace('-', "") == key.replace(['-', '_'], ""));
+            report(
+                "unknown_key", Severity::Info,
+                format!("{} has unknown key \"{}\"", label, key),
+                match hint {
+                    Some(k) => format!("Did you mean `{}`? Unknown keys are ignored", k),
+                    None => format!("{} doesn't read `{}` in {} frontmatter", if opencode { "OpenCode" } else { "Claude Code" }, key, target.entity_type),
+                },
+                Some(key), key_line(key), None,
+            );
+        }
+    }
+
+    // Tools
+    let tool_key = if target.entity_type == "agent" { "tools" } else { "allowed-tools" };
+    if let Some(value) = get(tool_key) {
+        let problems: Vec<(String, String)> = if opencode {
+            value.as_mapping().into_iter().flatten()
+                .filter_map(|(k, _)| k.as_str())
+                .filter_map(|name| check_opencode_tool(name, mcp_servers).map(|p| (name.to_string(), p)))
+                .collect()
+        } else {
+            tool_names(value).into_iter().filter_map(|name| check_claude_tool(&name, mcp_servers).map(|p| (name, p))).collect()
+        };
+        for (name, problem) in problems {
+            report(
+                "unknown_tool", Severity::Warning,
+                format!("{} lists unknown tool \"{}\"", label, name),
+                problem,
+                Some(tool_key), key_line(tool_key), None,
+            );
+        }
+    }
+
+    // Model
+    if let Some(model) = get("model").and_then(|v| v.as_str()) {
+        if let Some(problem) = check_model(target.tool, model) {
+            report(
+                "unknown_model", Severity::Warning,
+                format!("{} uses an unknown model \"{}\"", label, model),
+                problem,
+                Some("model"), key_line("model"), None,
+            );
+        }
+    }
+
+    findings
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    chars.next().map(|c| c.to_uppercase().chain(chars).collect()).unwrap_or_default()
+}
+
+/// Lint the frontmatter of every agent, skill and command. `tools` entries
+/// are checked against the MCP servers configured for the same tool.
+pub(crate) fn lint_frontmatter_internal(
+    agents: &[AgentEntity],
+    skills: &[SkillEntity],
+    commands: &[CommandEntity],
+    mcp_servers: &[McpServerEntity],
+) -> Vec<FrontmatterFinding> {
+    let servers_for = |tool: &str| -> HashSet<String> {
+        mcp_servers.iter()
+            .filter(|s| s.tool == tool)
+            .flat_map(|s| {
+                let name = normalize_server_name(&s.name);
+                // Plugin servers are exposed as plugin_<plugin>_<server>
+                let plugin = s.plugin_name.as_ref().map(|p| format!("plugin_{}_{}", normalize_server_name(p), name));
+                std::iter::once(name).chain(plugin)
+            })
+            .collect()
+    };
+    let claude_servers = servers_for("claude");
+    let opencode_servers = servers_for("opencode");
+
+    // Inline opencode.json definitions have no frontmatter to lint
+    let targets = agents.iter().filter(|a| a.json_pointer.is_none()).map(|a| (&a.base, "agent", None))
+        .chain(skills.iter().map(|s| (&s.base, "skill", Some(s.skill_dir.as_str()))))
+        .chain(commands.iter().filter(|c| c.json_pointer.is_none()).map(|c| (&c.base, "command", None)));
+
+    let mut findings = Vec::new();
+    for (base, entity_type, skill_dir) in targets {
+        let Some(content) = base.content.as_deref() else { continue };
+        let target = LintTarget {
+            entity_type,
+            entity_id: &base.id,
+            name: &base.name,
+            file: &base.path,
+            tool: &base.tool,
+            content,
+            plugin_name: base.plugin_name.as_deref(),
+            skill_dir,
+        };
+        let servers = if base.tool == "opencode" { &opencode_servers } else { &claude_servers };
+        findings.extend(lint_target(&target, servers));
+    }
+    findings
+}
+
+// ============================================================================
+// Public Tauri Commands
+// ============================================================================
+
+/// Lint the frontmatter of the given agents, skills and commands
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn lint_frontmatter(
+    agents: Vec<AgentEntity>,
+    skills: Vec<SkillEntity>,
+    commands: Vec<CommandEntity>,
+    mcp_servers: Vec<McpServerEntity>,
+) -> Result<Vec<FrontmatterFinding>, String> {
+    Ok(lint_frontmatter_internal(&agents, &skills, &commands, &mcp_servers))
+}
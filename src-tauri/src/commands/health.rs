@@ -0,0 +1,557 @@
+// ============================================================================
+// Agent Studio - Health Checks
+// Registry of checks run over a discovery result, with machine-applicable
+// fixes and per-project suppression
+// ============================================================================
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::fixes::FixOp;
+use super::{discover_all, generate_id, parse_json_file, permissions, ConfigStateType, DiscoveryResult, Severity};
+
+/// Per-project config file, at the project root
+pub const PROJECT_CONFIG_FILE: &str = ".agent-studio.json";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// A fix the backend can apply without user input, previewed with preview_fix
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HealthFix {
+    pub description: String,
+    pub ops: Vec<FixOp>,
+}
+
+/// One problem found by a check (mirrors HealthIssue on the frontend)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthIssue {
+    pub id: String,
+    pub check_id: String,  // Registry id plus kind, e.g. "hooks.recursive_delete"
+    pub severity: Severity,
+    pub category: String,
+    pub title: String,
+    pub description: String,
+    pub path: Option<String>,
+    pub entity_type: Option<String>,
+    pub entity_id: Option<String>,
+    pub project_path: Option<String>,
+    pub suggestion: Option<String>,
+    pub fix: Option<HealthFix>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthReport {
+    pub issues: Vec<HealthIssue>,
+    pub suppressed: Vec<HealthIssue>,
+    pub config_errors: Vec<String>,  // Unreadable .agent-studio.json files
+}
+
+/// Registry entry, as listed to the frontend
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HealthCheckInfo {
+    pub id: String,
+    pub category: String,
+    pub description: String,
+}
+
+struct HealthContext<'a> {
+    discovery: &'a DiscoveryResult,
+    projects: Vec<String>,
+}
+
+struct HealthCheck {
+    id: &'static str,
+    category: &'static str,
+    description: &'static str,
+    run: fn(&HealthContext) -> Vec<HealthIssue>,
+}
+
+/// Every check, in report order
+const CHECKS: &[HealthCheck] = &[
+    HealthCheck { id: "symlinks", category: "Broken Symlinks", description: "Symlinked entities whose target is missing", run: check_symlinks },
+    HealthCheck { id: "duplicates", category: "Duplicates", description: "Agents, skills and commands that share a name", run: check_duplicates },
+    HealthCheck { id: "agents", category: "Security", description: "Agents that bypass permissions", run: check_agents },
+    HealthCheck { id: "commands", category: "Best Practices", description: "Commands without a description", run: check_commands },
+    HealthCheck { id: "frontmatter", category: "Frontmatter", description: "Invalid YAML, missing or unknown keys, tools and models in agents, skills and commands", run: check_frontmatter },
+    HealthCheck { id: "plugins", category: "Plugins", description: "Plugins whose contents couldn't be read", run: check_plugins },
+    HealthCheck { id: "settings", category: "Empty Files", description: "Settings files with no content", run: check_settings },
+    HealthCheck { id: "config", category: "Project Config", description: "CLAUDE.md not linked to AGENTS.md", run: check_project_config },
+    HealthCheck { id: "hooks", category: "Hook Security", description: "Hook commands that are risky or don't resolve", run: check_hooks },
+    HealthCheck { id: "mcp", category: "MCP Servers", description: "MCP server commands, placeholders and transports", run: check_mcp },
+    HealthCheck { id: "secrets", category: "Secrets", description: "Literal tokens in git-tracked MCP and settings files", run: check_secrets },
+    HealthCheck { id: "permissions", category: "Permissions", description: "Over-broad, shadowed or duplicate permission rules", run: check_permissions },
+];
+
+// ============================================================================
+// Checks
+// ============================================================================
+
+struct IssueFields<'a> {
+    check_id: String,
+    severity: Severity,
+    category: &'a str,
+    title: String,
+    description: String,
+    path: Option<&'a str>,
+    entity: Option<(&'a str, &'a str)>,
+    project_path: Option<&'a str>,
+    suggestion: Option<String>,
+    fix: Option<HealthFix>,
+}
+
+fn issue(fields: IssueFields) -> HealthIssue {
+    let key = format!(
+        "{}#{}#{}#{}",
+        fields.check_id,
+        fields.path.unwrap_or_default(),
+        fields.entity.map(|(_, id)| id).unwrap_or_default(),
+        fields.title
+    );
+    HealthIssue {
+        id: generate_id("health", &key),
+        check_id: fields.check_id,
+        severity: fields.severity,
+        category: fields.category.to_string(),
+        title: fields.title,
+        description: fields.description,
+        path: fields.path.map(String::from),
+        entity_type: fields.entity.map(|(kind, _)| kind.to_string()),
+        entity_id: fields.entity.map(|(_, id)| id.to_string()),
+        project_path: fields.project_path.map(String::from),
+        suggestion: fields.suggestion,
+        fix: fields.fix,
+    }
+}
+
+fn has_description(frontmatter: &Option<std::collections::HashMap<String, serde_json::Value>>) -> bool {
+    frontmatter.as_ref()
+        .and_then(|f| f.get("description"))
+        .is_some_and(|d| d.as_str().map_or(!d.is_null(), |s| !s.trim().is_empty()))
+}
+
+fn check_symlinks(ctx: &HealthContext) -> Vec<HealthIssue> {
+    ctx.discovery.symlinks.iter().filter(|s| !s.target_exists).map(|s| issue(IssueFields {
+        check_id: "symlinks.broken".to_string(),
+        severity: Severity::Error,
+        category: "Broken Symlinks",
+        title: "Broken symlink detected".to_string(),
+        description: "Symlink target does not exist".to_string(),
+        path: Some(&s.path),
+        entity: s.entity_type.as_deref().zip(s.entity_id.as_deref()),
+        project_path: None,
+        suggestion: Some(format!("Remove the symlink or restore the target at: {}", s.target)),
+        fix: Some(HealthFix {
+            description: format!("Remove the dangling symlink {}", s.path),
+            ops: vec![FixOp::DeleteLink { path: s.path.clone() }],
+        }),
+    })).collect()
+}
+
+fn check_duplicates(ctx: &HealthContext) -> Vec<HealthIssue> {
+    ctx.discovery.duplicates.iter().map(|dup| issue(IssueFields {
+        check_id: format!("duplicates.{}", dup.entity_type),
+        severity: Severity::Warning,
+        category: "Duplicates",
+        title: format!("Duplicate {}: {}", dup.entity_type, dup.name),
+        description: format!("Found {} definitions with the same name", dup.entities.len()),
+        path: dup.entities.first().map(|e| e.path.as_str()),
+        entity: None,
+        project_path: None,
+        suggestion: Some("Only one definition will be active. Consider removing duplicates or renaming.".to_string()),
+        fix: None,
+    })).collect()
+}
+
+fn check_agents(ctx: &HealthContext) -> Vec<HealthIssue> {
+    let mut issues = Vec::new();
+    for agent in &ctx.discovery.agents {
+        let base = &agent.base;
+        let mode = agent.frontmatter.as_ref().and_then(|f| f.get("permissionMode")).and_then(|m| m.as_str());
+        if mode == Some("bypassPermissions") {
+            issues.push(issue(IssueFields {
+                check_id: "agents.bypass_permissions".to_string(),
+                severity: Severity::Warning,
+                category: "Security",
+                title: format!("Agent \"{}\" bypasses permissions", base.name),
+                description: "This agent runs with elevated permissions which could be risky".to_string(),
+                path: Some(&base.path),
+                entity: Some(("agent", &base.id)),
+                project_path: base.project_path.as_deref(),
+                suggestion: Some("Consider using \"acceptEdits\" or \"default\" permission mode unless bypass is necessary".to_string()),
+                fix: None,
+            }));
+        }
+    }
+    issues
+}
+
+fn check_commands(ctx: &HealthContext) -> Vec<HealthIssue> {
+    // Unparseable frontmatter is already reported by the frontmatter check
+    let broken: std::collections::HashSet<&str> = ctx.discovery.frontmatter_findings.iter()
+        .filter(|f| f.severity == Severity::Error)
+        .map(|f| f.entity_id.as_str())
+        .collect();
+    ctx.discovery.commands.iter()
+        .filter(|c| !has_description(&c.frontmatter) && !broken.contains(c.base.id.as_str()))
+        .map(|cmd| issue(IssueFields {
+            check_id: "commands.missing_description".to_string(),
+            severity: Severity::Info,
+            category: "Best Practices",
+            title: format!("Command \"{}\" has no description", cmd.base.name),
+            description: "Adding a description helps users understand what the command does".to_string(),
+            path: Some(&cmd.base.path),
+            entity: Some(("command", &cmd.base.id)),
+            project_path: cmd.base.project_path.as_deref(),
+            suggestion: Some("Add a description field to the YAML frontmatter".to_string()),
+            fix: None,
+        }))
+        .collect()
+}
+
+fn check_frontmatter(ctx: &HealthContext) -> Vec<HealthIssue> {
+    ctx.discovery.frontmatter_findings.iter().map(|finding| {
+        let location = match (finding.line, finding.column) {
+            (Some(line), Some(column)) => format!(" (line {}, column {})", line, column),
+            (Some(line), None) => format!(" (line {})", line),
+            _ => String::new(),
+        };
+        let suggestion = match finding.kind.as_str() {
+            "yaml_error" | "unclosed_frontmatter" | "not_a_mapping" => "Fix the YAML between the --- lines".to_string(),
+            "missing_key" => format!("Add {} to the YAML frontmatter", finding.key.as_deref().unwrap_or("the key")),
+            "name_mismatch" => "Rename the skill's directory or its name so they match".to_string(),
+            "unknown_key" => "Remove the key or correct its spelling".to_string(),
+            _ => format!("Check the {} value", finding.key.as_deref().unwrap_or("frontmatter")),
+        };
+        // A skill's name comes from its directory, an agent's from its file name
+        let file = Path::new(&finding.file);
+        let expected_name = match finding.entity_type.as_str() {
+            "skill" => file.parent().and_then(|d| d.file_name()),
+            _ => file.file_stem(),
+        }.map(|n| n.to_string_lossy().to_string());
+        // Plugin files are owned by the plugin and get overwritten on update
+        let expected_name = expected_name.filter(|_| finding.plugin_name.is_none());
+        let fix = match (finding.kind.as_str(), finding.key.as_deref(), expected_name) {
+            ("name_mismatch", _, Some(name)) | ("missing_key", Some("name"), Some(name)) => Some(HealthFix {
+                description: format!("Set name: {} in {}", name, finding.file),
+                ops: vec![FixOp::SetFrontmatterKey {
+                    path: finding.file.clone(),
+                    key: "name".to_string(),
+                    value: Some(serde_json::Value::String(name)),
+                }],
+            }),
+            _ => None,
+        };
+        let project_path = ctx.discovery.agents.iter().map(|a| &a.base)
+            .chain(ctx.discovery.skills.iter().map(|s| &s.base))
+            .chain(ctx.discovery.commands.iter().map(|c| &c.base))
+            .find(|b| b.id == finding.entity_id)
+            .and_then(|b| b.project_path.as_deref());
+        issue(IssueFields {
+            check_id: format!("frontmatter.{}", finding.kind),
+            severity: finding.severity,
+            category: "Frontmatter",
+            title: finding.title.clone(),
+            description: format!("{}{}", finding.description, location),
+            path: Some(&finding.file),
+            entity: Some((&finding.entity_type, &finding.entity_id)),
+            project_path,
+            suggestion: Some(suggestion),
+            fix,
+        })
+    }).collect()
+}
+
+fn check_plugins(ctx: &HealthContext) -> Vec<HealthIssue> {
+    ctx.discovery.plugins.iter()
+        .filter_map(|plugin| plugin.load_error.as_ref().map(|e| (plugin, e)))
+        .map(|(plugin, e)| issue(IssueFields {
+            check_id: "plugins.unreadable".to_string(),
+            severity: Severity::Warning,
+            category: "Plugins",
+            title: format!("Couldn't read plugin {}", plugin.base.name),
+            description: e.clone(),
+            path: Some(&plugin.plugin_dir),
+            entity: Some(("plugin", &plugin.base.id)),
+            project_path: plugin.base.project_path.as_deref(),
+            suggestion: Some("Check the plugin directory's permissions, or reinstall the plugin".to_string()),
+            fix: None,
+        }))
+        .collect()
+}
+
+fn check_settings(ctx: &HealthContext) -> Vec<HealthIssue> {
+    ctx.discovery.settings.iter()
+        .filter(|s| s.base.content.as_deref().is_none_or(|c| c.trim().len() < 10))
+        .map(|setting| issue(IssueFields {
+            check_id: "settings.empty".to_string(),
+            severity: Severity::Info,
+            category: "Empty Files",
+            title: "Settings file appears empty".to_string(),
+            description: format!("{} has minimal or no content", setting.base.path),
+            path: Some(&setting.base.path),
+            entity: Some(("settings", &setting.base.id)),
+            project_path: setting.base.project_path.as_deref(),
+            suggestion: Some("Add configuration or remove the file if not needed".to_string()),
+            fix: None,
+        }))
+        .collect()
+}
+
+fn check_project_config(ctx: &HealthContext) -> Vec<HealthIssue> {
+    let mut issues = Vec::new();
+    for project in &ctx.discovery.projects {
+        let Some(state) = &project.config_state else { continue };
+        let project = &project.path;
+        let (kind, severity, title, description) = match state.config_state {
+            ConfigStateType::MissingSymlink => ("missing_symlink", Severity::Info, "CLAUDE.md is missing", "AGENTS.md exists but Claude Code reads CLAUDE.md"),
+            ConfigStateType::NeedsMigration => ("needs_migration", Severity::Info, "CLAUDE.md is not shared with AGENTS.md", "Other agents read AGENTS.md, which doesn't exist"),
+            ConfigStateType::Conflict => ("conflict", Severity::Warning, "AGENTS.md and CLAUDE.md have diverged", "Both files have their own content"),
+            ConfigStateType::Correct | ConfigStateType::Empty => continue,
+        };
+        let claude_md = Path::new(project).join("CLAUDE.md").to_string_lossy().to_string();
+        let link = FixOp::CreateSymlink { path: claude_md.clone(), target: "AGENTS.md".to_string() };
+        let fix = match state.config_state {
+            ConfigStateType::MissingSymlink => Some(HealthFix {
+                description: "Create CLAUDE.md as a symlink to AGENTS.md".to_string(),
+                ops: vec![link],
+            }),
+            ConfigStateType::NeedsMigration => Some(HealthFix {
+                description: "Move CLAUDE.md to AGENTS.md and link CLAUDE.md to it".to_string(),
+                ops: vec![
+                    FixOp::Rename { from: claude_md, to: Path::new(project).join("AGENTS.md").to_string_lossy().to_string() },
+                    link,
+                ],
+            }),
+            _ => None,
+        };
+        issues.push(issue(IssueFields {
+            check_id: format!("config.{}", kind),
+            severity,
+            category: "Project Config",
+            title: title.to_string(),
+            description: description.to_string(),
+            path: Some(project),
+            entity: None,
+            project_path: Some(project),
+            suggestion: Some(if fix.is_some() {
+                "Link CLAUDE.md to AGENTS.md so every agent reads the same instructions".to_string()
+            } else {
+                "Merge the two files into AGENTS.md, then link CLAUDE.md to it".to_string()
+            }),
+            fix,
+        }));
+    }
+    issues
+}
+
+fn check_hooks(ctx: &HealthContext) -> Vec<HealthIssue> {
+    ctx.discovery.hook_findings.iter().map(|finding| {
+        let origin = match &finding.plugin_name {
+            Some(plugin) => format!("plugin \"{}\"", plugin),
+            None => format!("{} settings", finding.source),
+        };
+        let matcher = finding.matcher.as_deref().map(|m| format!(" [{}]", m)).unwrap_or_default();
+        issue(IssueFields {
+            check_id: format!("hooks.{}", finding.kind),
+            severity: finding.severity,
+            category: "Hook Security",
+            title: finding.title.clone(),
+            description: format!("{} ({}{} hook in {})", finding.description, finding.event, matcher, origin),
+            path: Some(&finding.file),
+            entity: Some(("hook", &finding.hook_id)),
+            project_path: None,
+            suggestion: Some(format!("Review the hook command: {}", finding.command)),
+            fix: None,
+        })
+    }).collect()
+}
+
+fn check_mcp(ctx: &HealthContext) -> Vec<HealthIssue> {
+    ctx.discovery.mcp_findings.iter().map(|finding| {
+        let origin = match &finding.plugin_name {
+            Some(plugin) => format!("plugin \"{}\"", plugin),
+            None => format!("{} {} config", finding.scope, finding.tool),
+        };
+        let suggestion = if finding.kind == "unset_env_var" {
+            Some("Export the variable in your shell or add it to settings.json \"env\"".to_string())
+        } else {
+            finding.field.as_ref().map(|f| format!("Check the server's {}", f))
+        };
+        issue(IssueFields {
+            check_id: format!("mcp.{}", finding.kind),
+            severity: finding.severity,
+            category: "MCP Servers",
+            title: format!("{}: {}", finding.server_name, finding.title),
+            description: format!("{} ({})", finding.description, origin),
+            path: Some(&finding.file),
+            entity: Some(("mcp", &finding.server_id)),
+            project_path: None,
+            suggestion,
+            fix: None,
+        })
+    }).collect()
+}
+
+fn check_secrets(ctx: &HealthContext) -> Vec<HealthIssue> {
+    ctx.discovery.secret_findings.iter().map(|finding| {
+        let (target, reference) = if finding.tool == "opencode" {
+            (".env", format!("{{env:{}}}", finding.suggested_var))
+        } else {
+            (".claude/settings.local.json", format!("${{{}}}", finding.suggested_var))
+        };
+        let suggestion = if finding.source == "settings" {
+            "Move the variable to .claude/settings.local.json".to_string()
+        } else {
+            format!("Move the value to {} and reference it as {}", target, reference)
+        };
+        issue(IssueFields {
+            check_id: format!("secrets.{}", finding.kind),
+            severity: finding.severity,
+            category: "Secrets",
+            title: format!("{} ({})", finding.title, finding.preview),
+            description: finding.description.clone(),
+            path: Some(&finding.file),
+            entity: Some((if finding.source == "mcp" { "mcp" } else { "settings" }, &finding.entity_id)),
+            project_path: finding.project_path.as_deref(),
+            fix: finding.fixable.then(|| HealthFix {
+                description: suggestion.clone(),
+                ops: vec![FixOp::ExtractSecret { finding: Box::new(finding.clone()), var_name: None }],
+            }),
+            suggestion: Some(suggestion),
+        })
+    }).collect()
+}
+
+fn check_permissions(ctx: &HealthContext) -> Vec<HealthIssue> {
+    // Each project's audit layers its settings over the global ones; keep global findings once
+    let mut seen = std::collections::HashSet::new();
+    let mut issues = Vec::new();
+    let projects: Vec<Option<&String>> = if ctx.projects.is_empty() { vec![None] } else { ctx.projects.iter().map(Some).collect() };
+    for project in projects {
+        let Ok(findings) = permissions::audit_permissions(project.cloned()) else { continue };
+        for finding in findings.into_iter().filter(|f| seen.insert(f.id.clone())) {
+            let project_path = (finding.layer != "global").then_some(project).flatten();
+            issues.push(issue(IssueFields {
+                check_id: format!("permissions.{}", finding.kind),
+                severity: finding.severity,
+                category: "Permissions",
+                title: finding.title.clone(),
+                description: finding.description.clone(),
+                path: Some(&finding.file),
+                entity: None,
+                project_path: project_path.map(String::as_str),
+                suggestion: finding.fix.as_ref().map(|f| f.description.clone()),
+                fix: finding.fix.as_ref().map(|f| HealthFix {
+                    description: f.description.clone(),
+                    ops: vec![FixOp::JsonPatch { file: f.file.clone(), ops: f.ops.clone() }],
+                }),
+            }));
+        }
+    }
+    issues
+}
+
+// ============================================================================
+// Suppression
+// ============================================================================
+
+/// A `health.suppress` entry: a check id (or prefix such as "hooks"),
+/// optionally limited to one file relative to the project
+struct Suppression {
+    check: String,
+    path: Option<PathBuf>,
+}
+
+/// Read `health.suppress` from a project's .agent-studio.json:
+/// `["commands.missing_description", { "check": "secrets", "path": "fixtures/.mcp.json" }]`
+fn load_suppressions(project: &Path) -> Result<Vec<Suppression>, String> {
+    let file = project.join(PROJECT_CONFIG_FILE);
+    if !file.exists() {
+        return Ok(Vec::new());
+    }
+    let config = parse_json_file(&file).ok_or_else(|| format!("Could not parse {}", file.display()))?;
+    let entries = config.pointer("/health/suppress").and_then(|s| s.as_array()).cloned().unwrap_or_default();
+    entries.iter().map(|entry| match entry {
+        serde_json::Value::String(check) => Ok(Suppression { check: check.clone(), path: None }),
+        serde_json::Value::Object(obj) => {
+            let check = obj.get("check").and_then(|c| c.as_str())
+                .ok_or_else(|| format!("{}: suppress entries need a \"check\"", file.display()))?;
+            let path = obj.get("path").and_then(|p| p.as_str()).map(|p| project.join(p));
+            Ok(Suppression { check: check.to_string(), path })
+        }
+        _ => Err(format!("{}: suppress entries must be strings or objects", file.display())),
+    }).collect()
+}
+
+fn check_matches(pattern: &str, check_id: &str) -> bool {
+    check_id == pattern || check_id.strip_prefix(pattern).is_some_and(|rest| rest.starts_with('.'))
+}
+
+/// The project an issue belongs to, for suppression
+fn issue_project<'a>(issue: &HealthIssue, projects: &'a [String]) -> Option<&'a String> {
+    projects.iter().find(|p| {
+        issue.project_path.as_deref() == Some(p.as_str())
+            || issue.path.as_deref().is_some_and(|path| Path::new(path).starts_with(p))
+    })
+}
+
+// ============================================================================
+// Entry Points
+// ============================================================================
+
+/// Run every registered check over a discovery result and apply project suppressions
+pub(crate) fn run_health_checks_internal(discovery: &DiscoveryResult) -> HealthReport {
+    let projects: Vec<String> = discovery.projects.iter().map(|p| p.path.clone()).collect();
+    let ctx = HealthContext { discovery, projects };
+    let projects = &ctx.projects;
+    let mut report = HealthReport::default();
+
+    let mut suppressions = std::collections::HashMap::new();
+    for project in projects {
+        match load_suppressions(Path::new(project)) {
+            Ok(list) => {
+                suppressions.insert(project.as_str(), list);
+            }
+            Err(e) => report.config_errors.push(e),
+        }
+    }
+
+    for check in CHECKS {
+        for issue in (check.run)(&ctx) {
+            let suppressed = issue_project(&issue, projects)
+                .and_then(|p| suppressions.get(p.as_str()))
+                .is_some_and(|list| list.iter().any(|s| {
+                    check_matches(&s.check, &issue.check_id)
+                        && s.path.as_ref().is_none_or(|p| issue.path.as_deref().map(Path::new) == Some(p.as_path()))
+                }));
+            if suppressed {
+                report.suppressed.push(issue);
+            } else {
+                report.issues.push(issue);
+            }
+        }
+    }
+    report.issues.sort_by_key(|i| i.severity);
+    report
+}
+
+/// Discover everything for the given projects and run the health checks
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn run_health_checks(project_paths: Option<Vec<String>>) -> Result<HealthReport, String> {
+    let result = discover_all(project_paths)?;
+    Ok(result.health)
+}
+
+/// The registered checks, for listing and for writing suppressions
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn list_health_checks() -> Vec<HealthCheckInfo> {
+    CHECKS.iter().map(|c| HealthCheckInfo {
+        id: c.id.to_string(),
+        category: c.category.to_string(),
+        description: c.description.to_string(),
+    }).collect()
+}
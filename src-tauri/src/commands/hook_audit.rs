@@ -0,0 +1,587 @@
+// ============================================================================
+// Agent Studio - Hook Security Audit
+// Static analysis of hook commands from settings files and plugin hooks.json
+// ============================================================================
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+use std::sync::LazyLock;
+
+use super::{generate_id, get_home_dir, HookEntity, PluginEntity, Severity};
+
+/// Commands that download content from the network
+const FETCH_COMMANDS: &[&str] = &["curl", "wget", "fetch", "http", "xh"];
+
+/// Commands that execute whatever they read on stdin or are given as a script
+const INTERPRETERS: &[&str] = &[
+    "sh", "bash", "zsh", "dash", "ksh", "fish", "python", "python3", "node", "ruby", "perl",
+    "php", "deno", "bun", "tsx", "ts-node",
+];
+
+/// Words that wrap another command, e.g. `sudo rm ...`
+const COMMAND_PREFIXES: &[&str] = &["sudo", "env", "exec", "command", "nohup", "time", "nice"];
+
+/// Shell builtins and keywords that never resolve through PATH
+const SHELL_BUILTINS: &[&str] = &[
+    ".", ":", "[", "[[", "!", "{", "}", "alias", "break", "case", "cd", "continue", "do", "done",
+    "echo", "elif", "else", "esac", "eval", "exit", "export", "false", "fi", "for", "function",
+    "if", "in", "local", "printf", "pwd", "read", "readonly", "return", "set", "shift", "source",
+    "test", "then", "trap", "true", "type", "ulimit", "umask", "unset", "until", "wait", "while",
+];
+
+/// Directories a GUI app's PATH often lacks but the user's shell (and Claude Code) has
+const EXTRA_BIN_DIRS: &[&str] = &["/usr/local/bin", "/opt/homebrew/bin", "/opt/homebrew/sbin"];
+const EXTRA_HOME_BIN_DIRS: &[&str] = &[".local/bin", ".cargo/bin", ".bun/bin", ".deno/bin", ".volta/bin"];
+
+/// Commands that write to the paths given as arguments
+const FILE_WRITE_COMMANDS: &[&str] = &["tee", "touch", "mkdir"];
+
+/// Commands whose last argument is the written destination
+const FILE_COPY_COMMANDS: &[&str] = &["cp", "mv", "ln", "install", "rsync"];
+
+/// A download run through substitution: bash -c "$(curl ...)", source <(curl ...)
+static DOWNLOAD_SUBSTITUTION: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?:\$\(|<\(|`)\s*(?:curl|wget)\b").unwrap());
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HookFinding {
+    pub id: String,
+    pub kind: String,  // "remote_code_execution", "recursive_delete", "write_outside_project", "missing_script", "script_not_executable", "command_not_found"
+    pub severity: Severity,
+    pub title: String,
+    pub description: String,
+    pub file: String,
+    pub event: String,
+    pub matcher: Option<String>,
+    pub hook_id: String,
+    pub hook_index: usize,
+    pub command: String,
+    pub source: String,  // "global", "project", "local", "plugin"
+    pub plugin_name: Option<String>,
+}
+
+/// A piece of a shell command line
+#[derive(Debug, PartialEq)]
+enum Token {
+    Word(String),
+    /// Control operator (`|`, `&&`, `;`, ...) or redirection (`>`, `2>>`, ...)
+    Op(String),
+}
+
+/// One simple command within a command line
+#[derive(Default)]
+struct Segment {
+    words: Vec<String>,
+    /// (operator, target) pairs for output redirections
+    redirects: Vec<(String, String)>,
+    /// Whether stdin comes from the previous segment through `|`
+    piped: bool,
+}
+
+/// What path variables expand to for a given hook
+struct HookContext {
+    project_dir: Option<PathBuf>,
+    plugin_root: Option<PathBuf>,
+    home: Option<PathBuf>,
+}
+
+// ============================================================================
+// Shell Parsing
+// ============================================================================
+
+/// Split a command line into words and operators, honoring quotes and
+/// keeping `$(...)`, backtick and `<(...)` substitutions inside their word
+fn tokenize(command: &str) -> Vec<Token> {
+    let chars: Vec<char> = command.chars().collect();
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut i = 0;
+
+    let flush = |word: &mut String, in_word: &mut bool, tokens: &mut Vec<Token>| {
+        if *in_word {
+            tokens.push(Token::Word(std::mem::take(word)));
+            *in_word = false;
+        }
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\'' => {
+                in_word = true;
+                i += 1;
+                while i < chars.len() && chars[i] != '\'' {
+                    word.push(chars[i]);
+                    i += 1;
+                }
+            }
+            '"' => {
+                in_word = true;
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    if chars[i] == '\\' && i + 1 < chars.len() {
+                        i += 1;
+                    }
+                    word.push(chars[i]);
+                    i += 1;
+                }
+            }
+            '\\' => {
+                in_word = true;
+                if i + 1 < chars.len() && chars[i + 1] != '\n' {
+                    word.push(chars[i + 1]);
+                }
+                i += 1;
+            }
+            '$' | '<' if chars.get(i + 1) == Some(&'(') => {
+                // Command or process substitution: keep it verbatim in the word
+                in_word = true;
+                let mut depth = 0;
+                while i < chars.len() {
+                    word.push(chars[i]);
+                    match chars[i] {
+                        '(' => depth += 1,
+                        ')' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        _ => {}
+                    }
+                    i += 1;
+                }
+            }
+            '`' => {
+                in_word = true;
+                word.push(c);
+                i += 1;
+                while i < chars.len() && chars[i] != '`' {
+                    word.push(chars[i]);
+                    i += 1;
+                }
+                word.push('`');
+            }
+            '#' if !in_word => {
+                // Comment until end of line
+                while i + 1 < chars.len() && chars[i + 1] != '\n' {
+                    i += 1;
+                }
+            }
+            ' ' | '\t' => flush(&mut word, &mut in_word, &mut tokens),
+            '\n' | ';' => {
+                flush(&mut word, &mut in_word, &mut tokens);
+                tokens.push(Token::Op(";".to_string()));
+            }
+            '|' | '&' => {
+                let doubled = chars.get(i + 1) == Some(&c);
+                if c == '&' && chars.get(i + 1) == Some(&'>') {
+                    // `&>` / `&>>` redirect both streams
+                    flush(&mut word, &mut in_word, &mut tokens);
+                    let append = chars.get(i + 2) == Some(&'>');
+                    tokens.push(Token::Op(if append { "&>>" } else { "&>" }.to_string()));
+                    i += if append { 2 } else { 1 };
+                } else {
+                    flush(&mut word, &mut in_word, &mut tokens);
+                    let op = match (c, doubled) {
+                        ('|', true) => "||",
+                        ('|', false) => "|",
+                        ('&', true) => "&&",
+                        _ => "&",
+                    };
+                    tokens.push(Token::Op(op.to_string()));
+                    if doubled {
+                        i += 1;
+                    }
+                }
+            }
+            '>' | '<' => {
+                // A pending all-digit word is a file descriptor (`2>`)
+                let mut op = String::new();
+                if in_word && !word.is_empty() && word.chars().all(|d| d.is_ascii_digit()) {
+                    op = std::mem::take(&mut word);
+                    in_word = false;
+                } else {
+                    flush(&mut word, &mut in_word, &mut tokens);
+                }
+                op.push(c);
+                if chars.get(i + 1) == Some(&c) {
+                    op.push(c);
+                    i += 1;
+                }
+                if chars.get(i + 1) == Some(&'&') {
+                    // Descriptor duplication like `2>&1` has no file target
+                    op.push('&');
+                    i += 1;
+                    while i + 1 < chars.len() && (chars[i + 1].is_ascii_digit() || chars[i + 1] == '-') {
+                        op.push(chars[i + 1]);
+                        i += 1;
+                    }
+                }
+                tokens.push(Token::Op(op));
+            }
+            _ => {
+                in_word = true;
+                word.push(c);
+            }
+        }
+        i += 1;
+    }
+    flush(&mut word, &mut in_word, &mut tokens);
+    tokens
+}
+
+/// Group tokens into simple commands separated by control operators
+fn parse_segments(command: &str) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut current = Segment::default();
+    let mut tokens = tokenize(command).into_iter().peekable();
+
+    while let Some(token) = tokens.next() {
+        match token {
+            Token::Word(word) => current.words.push(word),
+            Token::Op(op) if matches!(op.as_str(), "|" | "||" | "&&" | "&" | ";") => {
+                let piped = op == "|";
+                if !current.words.is_empty() || !current.redirects.is_empty() {
+                    segments.push(std::mem::take(&mut current));
+                }
+                current.piped = piped;
+            }
+            Token::Op(op) => {
+                let is_output = (op.contains('>') && !op.contains('&')) || op.starts_with("&>");
+                if let Some(Token::Word(_)) = tokens.peek() {
+                    if let Some(Token::Word(target)) = tokens.next() {
+                        if is_output {
+                            current.redirects.push((op, target));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    if !current.words.is_empty() || !current.redirects.is_empty() {
+        segments.push(current);
+    }
+    segments
+}
+
+/// Skip variable assignments and wrappers like `sudo`/`env` to find the real command
+fn command_words(words: &[String]) -> &[String] {
+    let mut start = 0;
+    while start < words.len() {
+        let word = &words[start];
+        let is_assignment = word.split_once('=')
+            .is_some_and(|(name, _)| !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'));
+        let is_prefix_flag = word.starts_with('-') && start > 0 && COMMAND_PREFIXES.contains(&words[start - 1].as_str());
+        if is_assignment || is_prefix_flag || COMMAND_PREFIXES.contains(&word.as_str()) {
+            start += 1;
+        } else {
+            break;
+        }
+    }
+    &words[start..]
+}
+
+fn base_name(word: &str) -> &str {
+    word.rsplit('/').next().unwrap_or(word)
+}
+
+// ============================================================================
+// Path Resolution
+// ============================================================================
+
+impl HookContext {
+    fn for_hook(hook: &HookEntity, plugin_root: Option<&Path>) -> Self {
+        // Project and local settings live in <project>/.claude/
+        let project_dir = match hook.source.as_str() {
+            "project" | "local" => Path::new(&hook.source_path).parent().and_then(|p| p.parent()).map(PathBuf::from),
+            _ => None,
+        };
+        HookContext {
+            project_dir,
+            plugin_root: plugin_root.map(PathBuf::from),
+            home: get_home_dir(),
+        }
+    }
+
+    /// Expand the variables Claude Code provides and resolve relative paths
+    /// against the project directory; `None` if the path can't be known statically
+    fn resolve(&self, word: &str) -> Option<PathBuf> {
+        let mut expanded = word.to_string();
+        let vars: [(&str, &Option<PathBuf>); 3] = [
+            ("CLAUDE_PROJECT_DIR", &self.project_dir),
+            ("CLAUDE_PLUGIN_ROOT", &self.plugin_root),
+            ("HOME", &self.home),
+        ];
+        for (name, value) in vars {
+            for pattern in [format!("${{{}}}", name), format!("${}", name)] {
+                if expanded.contains(&pattern) {
+                    expanded = expanded.replace(&pattern, &value.as_ref()?.to_string_lossy());
+                }
+            }
+        }
+        if expanded == "~" || expanded.starts_with("~/") {
+            expanded = format!("{}{}", self.home.as_ref()?.to_string_lossy(), &expanded[1..]);
+        }
+        if expanded.is_empty() || expanded.contains(['$', '`', '*', '?']) {
+            return None;
+        }
+
+        let path = PathBuf::from(&expanded);
+        let path = if path.is_absolute() { path } else { self.project_dir.as_ref()?.join(path) };
+        Some(normalize(&path))
+    }
+
+    /// Whether a hook writing to `path` stays within places it is expected to touch
+    fn is_expected_write_location(&self, path: &Path) -> bool {
+        let mut roots: Vec<PathBuf> = vec![
+            PathBuf::from("/dev"),
+            PathBuf::from("/tmp"),
+            PathBuf::from("/var/tmp"),
+            PathBuf::from("/private/tmp"),
+            std::env::temp_dir(),
+        ];
+        roots.extend(self.project_dir.clone());
+        roots.extend(self.plugin_root.clone());
+        roots.extend(self.home.as_ref().map(|h| h.join(".claude")));
+        roots.iter().any(|root| path.starts_with(root))
+    }
+}
+
+/// Lexically resolve `.` and `..` components
+fn normalize(path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                result.pop();
+            }
+            Component::CurDir => {}
+            other => result.push(other.as_os_str()),
+        }
+    }
+    result
+}
+
+pub(crate) fn find_on_path(name: &str, home: Option<&Path>) -> bool {
+    let mut dirs: Vec<PathBuf> = std::env::var_os("PATH")
+        .map(|p| std::env::split_paths(&p).collect())
+        .unwrap_or_default();
+    dirs.extend(EXTRA_BIN_DIRS.iter().map(PathBuf::from));
+    if let Some(home) = home {
+        dirs.extend(EXTRA_HOME_BIN_DIRS.iter().map(|d| home.join(d)));
+    }
+    let candidates: Vec<String> = if cfg!(windows) {
+        ["", ".exe", ".cmd", ".bat"].iter().map(|ext| format!("{}{}", name, ext)).collect()
+    } else {
+        vec![name.to_string()]
+    };
+    dirs.iter().any(|dir| candidates.iter().any(|c| dir.join(c).is_file()))
+}
+
+#[cfg(unix)]
+pub(crate) fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata().map(|m| m.permissions().mode() & 0o111 != 0).unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+pub(crate) fn is_executable(_path: &Path) -> bool {
+    true
+}
+
+fn looks_like_script(word: &str) -> bool {
+    const SCRIPT_EXTENSIONS: &[&str] = &[".sh", ".bash", ".zsh", ".py", ".js", ".mjs", ".cjs", ".ts", ".rb", ".pl", ".php"];
+    !word.starts_with('-') && (word.contains('/') || SCRIPT_EXTENSIONS.iter().any(|ext| word.ends_with(ext)))
+}
+
+// ============================================================================
+// Analysis
+// ============================================================================
+
+/// (kind, severity, title, description) for each problem in a single command
+fn analyze_command(command: &str, ctx: &HookContext) -> Vec<(&'static str, Severity, String, String)> {
+    let mut issues = Vec::new();
+    let segments = parse_segments(command);
+
+    // Downloads piped straight into an interpreter
+    for pair in segments.windows(2) {
+        let (fetch, exec) = (command_words(&pair[0].words), command_words(&pair[1].words));
+        if let (Some(fetch), Some(exec)) = (fetch.first(), exec.first()) {
+            if pair[1].piped && FETCH_COMMANDS.contains(&base_name(fetch)) && INTERPRETERS.contains(&base_name(exec)) {
+                issues.push((
+                    "remote_code_execution",
+                    Severity::Error,
+                    format!("Downloads and runs code with {}", base_name(exec)),
+                    format!("`{}` output is piped into `{}`, so whatever the server returns runs on every trigger", base_name(fetch), base_name(exec)),
+                ));
+            }
+        }
+    }
+    // Downloads executed through substitution, e.g. bash -c "$(curl ...)" or source <(curl ...)
+    if DOWNLOAD_SUBSTITUTION.is_match(command) && !issues.iter().any(|i| i.0 == "remote_code_execution") {
+        let executes = segments.iter().any(|s| {
+            command_words(&s.words).first().is_some_and(|w| {
+                INTERPRETERS.contains(&base_name(w)) || matches!(w.as_str(), "eval" | "source" | ".")
+            })
+        });
+        if executes {
+            issues.push((
+                "remote_code_execution",
+                Severity::Error,
+                "Downloads and runs code".to_string(),
+                "A network download is executed through command substitution".to_string(),
+            ));
+        }
+    }
+
+    for segment in &segments {
+        let words = command_words(&segment.words);
+        let Some(program) = words.first() else {
+            check_writes(segment, &[], ctx, &mut issues);
+            continue;
+        };
+        let name = base_name(program);
+        let args = &words[1..];
+
+        // rm -rf
+        if name == "rm" {
+            let flags: Vec<&String> = args.iter().filter(|a| a.starts_with('-')).collect();
+            let has_short = |f: char| flags.iter().any(|a| !a.starts_with("--") && a.contains(f));
+            let recursive = has_short('r') || has_short('R') || flags.iter().any(|a| *a == "--recursive");
+            let force = has_short('f') || flags.iter().any(|a| *a == "--force");
+            if recursive && force {
+                let targets: Vec<&String> = args.iter().filter(|a| !a.starts_with('-')).collect();
+                let catastrophic = targets.iter().any(|t| {
+                    matches!(t.as_str(), "/" | "/*" | "~" | "~/" | "~/*" | "*" | "." | ".." | "$HOME" | "${HOME}" | "$HOME/" | "${HOME}/")
+                        || (t.starts_with('$') && t.ends_with('/'))
+                });
+                issues.push((
+                    "recursive_delete",
+                    if catastrophic { Severity::Error } else { Severity::Warning },
+                    "Recursively force-deletes files".to_string(),
+                    format!("`rm {}` runs without confirmation on every trigger", args.join(" ")),
+                ));
+            }
+        }
+
+        check_writes(segment, words, ctx, &mut issues);
+
+        // Does the program (or the script it runs) exist?
+        if program.contains(['$', '`']) && ctx.resolve(program).is_none() {
+            continue;
+        }
+        if program.contains('/') {
+            check_script(program, ctx, true, &mut issues);
+        } else if SHELL_BUILTINS.contains(&name) {
+            continue;
+        } else if !find_on_path(name, ctx.home.as_deref()) {
+            issues.push((
+                "command_not_found",
+                Severity::Warning,
+                format!("`{}` is not on PATH", name),
+                format!("The hook will fail with \"command not found\" unless `{}` is installed", name),
+            ));
+        } else if INTERPRETERS.contains(&name) && !args.iter().any(|a| a == "-c" || a == "-m" || a == "-e") {
+            if let Some(script) = args.iter().find(|a| !a.starts_with('-')) {
+                if looks_like_script(script) {
+                    check_script(script, ctx, false, &mut issues);
+                }
+            }
+        }
+    }
+
+    issues
+}
+
+fn check_script(word: &str, ctx: &HookContext, needs_exec: bool, issues: &mut Vec<(&'static str, Severity, String, String)>) {
+    let Some(path) = ctx.resolve(word) else { return };
+    if !path.exists() {
+        issues.push((
+            "missing_script",
+            Severity::Error,
+            format!("Script not found: {}", word),
+            format!("{} does not exist", path.display()),
+        ));
+    } else if needs_exec && path.is_file() && !is_executable(&path) {
+        issues.push((
+            "script_not_executable",
+            Severity::Error,
+            format!("Script is not executable: {}", word),
+            format!("{} lacks the executable bit; run `chmod +x` or invoke it through an interpreter", path.display()),
+        ));
+    }
+}
+
+fn check_writes(segment: &Segment, words: &[String], ctx: &HookContext, issues: &mut Vec<(&'static str, Severity, String, String)>) {
+    let mut targets: Vec<&String> = segment.redirects.iter().map(|(_, target)| target).collect();
+    if let Some(program) = words.first() {
+        let name = base_name(program);
+        let args: Vec<&String> = words[1..].iter().filter(|a| !a.starts_with('-')).collect();
+        if FILE_WRITE_COMMANDS.contains(&name) {
+            targets.extend(args);
+        } else if FILE_COPY_COMMANDS.contains(&name) && args.len() > 1 {
+            targets.extend(args.last().copied());
+        }
+    }
+
+    for target in targets {
+        if let Some(path) = ctx.resolve(target) {
+            if !ctx.is_expected_write_location(&path) {
+                issues.push((
+                    "write_outside_project",
+                    Severity::Warning,
+                    format!("Writes outside the project: {}", target),
+                    format!("{} is outside the project, plugin and ~/.claude directories", path.display()),
+                ));
+            }
+        }
+    }
+}
+
+/// Audit every hook; plugin hooks resolve ${CLAUDE_PLUGIN_ROOT} against their plugin
+pub(crate) fn audit_hooks_internal(hooks: &[HookEntity], plugins: &[PluginEntity]) -> Vec<HookFinding> {
+    let mut findings = Vec::new();
+    let mut seen = HashSet::new();
+    for hook in hooks {
+        // Plugin hook files always live inside the plugin directory
+        let plugin_root = plugins.iter()
+            .map(|p| Path::new(p.plugin_dir.as_str()))
+            .filter(|dir| hook.is_from_plugin && !dir.as_os_str().is_empty())
+            .find(|dir| Path::new(&hook.source_path).starts_with(dir));
+        let ctx = HookContext::for_hook(hook, plugin_root);
+        for (index, definition) in hook.hooks.iter().enumerate() {
+            let Some(command) = definition.command.as_deref().filter(|_| definition.hook_type == "command") else {
+                continue;
+            };
+            for (kind, severity, title, description) in analyze_command(command, &ctx) {
+                let id = generate_id("hookaudit", &format!("{}#{}#{}#{}", hook.id, index, kind, description));
+                if !seen.insert(id.clone()) {
+                    continue;
+                }
+                findings.push(HookFinding {
+                    id,
+                    kind: kind.to_string(),
+                    severity,
+                    title,
+                    description,
+                    file: hook.source_path.clone(),
+                    event: hook.event.clone(),
+                    matcher: hook.matcher.clone(),
+                    hook_id: hook.id.clone(),
+                    hook_index: index,
+                    command: command.to_string(),
+                    source: hook.source.clone(),
+                    plugin_name: hook.plugin_name.clone(),
+                });
+            }
+        }
+    }
+    findings
+}
@@ -0,0 +1,510 @@
+// ============================================================================
+// Agent Studio - Hook Test Harness
+// Run a hook against a realistic event payload, isolated from the project,
+// and interpret the result the way Claude Code would
+// ============================================================================
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::task::JoinHandle;
+
+use super::{HookDefinition, HookEntity};
+
+/// Claude Code's default hook timeout in seconds
+const DEFAULT_HOOK_TIMEOUT: u64 = 60;
+
+/// How long to wait for the last output once the hook and its children are gone
+const PIPE_DRAIN: Duration = Duration::from_secs(1);
+
+/// Environment variables passed through to the hook process
+const PASSTHROUGH_ENV: &[&str] = &["PATH", "HOME", "USER", "SHELL", "LANG", "TMPDIR", "TEMP", "TMP", "SystemRoot"];
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// How Claude Code would react to a hook's result
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum HookDecision {
+    /// Execution continues normally
+    Allow,
+    /// The tool call / prompt / stop is blocked and the reason fed back
+    Block,
+    /// The user is asked to confirm the tool call
+    Ask,
+    /// stdout or additionalContext is added to the conversation
+    InjectContext,
+    /// `continue: false` - Claude stops after this hook
+    StopSession,
+    /// The hook failed but execution continues
+    NonBlockingError,
+    /// The hook cannot be run locally (prompt hooks)
+    Skipped,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HookTestResult {
+    pub hook_index: usize,
+    pub hook_type: String,
+    pub command: Option<String>,
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    pub timed_out: bool,
+    pub duration_ms: u64,
+    pub decision: HookDecision,
+    pub reason: Option<String>,
+    pub additional_context: Option<String>,
+    pub system_message: Option<String>,
+}
+
+/// How a hook under test is kept from changing anything
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum HookSandbox {
+    /// Linux bubblewrap: the whole filesystem is read-only apart from a
+    /// private /tmp, and there is no network
+    Bwrap,
+    /// macOS Seatbelt: writes outside a scratch directory and network access are denied
+    SandboxExec,
+    /// No OS sandbox was available and the caller asked to run the hook anyway,
+    /// with full access to the user's files and the network
+    Unsandboxed,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HookTestReport {
+    pub event: String,
+    pub payload: serde_json::Value,
+    pub results: Vec<HookTestResult>,
+    pub sandbox: HookSandbox,
+}
+
+// ============================================================================
+// Sample Payloads
+// ============================================================================
+
+/// Pick a concrete tool name that a matcher like "Edit|Write" or "mcp__github__.*" matches
+fn sample_tool_name(matcher: Option<&str>) -> String {
+    let matcher = matcher.map(str::trim).unwrap_or("");
+    matcher.split('|')
+        .map(str::trim)
+        .find(|alt| !alt.is_empty() && alt.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'))
+        .map(String::from)
+        .unwrap_or_else(|| "Bash".to_string())
+}
+
+fn sample_tool_input(tool_name: &str, cwd: &str) -> serde_json::Value {
+    let file_path = format!("{}/src/main.rs", cwd.trim_end_matches('/'));
+    match tool_name {
+        "Bash" => serde_json::json!({ "command": "npm test", "description": "Run the test suite" }),
+        "Edit" | "MultiEdit" => serde_json::json!({ "file_path": file_path, "old_string": "foo", "new_string": "bar" }),
+        "Write" => serde_json::json!({ "file_path": file_path, "content": "fn main() {}\n" }),
+        "Read" => serde_json::json!({ "file_path": file_path }),
+        "Glob" => serde_json::json!({ "pattern": "**/*.rs" }),
+        "Grep" => serde_json::json!({ "pattern": "TODO", "path": cwd }),
+        "WebFetch" => serde_json::json!({ "url": "https://example.com", "prompt": "Summarize the page" }),
+        "WebSearch" => serde_json::json!({ "query": "rust async runtimes" }),
+        "Task" => serde_json::json!({ "description": "Investigate failure", "prompt": "Find the root cause", "subagent_type": "general-purpose" }),
+        _ => serde_json::json!({}),
+    }
+}
+
+/// Build the JSON that Claude Code writes to a hook's stdin for an event
+fn build_payload(event: &str, matcher: Option<&str>, cwd: &str) -> serde_json::Value {
+    let mut payload = serde_json::json!({
+        "session_id": "agent-studio-test",
+        "transcript_path": format!("{}/.claude/agent-studio-test.jsonl", cwd.trim_end_matches('/')),
+        "cwd": cwd,
+        "permission_mode": "default",
+        "hook_event_name": event,
+    });
+    let extra = match event {
+        "PreToolUse" | "PermissionRequest" => {
+            let tool_name = sample_tool_name(matcher);
+            serde_json::json!({ "tool_name": tool_name, "tool_input": sample_tool_input(&tool_name, cwd) })
+        }
+        "PostToolUse" => {
+            let tool_name = sample_tool_name(matcher);
+            serde_json::json!({
+                "tool_name": tool_name,
+                "tool_input": sample_tool_input(&tool_name, cwd),
+                "tool_response": { "success": true },
+            })
+        }
+        "UserPromptSubmit" => serde_json::json!({ "prompt": "Add a unit test for the parser" }),
+        "Notification" => serde_json::json!({ "message": "Claude needs your permission to use Bash", "notification_type": "permission_prompt" }),
+        "Stop" | "SubagentStop" => serde_json::json!({ "stop_hook_active": false }),
+        "SubagentStart" => serde_json::json!({ "agent_type": "general-purpose" }),
+        "PreCompact" => serde_json::json!({ "trigger": matcher.filter(|m| *m == "auto").unwrap_or("manual"), "custom_instructions": "" }),
+        "SessionStart" => serde_json::json!({ "source": matcher.filter(|m| !m.contains('|') && !m.is_empty()).unwrap_or("startup") }),
+        "SessionEnd" => serde_json::json!({ "reason": "other" }),
+        _ => serde_json::json!({}),
+    };
+    if let (Some(obj), Some(extra)) = (payload.as_object_mut(), extra.as_object()) {
+        obj.extend(extra.clone());
+    }
+    payload
+}
+
+// ============================================================================
+// Result Interpretation
+// ============================================================================
+
+struct Interpretation {
+    decision: HookDecision,
+    reason: Option<String>,
+    additional_context: Option<String>,
+    system_message: Option<String>,
+}
+
+/// Apply Claude Code's exit code and JSON output rules to a hook result
+fn interpret(event: &str, exit_code: Option<i32>, stdout: &str, stderr: &str, timed_out: bool) -> Interpretation {
+    let plain = |decision, reason: Option<String>| Interpretation {
+        decision,
+        reason,
+        additional_context: None,
+        system_message: None,
+    };
+    let non_empty = |s: &str| Some(s.trim().to_string()).filter(|s| !s.is_empty());
+
+    if timed_out {
+        return plain(HookDecision::NonBlockingError, Some("Hook timed out".to_string()));
+    }
+    match exit_code {
+        Some(0) => {}
+        Some(2) => {
+            // Exit code 2 is a blocking error; stderr is fed back to Claude
+            let decision = match event {
+                "PreToolUse" | "PermissionRequest" | "UserPromptSubmit" | "Stop" | "SubagentStop" => HookDecision::Block,
+                _ => HookDecision::NonBlockingError,
+            };
+            return plain(decision, non_empty(stderr));
+        }
+        _ => return plain(HookDecision::NonBlockingError, non_empty(stderr).or_else(|| non_empty(stdout))),
+    }
+
+    let output: Option<serde_json::Value> = serde_json::from_str(stdout.trim())
+        .ok()
+        .filter(|v: &serde_json::Value| v.is_object());
+    let Some(output) = output else {
+        // Plain stdout is added as context for prompt and session start events
+        let decision = match (event, non_empty(stdout)) {
+            ("UserPromptSubmit" | "SessionStart", Some(_)) => HookDecision::InjectContext,
+            _ => HookDecision::Allow,
+        };
+        let context = if decision == HookDecision::InjectContext { non_empty(stdout) } else { None };
+        return Interpretation { decision, reason: None, additional_context: context, system_message: None };
+    };
+
+    let str_field = |value: &serde_json::Value, key: &str| value.get(key).and_then(|v| v.as_str()).map(String::from);
+    let specific = output.get("hookSpecificOutput").cloned().unwrap_or(serde_json::Value::Null);
+    let system_message = str_field(&output, "systemMessage");
+    let additional_context = str_field(&specific, "additionalContext");
+
+    if output.get("continue").and_then(|v| v.as_bool()) == Some(false) {
+        return Interpretation {
+            decision: HookDecision::StopSession,
+            reason: str_field(&output, "stopReason"),
+            additional_context,
+            system_message,
+        };
+    }
+
+    let permission_decision = str_field(&specific, "permissionDecision")
+        .or_else(|| specific.get("decision").and_then(|d| str_field(d, "behavior")));
+    let (decision, reason) = match (permission_decision.as_deref(), str_field(&output, "decision").as_deref()) {
+        (Some("deny"), _) => (HookDecision::Block, str_field(&specific, "permissionDecisionReason")),
+        (Some("ask"), _) => (HookDecision::Ask, str_field(&specific, "permissionDecisionReason")),
+        (Some("allow"), _) => (HookDecision::Allow, str_field(&specific, "permissionDecisionReason")),
+        (_, Some("block")) => (HookDecision::Block, str_field(&output, "reason")),
+        (_, Some("approve")) => (HookDecision::Allow, str_field(&output, "reason")),
+        _ if additional_context.is_some() => (HookDecision::InjectContext, None),
+        _ => (HookDecision::Allow, None),
+    };
+
+    Interpretation { decision, reason, additional_context, system_message }
+}
+
+// ============================================================================
+// Sandbox
+// ============================================================================
+
+/// The OS sandbox to run hooks under, if any, checked once. bwrap is tried for
+/// real because it's often installed where user namespaces are disabled.
+static SANDBOX: LazyLock<Option<HookSandbox>> = LazyLock::new(|| {
+    if cfg!(target_os = "linux") {
+        let works = std::process::Command::new("bwrap")
+            .args(["--ro-bind", "/", "/", "--dev", "/dev", "--unshare-net", "true"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success());
+        if works {
+            return Some(HookSandbox::Bwrap);
+        }
+    }
+    if cfg!(target_os = "macos") && Path::new("/usr/bin/sandbox-exec").is_file() {
+        return Some(HookSandbox::SandboxExec);
+    }
+    None
+});
+
+/// An empty directory for the hook to write to, removed when the test ends
+struct ScratchDir(PathBuf);
+
+impl ScratchDir {
+    fn create() -> Result<Self, String> {
+        let nanos = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_nanos();
+        let dir = std::env::temp_dir().join(format!("agent-studio-hook-{}-{}", std::process::id(), nanos));
+        std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        // Canonical, so a sandbox profile's path matches what the kernel sees (/private/var on macOS)
+        Ok(ScratchDir(std::fs::canonicalize(&dir).unwrap_or(dir)))
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Seatbelt profile: anything may be read, nothing written outside `scratch`, no network
+fn seatbelt_profile(scratch: &Path) -> String {
+    let quoted = scratch.to_string_lossy().replace('\\', "\\\\").replace('"', "\\\"");
+    format!(
+        "(version 1)(allow default)(deny network*)(deny file-write*)\
+         (allow file-write* (subpath \"{}\") (literal \"/dev/null\") (literal \"/dev/tty\"))",
+        quoted
+    )
+}
+
+/// The command line that runs `command` through the shell inside the sandbox
+fn sandboxed_command(sandbox: HookSandbox, command: &str, cwd: &Path, scratch: &Path) -> tokio::process::Command {
+    match sandbox {
+        HookSandbox::Bwrap => {
+            let mut c = tokio::process::Command::new("bwrap");
+            // The project is bound again after /tmp is replaced, in case it lives there
+            c.args(["--ro-bind", "/", "/", "--dev", "/dev", "--proc", "/proc", "--tmpfs", "/tmp", "--ro-bind"])
+                .arg(cwd)
+                .arg(cwd)
+                .args(["--unshare-net", "--unshare-pid", "--die-with-parent", "--chdir"])
+                .arg(cwd)
+                .args(["--", "sh", "-c", command]);
+            c
+        }
+        HookSandbox::SandboxExec => {
+            let mut c = tokio::process::Command::new("/usr/bin/sandbox-exec");
+            c.arg("-p").arg(seatbelt_profile(scratch)).args(["sh", "-c", command]);
+            c
+        }
+        HookSandbox::Unsandboxed if cfg!(windows) => {
+            let mut c = tokio::process::Command::new("cmd");
+            c.arg("/C").arg(command);
+            c
+        }
+        HookSandbox::Unsandboxed => {
+            let mut c = tokio::process::Command::new("sh");
+            c.arg("-c").arg(command);
+            c
+        }
+    }
+}
+
+/// Read a pipe in the background; the buffer holds whatever has arrived so far,
+/// so output is still there if the hook has to be killed
+fn capture<R: AsyncRead + Unpin + Send + 'static>(pipe: Option<R>) -> (Arc<Mutex<Vec<u8>>>, JoinHandle<()>) {
+    let buffer = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&buffer);
+    let task = tokio::spawn(async move {
+        let Some(mut pipe) = pipe else { return };
+        let mut chunk = [0u8; 8192];
+        while let Ok(n) = pipe.read(&mut chunk).await {
+            if n == 0 {
+                break;
+            }
+            sink.lock().unwrap_or_else(|e| e.into_inner()).extend_from_slice(&chunk[..n]);
+        }
+    });
+    (buffer, task)
+}
+
+fn captured_text(buffer: &Mutex<Vec<u8>>) -> String {
+    String::from_utf8_lossy(&buffer.lock().unwrap_or_else(|e| e.into_inner())).to_string()
+}
+
+/// Kill everything the hook started: it runs in its own process group, so
+/// background jobs and grandchildren go too, not just the shell
+#[cfg(unix)]
+fn kill_process_group(pid: Option<u32>) {
+    if let Some(pid) = pid.and_then(|p| i32::try_from(p).ok()) {
+        // SAFETY: kill has no memory effects; a group that's already gone gives ESRCH
+        unsafe {
+            libc::kill(-pid, libc::SIGKILL);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn kill_process_group(_pid: Option<u32>) {}
+
+// ============================================================================
+// Execution
+// ============================================================================
+
+async fn run_definition(
+    index: usize,
+    hook: &HookDefinition,
+    event: &str,
+    payload: &serde_json::Value,
+    sandbox: HookSandbox,
+    cwd: &Path,
+    scratch: &Path,
+) -> Result<HookTestResult, String> {
+    let command = match (hook.hook_type.as_str(), hook.command.as_deref()) {
+        ("command", Some(command)) => command.to_string(),
+        _ => {
+            return Ok(HookTestResult {
+                hook_index: index,
+                hook_type: hook.hook_type.clone(),
+                command: hook.command.clone(),
+                exit_code: None,
+                stdout: String::new(),
+                stderr: String::new(),
+                timed_out: false,
+                duration_ms: 0,
+                decision: HookDecision::Skipped,
+                reason: Some("Only command hooks can be run locally".to_string()),
+                additional_context: None,
+                system_message: None,
+            });
+        }
+    };
+
+    let mut cmd = sandboxed_command(sandbox, &command, cwd, scratch);
+    // Run with a minimal environment so the test doesn't depend on the app's env
+    cmd.env_clear();
+    for key in PASSTHROUGH_ENV {
+        if let Ok(value) = std::env::var(key) {
+            cmd.env(key, value);
+        }
+    }
+    let tmp = if sandbox == HookSandbox::Bwrap { Path::new("/tmp") } else { scratch };
+    cmd.env("TMPDIR", tmp)
+        .env("CLAUDE_PROJECT_DIR", cwd)
+        .current_dir(cwd)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    #[cfg(unix)]
+    cmd.process_group(0);
+
+    let started = Instant::now();
+    let mut child = cmd.spawn().map_err(|e| format!("Failed to start hook: {}", e))?;
+    let pid = child.id();
+
+    // Stdin and both output pipes are serviced in the background, so a hook that
+    // never reads its input or fills stdout can't hold up the timeout
+    let input = serde_json::to_vec(payload).map_err(|e| e.to_string())?;
+    let stdin = child.stdin.take();
+    let writer = tokio::spawn(async move {
+        if let Some(mut stdin) = stdin {
+            // A hook may exit without reading stdin; a broken pipe is not an error here
+            let _ = stdin.write_all(&input).await;
+        }
+    });
+    let (stdout, mut stdout_task) = capture(child.stdout.take());
+    let (stderr, mut stderr_task) = capture(child.stderr.take());
+
+    let timeout = Duration::from_secs(hook.timeout.map(u64::from).unwrap_or(DEFAULT_HOOK_TIMEOUT));
+    let status = tokio::time::timeout(timeout, child.wait()).await;
+    // Also reaps anything the hook left running in the background, which could hold the pipes open
+    kill_process_group(pid);
+    let _ = child.start_kill();
+    writer.abort();
+    let _ = tokio::time::timeout(PIPE_DRAIN, async {
+        let _ = (&mut stdout_task).await;
+        let _ = (&mut stderr_task).await;
+    })
+    .await;
+    stdout_task.abort();
+    stderr_task.abort();
+    let duration_ms = started.elapsed().as_millis() as u64;
+
+    let (exit_code, timed_out) = match status {
+        Ok(Ok(status)) => (status.code(), false),
+        Ok(Err(e)) => return Err(format!("Failed to run hook: {}", e)),
+        Err(_) => (None, true),
+    };
+    let (stdout, stderr) = (captured_text(&stdout), captured_text(&stderr));
+
+    let interpretation = interpret(event, exit_code, &stdout, &stderr, timed_out);
+    Ok(HookTestResult {
+        hook_index: index,
+        hook_type: hook.hook_type.clone(),
+        command: Some(command),
+        exit_code,
+        stdout,
+        stderr,
+        timed_out,
+        duration_ms,
+        decision: interpretation.decision,
+        reason: interpretation.reason,
+        additional_context: interpretation.additional_context,
+        system_message: interpretation.system_message,
+    })
+}
+
+// ============================================================================
+// Public Tauri Commands
+// ============================================================================
+
+/// Build the sample stdin payload a hook would receive for its event and matcher
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn build_hook_payload(event: String, matcher: Option<String>, project_path: Option<String>) -> serde_json::Value {
+    let cwd = project_path.unwrap_or_else(|| std::env::temp_dir().to_string_lossy().to_string());
+    build_payload(&event, matcher.as_deref(), &cwd)
+}
+
+/// Run every hook of a hook entity against a payload (a sample one if omitted)
+/// under an OS sandbox (see `HookSandbox`). Without one the hook is only run
+/// when `allow_unsandboxed` is set, and the report says so.
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn test_hook(
+    hook: HookEntity,
+    payload: Option<serde_json::Value>,
+    project_path: Option<String>,
+    allow_unsandboxed: Option<bool>,
+) -> Result<HookTestReport, String> {
+    let sandbox = match *SANDBOX {
+        Some(sandbox) => sandbox,
+        None if allow_unsandboxed.unwrap_or(false) => HookSandbox::Unsandboxed,
+        None => return Err(
+            "No sandbox is available to test hooks in (bubblewrap on Linux, sandbox-exec on macOS). \
+             Install one, or allow running the hook unsandboxed with full access to your files and network."
+                .to_string(),
+        ),
+    };
+    let cwd = project_path
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir);
+    if !cwd.is_dir() {
+        return Err(format!("Working directory does not exist: {}", cwd.display()));
+    }
+    let payload = payload.unwrap_or_else(|| build_payload(&hook.event, hook.matcher.as_deref(), &cwd.to_string_lossy()));
+
+    let scratch = ScratchDir::create()?;
+    let mut results = Vec::new();
+    for (index, definition) in hook.hooks.iter().enumerate() {
+        results.push(run_definition(index, definition, &hook.event, &payload, sandbox, &cwd, &scratch.0).await?);
+    }
+
+    Ok(HookTestReport { event: hook.event, payload, results, sandbox })
+}
@@ -0,0 +1,344 @@
+// ============================================================================
+// Agent Studio - Hook Editing
+// Validated create/update/reorder/delete of hooks blocks in settings files
+// ============================================================================
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use super::json_edit;
+use super::{ensure_not_plugin_owned, HookDefinition, HookMatcher};
+
+/// Hook events understood by Claude Code
+pub(crate) const HOOK_EVENTS: &[&str] = &[
+    "PreToolUse", "PermissionRequest", "PostToolUse", "Notification", "UserPromptSubmit",
+    "Stop", "SubagentStop", "SubagentStart", "PreCompact", "SessionStart", "SessionEnd",
+];
+
+/// Events that ignore `matcher` entirely
+const MATCHERLESS_EVENTS: &[&str] = &["UserPromptSubmit", "Stop", "SubagentStop"];
+
+/// Events that support `type: "prompt"` hooks
+const PROMPT_EVENTS: &[&str] = &["PreToolUse", "PermissionRequest", "UserPromptSubmit", "Stop", "SubagentStop"];
+
+/// Fixed matcher values for events that don't take a regex
+const FIXED_MATCHERS: &[(&str, &[&str])] = &[
+    ("SessionStart", &["startup", "resume", "clear", "compact"]),
+    ("PreCompact", &["manual", "auto"]),
+    ("SessionEnd", &["clear", "logout", "prompt_input_exit", "other"]),
+];
+
+/// Timeout bounds in seconds
+const MIN_HOOK_TIMEOUT: u32 = 1;
+const MAX_HOOK_TIMEOUT: u32 = 600;
+
+// ============================================================================
+// Validation
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HookValidationError {
+    pub path: String,  // e.g. "hooks.PreToolUse[0].hooks[1].timeout"
+    pub message: String,
+}
+
+fn validate_event(event: &str) -> Result<(), HookValidationError> {
+    if HOOK_EVENTS.contains(&event) {
+        return Ok(());
+    }
+    let suggestion = HOOK_EVENTS.iter()
+        .find(|e| e.eq_ignore_ascii_case(event))
+        .map(|e| format!(" (did you mean {}?)", e))
+        .unwrap_or_default();
+    Err(HookValidationError {
+        path: format!("hooks.{}", event),
+        message: format!("Unknown hook event \"{}\"{}", event, suggestion),
+    })
+}
+
+fn validate_matcher(event: &str, matcher: Option<&str>, path: &str) -> Vec<HookValidationError> {
+    let mut errors = Vec::new();
+    let matcher = match matcher.map(str::trim) {
+        Some(m) if !m.is_empty() && m != "*" => m,
+        _ => return errors,
+    };
+    let error = |message: String| HookValidationError { path: format!("{}.matcher", path), message };
+
+    if MATCHERLESS_EVENTS.contains(&event) {
+        errors.push(error(format!("{} hooks do not use a matcher; remove it", event)));
+    } else if let Some((_, allowed)) = FIXED_MATCHERS.iter().find(|(e, _)| *e == event) {
+        for value in matcher.split('|').map(str::trim) {
+            if !allowed.contains(&value) {
+                errors.push(error(format!(
+                    "\"{}\" is not a valid {} matcher (expected one of: {})",
+                    value, event, allowed.join(", ")
+                )));
+            }
+        }
+    } else if let Err(e) = regex::Regex::new(matcher) {
+        errors.push(error(format!("Invalid matcher regex: {}", e)));
+    }
+    errors
+}
+
+fn validate_definition(event: &str, hook: &HookDefinition, path: &str) -> Vec<HookValidationError> {
+    let mut errors = Vec::new();
+    let error = |field: &str, message: String| HookValidationError { path: format!("{}.{}", path, field), message };
+
+    match hook.hook_type.as_str() {
+        "command" => {
+            if hook.command.as_deref().is_none_or(|c| c.trim().is_empty()) {
+                errors.push(error("command", "Command hooks require a non-empty command".to_string()));
+            }
+            if hook.prompt.is_some() {
+                errors.push(error("prompt", "Command hooks cannot have a prompt".to_string()));
+            }
+        }
+        "prompt" => {
+            if hook.prompt.as_deref().is_none_or(|p| p.trim().is_empty()) {
+                errors.push(error("prompt", "Prompt hooks require a non-empty prompt".to_string()));
+            }
+            if hook.command.is_some() {
+                errors.push(error("command", "Prompt hooks cannot have a command".to_string()));
+            }
+            if !PROMPT_EVENTS.contains(&event) {
+                errors.push(error("type", format!(
+                    "Prompt hooks are not supported for {} (supported: {})",
+                    event, PROMPT_EVENTS.join(", ")
+                )));
+            }
+        }
+        other => errors.push(error("type", format!("Unknown hook type \"{}\" (expected \"command\" or \"prompt\")", other))),
+    }
+
+    if let Some(timeout) = hook.timeout {
+        if !(MIN_HOOK_TIMEOUT..=MAX_HOOK_TIMEOUT).contains(&timeout) {
+            errors.push(error("timeout", format!(
+                "Timeout must be between {} and {} seconds (got {})",
+                MIN_HOOK_TIMEOUT, MAX_HOOK_TIMEOUT, timeout
+            )));
+        }
+    }
+    errors
+}
+
+fn validate_hook_matcher_internal(event: &str, matcher: &HookMatcher, index: usize) -> Vec<HookValidationError> {
+    if let Err(e) = validate_event(event) {
+        return vec![e];
+    }
+    let path = format!("hooks.{}[{}]", event, index);
+    let mut errors = validate_matcher(event, matcher.matcher.as_deref(), &path);
+    if matcher.hooks.is_empty() {
+        errors.push(HookValidationError {
+            path: format!("{}.hooks", path),
+            message: "A matcher needs at least one hook".to_string(),
+        });
+    }
+    for (idx, hook) in matcher.hooks.iter().enumerate() {
+        errors.extend(validate_definition(event, hook, &format!("{}.hooks[{}]", path, idx)));
+    }
+    errors
+}
+
+fn into_result(errors: Vec<HookValidationError>) -> Result<(), String> {
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.iter().map(|e| format!("{}: {}", e.path, e.message)).collect::<Vec<_>>().join("; "))
+    }
+}
+
+// ============================================================================
+// JSON Helpers
+// ============================================================================
+
+/// Serialize a hook definition, omitting unset optional fields
+fn definition_to_json(hook: &HookDefinition) -> serde_json::Value {
+    let mut obj = serde_json::Map::new();
+    obj.insert("type".to_string(), hook.hook_type.clone().into());
+    if let Some(command) = &hook.command {
+        obj.insert("command".to_string(), command.clone().into());
+    }
+    if let Some(prompt) = &hook.prompt {
+        obj.insert("prompt".to_string(), prompt.clone().into());
+    }
+    if let Some(timeout) = hook.timeout {
+        obj.insert("timeout".to_string(), timeout.into());
+    }
+    if let Some(once) = hook.once {
+        obj.insert("once".to_string(), once.into());
+    }
+    serde_json::Value::Object(obj)
+}
+
+fn matcher_to_json(matcher: &HookMatcher) -> serde_json::Value {
+    let mut obj = serde_json::Map::new();
+    if let Some(m) = &matcher.matcher {
+        obj.insert("matcher".to_string(), m.clone().into());
+    }
+    obj.insert("hooks".to_string(), matcher.hooks.iter().map(definition_to_json).collect());
+    serde_json::Value::Object(obj)
+}
+
+/// Set `pointer` to `value`, or remove it when `value` is None
+fn set_or_remove(text: &str, pointer: &str, value: Option<serde_json::Value>) -> Result<String, String> {
+    match value {
+        Some(v) => json_edit::set(text, pointer, &v),
+        None if json_edit::get(text, pointer)?.is_some() => json_edit::remove(text, pointer),
+        None => Ok(text.to_string()),
+    }
+}
+
+fn array_len(text: &str, pointer: &str) -> Result<usize, String> {
+    Ok(json_edit::get(text, pointer)?
+        .and_then(|v| v.as_array().map(|a| a.len()))
+        .unwrap_or(0))
+}
+
+fn ensure_index(text: &str, pointer: &str, index: usize, what: &str) -> Result<(), String> {
+    let len = array_len(text, pointer)?;
+    if index >= len {
+        return Err(format!("{} index {} is out of range ({} defined)", what, index, len));
+    }
+    Ok(())
+}
+
+/// Remove the array at `pointer` if it has become empty
+fn prune_empty(text: String, pointer: &str) -> Result<String, String> {
+    match json_edit::get(&text, pointer)? {
+        Some(serde_json::Value::Array(arr)) if arr.is_empty() => json_edit::remove(&text, pointer),
+        _ => Ok(text),
+    }
+}
+
+/// Edit a settings file's text; hooks shipped by a plugin are read-only
+fn edit_settings<F>(settings_path: &str, edit: F) -> Result<(), String>
+where
+    F: FnOnce(&str) -> Result<String, String>,
+{
+    let path = PathBuf::from(settings_path);
+    ensure_not_plugin_owned(&path)?;
+    json_edit::edit_file(&path, edit)
+}
+
+fn event_pointer(event: &str) -> String {
+    json_edit::build_pointer(&["hooks", event])
+}
+
+fn matcher_pointer(event: &str, index: usize) -> String {
+    json_edit::build_pointer(&["hooks", event, &index.to_string()])
+}
+
+// ============================================================================
+// Public Tauri Commands
+// ============================================================================
+
+/// Validate a matcher block without writing it
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn validate_hook(event: String, matcher: HookMatcher) -> Vec<HookValidationError> {
+    validate_hook_matcher_internal(&event, &matcher, 0)
+}
+
+/// Append a new matcher block under an event
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn add_hook_matcher(settings_path: String, event: String, matcher: HookMatcher) -> Result<(), String> {
+    let pointer = event_pointer(&event);
+    edit_settings(&settings_path, |text| {
+        let index = array_len(text, &pointer)?;
+        into_result(validate_hook_matcher_internal(&event, &matcher, index))?;
+        json_edit::append(text, &pointer, &matcher_to_json(&matcher))
+    })
+}
+
+/// Replace the matcher and hooks of an existing block, keeping any other keys
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn update_hook_matcher(settings_path: String, event: String, index: usize, matcher: HookMatcher) -> Result<(), String> {
+    into_result(validate_hook_matcher_internal(&event, &matcher, index))?;
+    let pointer = matcher_pointer(&event, index);
+    edit_settings(&settings_path, |text| {
+        ensure_index(text, &event_pointer(&event), index, "Matcher")?;
+        let text = set_or_remove(text, &format!("{}/matcher", pointer), matcher.matcher.clone().map(Into::into))?;
+        let hooks: serde_json::Value = matcher.hooks.iter().map(definition_to_json).collect();
+        json_edit::set(&text, &format!("{}/hooks", pointer), &hooks)
+    })
+}
+
+/// Remove a matcher block; the event key is removed once it has no blocks left
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn remove_hook_matcher(settings_path: String, event: String, index: usize) -> Result<(), String> {
+    edit_settings(&settings_path, |text| {
+        ensure_index(text, &event_pointer(&event), index, "Matcher")?;
+        let text = json_edit::remove(text, &matcher_pointer(&event, index))?;
+        prune_empty(text, &event_pointer(&event))
+    })
+}
+
+/// Move a matcher block to a new position within its event (hooks run in order).
+/// The block's text moves as written, comments and formatting included.
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn move_hook_matcher(settings_path: String, event: String, from: usize, to: usize) -> Result<(), String> {
+    let pointer = event_pointer(&event);
+    edit_settings(&settings_path, |text| {
+        let len = array_len(text, &pointer)?;
+        if from >= len || to >= len {
+            return Err(format!("Matcher index out of range ({} defined)", len));
+        }
+        json_edit::move_element(text, &pointer, from, to)
+    })
+}
+
+/// Append a hook definition to an existing matcher block
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn add_hook_definition(settings_path: String, event: String, matcher_index: usize, hook: HookDefinition) -> Result<(), String> {
+    validate_event(&event).map_err(|e| e.message)?;
+    let hooks_pointer = format!("{}/hooks", matcher_pointer(&event, matcher_index));
+    edit_settings(&settings_path, |text| {
+        ensure_index(text, &event_pointer(&event), matcher_index, "Matcher")?;
+        let path = format!("hooks.{}[{}].hooks[{}]", event, matcher_index, array_len(text, &hooks_pointer)?);
+        into_result(validate_definition(&event, &hook, &path))?;
+        json_edit::append(text, &hooks_pointer, &definition_to_json(&hook))
+    })
+}
+
+/// Update a hook definition in place, keeping any keys Agent Studio doesn't know about
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn update_hook_definition(
+    settings_path: String,
+    event: String,
+    matcher_index: usize,
+    hook_index: usize,
+    hook: HookDefinition,
+) -> Result<(), String> {
+    validate_event(&event).map_err(|e| e.message)?;
+    let path = format!("hooks.{}[{}].hooks[{}]", event, matcher_index, hook_index);
+    into_result(validate_definition(&event, &hook, &path))?;
+
+    let hooks_pointer = format!("{}/hooks", matcher_pointer(&event, matcher_index));
+    let pointer = format!("{}/{}", hooks_pointer, hook_index);
+    edit_settings(&settings_path, |text| {
+        ensure_index(text, &event_pointer(&event), matcher_index, "Matcher")?;
+        ensure_index(text, &hooks_pointer, hook_index, "Hook")?;
+        let text = json_edit::set(text, &format!("{}/type", pointer), &hook.hook_type.clone().into())?;
+        let text = set_or_remove(&text, &format!("{}/command", pointer), hook.command.clone().map(Into::into))?;
+        let text = set_or_remove(&text, &format!("{}/prompt", pointer), hook.prompt.clone().map(Into::into))?;
+        let text = set_or_remove(&text, &format!("{}/timeout", pointer), hook.timeout.map(Into::into))?;
+        set_or_remove(&text, &format!("{}/once", pointer), hook.once.map(Into::into))
+    })
+}
+
+/// Remove a hook definition; an emptied matcher block is removed as well
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn remove_hook_definition(settings_path: String, event: String, matcher_index: usize, hook_index: usize) -> Result<(), String> {
+    let hooks_pointer = format!("{}/hooks", matcher_pointer(&event, matcher_index));
+    edit_settings(&settings_path, |text| {
+        ensure_index(text, &event_pointer(&event), matcher_index, "Matcher")?;
+        ensure_index(text, &hooks_pointer, hook_index, "Hook")?;
+        let text = json_edit::remove(text, &format!("{}/{}", hooks_pointer, hook_index))?;
+        if array_len(&text, &hooks_pointer)? == 0 {
+            let text = json_edit::remove(&text, &matcher_pointer(&event, matcher_index))?;
+            prune_empty(text, &event_pointer(&event))
+        } else {
+            Ok(text)
+        }
+    })
+}
@@ -0,0 +1,723 @@
+// ============================================================================
+// Agent Studio - Format-Preserving JSON Editing
+// Surgical edits to config files that keep key order, indentation, comments,
+// trailing commas and everything outside the edited key byte-for-byte intact.
+// Accepts JSONC (opencode.jsonc, or .json files with comments).
+// ============================================================================
+
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// ============================================================================
+// Syntax Tree
+// ============================================================================
+
+/// A JSON value with the byte span it occupies in the source text
+struct Node {
+    start: usize,
+    end: usize,
+    /// Offset of the `,` after this element, when it's in a container and has one
+    comma: Option<usize>,
+    kind: NodeKind,
+}
+
+enum NodeKind {
+    Object(Vec<Member>),
+    Array(Vec<Node>),
+    Scalar,
+}
+
+struct Member {
+    key: String,
+    start: usize,  // offset of the opening quote of the key
+    value: Node,
+}
+
+/// A container element: key start (or value start) to value end, and its comma
+#[derive(Clone, Copy)]
+struct Element {
+    start: usize,
+    end: usize,
+    comma: Option<usize>,
+}
+
+impl Node {
+    fn elements(&self) -> Vec<Element> {
+        match &self.kind {
+            NodeKind::Object(members) => members.iter()
+                .map(|m| Element { start: m.start, end: m.value.end, comma: m.value.comma })
+                .collect(),
+            NodeKind::Array(items) => items.iter()
+                .map(|n| Element { start: n.start, end: n.end, comma: n.comma })
+                .collect(),
+            NodeKind::Scalar => Vec::new(),
+        }
+    }
+}
+
+struct Parser<'a> {
+    src: &'a str,
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(src: &'a str) -> Self {
+        Parser { src, bytes: src.as_bytes(), pos: 0 }
+    }
+
+    fn error(&self, message: &str) -> String {
+        let (line, column) = line_column(self.src, self.pos);
+        format!("{} at line {}, column {}", message, line, column)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    /// Skip whitespace and `//` / `/* */` comments
+    fn skip_ws(&mut self) {
+        self.pos = skip_trivia(self.src, self.pos);
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), String> {
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("Expected '{}'", byte as char)))
+        }
+    }
+
+    fn parse_document(&mut self) -> Result<Node, String> {
+        self.skip_ws();
+        let root = self.parse_value()?;
+        self.skip_ws();
+        if self.pos < self.bytes.len() {
+            return Err(self.error("Unexpected trailing content"));
+        }
+        Ok(root)
+    }
+
+    fn parse_value(&mut self) -> Result<Node, String> {
+        match self.peek() {
+            Some(b'{') => self.parse_object(),
+            Some(b'[') => self.parse_array(),
+            Some(b'"') => {
+                let start = self.pos;
+                self.parse_string()?;
+                Ok(Node { start, end: self.pos, comma: None, kind: NodeKind::Scalar })
+            }
+            Some(_) => self.parse_literal(),
+            None => Err(self.error("Unexpected end of input")),
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<Node, String> {
+        let start = self.pos;
+        self.expect(b'{')?;
+        let mut members = Vec::new();
+        self.skip_ws();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(Node { start, end: self.pos, comma: None, kind: NodeKind::Object(members) });
+        }
+        loop {
+            self.skip_ws();
+            let key_start = self.pos;
+            if self.peek() != Some(b'"') {
+                return Err(self.error("Expected object key"));
+            }
+            let key = self.parse_string()?;
+            self.skip_ws();
+            self.expect(b':')?;
+            self.skip_ws();
+            let mut value = self.parse_value()?;
+            self.skip_ws();
+            if self.peek() == Some(b',') {
+                value.comma = Some(self.pos);
+                self.pos += 1;
+                self.skip_ws();
+            }
+            let separated = value.comma.is_some();
+            members.push(Member { key, start: key_start, value });
+            // The closing brace may follow a comma: JSONC allows trailing commas
+            match self.peek() {
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(Node { start, end: self.pos, comma: None, kind: NodeKind::Object(members) });
+                }
+                _ if separated => {}
+                _ => return Err(self.error("Expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<Node, String> {
+        let start = self.pos;
+        self.expect(b'[')?;
+        let mut items = Vec::new();
+        self.skip_ws();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(Node { start, end: self.pos, comma: None, kind: NodeKind::Array(items) });
+        }
+        loop {
+            self.skip_ws();
+            let mut item = self.parse_value()?;
+            self.skip_ws();
+            if self.peek() == Some(b',') {
+                item.comma = Some(self.pos);
+                self.pos += 1;
+                self.skip_ws();
+            }
+            let separated = item.comma.is_some();
+            items.push(item);
+            match self.peek() {
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(Node { start, end: self.pos, comma: None, kind: NodeKind::Array(items) });
+                }
+                _ if separated => {}
+                _ => return Err(self.error("Expected ',' or ']'")),
+            }
+        }
+    }
+
+    /// Parse a string literal and return its decoded value
+    fn parse_string(&mut self) -> Result<String, String> {
+        let start = self.pos;
+        self.expect(b'"')?;
+        loop {
+            match self.peek() {
+                Some(b'\\') => self.pos += 2,
+                Some(b'"') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(_) => self.pos += 1,
+                None => return Err(self.error("Unterminated string")),
+            }
+        }
+        serde_json::from_str(&self.src[start..self.pos]).map_err(|e| self.error(&format!("Invalid string ({})", e)))
+    }
+
+    /// Parse a number, `true`, `false` or `null`
+    fn parse_literal(&mut self) -> Result<Node, String> {
+        let start = self.pos;
+        while let Some(b) = self.peek() {
+            if matches!(b, b',' | b'}' | b']' | b' ' | b'\t' | b'\n' | b'\r' | b'/') {
+                break;
+            }
+            self.pos += 1;
+        }
+        let literal = &self.src[start..self.pos];
+        if literal.is_empty() || serde_json::from_str::<serde_json::Value>(literal).is_err() {
+            self.pos = start;
+            return Err(self.error("Invalid value"));
+        }
+        Ok(Node { start, end: self.pos, comma: None, kind: NodeKind::Scalar })
+    }
+}
+
+fn line_column(src: &str, offset: usize) -> (usize, usize) {
+    let before = &src[..offset.min(src.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().map_or(0, |l| l.chars().count()) + 1;
+    (line, column)
+}
+
+// ============================================================================
+// Comments
+// ============================================================================
+
+/// Offset of the first byte at or after `pos` that isn't whitespace or a comment.
+/// An unterminated block comment runs to the end of the text.
+fn skip_trivia(src: &str, mut pos: usize) -> usize {
+    let bytes = src.as_bytes();
+    loop {
+        match bytes.get(pos) {
+            Some(b' ' | b'\t' | b'\n' | b'\r') => pos += 1,
+            Some(b'/') if bytes.get(pos + 1) == Some(&b'/') => {
+                pos = src[pos..].find('\n').map_or(src.len(), |i| pos + i);
+            }
+            Some(b'/') if bytes.get(pos + 1) == Some(&b'*') => {
+                pos = src[pos + 2..].find("*/").map_or(src.len(), |i| pos + 2 + i + 2);
+            }
+            _ => return pos,
+        }
+    }
+}
+
+/// Plain JSON from JSONC: comments become spaces (newlines are kept, so
+/// error positions still match the source) and trailing commas are dropped
+fn strip_comments(src: &str) -> String {
+    let bytes = src.as_bytes();
+    let mut out = String::with_capacity(src.len());
+    let mut pos = 0;
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b'"' => {
+                let start = pos;
+                pos += 1;
+                while pos < bytes.len() && bytes[pos] != b'"' {
+                    pos += if bytes[pos] == b'\\' { 2 } else { 1 };
+                }
+                pos = (pos + 1).min(bytes.len());
+                out.push_str(&src[start..pos]);
+            }
+            b'/' if matches!(bytes.get(pos + 1), Some(b'/' | b'*')) => {
+                let end = skip_trivia(src, pos);
+                // skip_trivia also swallows whitespace after the comment; that's fine to blank too
+                out.extend(src[pos..end].chars().map(|c| if c == '\n' || c == '\r' { c } else { ' ' }));
+                pos = end;
+            }
+            b',' if matches!(bytes.get(skip_trivia(src, pos + 1)), Some(b'}' | b']')) => {
+                out.push(' ');
+                pos += 1;
+            }
+            _ => {
+                let len = src[pos..].chars().next().map_or(1, char::len_utf8);
+                out.push_str(&src[pos..pos + len]);
+                pos += len;
+            }
+        }
+    }
+    out
+}
+
+// ============================================================================
+// Formatting Helpers
+// ============================================================================
+
+/// Formatting conventions detected from the file being edited
+struct Style {
+    indent_unit: String,
+    newline: &'static str,
+}
+
+impl Style {
+    fn detect(text: &str) -> Self {
+        let newline = if text.contains("\r\n") { "\r\n" } else { "\n" };
+        let indent_unit = text.lines()
+            .skip(1)
+            .map(|line| &line[..line.len() - line.trim_start().len()])
+            .find(|ws| !ws.is_empty() && ws.len() < 9)
+            .map(|ws| if ws.starts_with('\t') { "\t".to_string() } else { " ".repeat(ws.len()) })
+            .unwrap_or_else(|| "  ".to_string());
+        Style { indent_unit, newline }
+    }
+}
+
+/// Leading whitespace of the line containing `offset`
+fn line_indent(text: &str, offset: usize) -> &str {
+    let line_start = text[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let line = &text[line_start..];
+    &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+}
+
+fn line_start(text: &str, offset: usize) -> usize {
+    text[..offset].rfind('\n').map_or(0, |i| i + 1)
+}
+
+/// Offset just past the line break ending the line that contains `offset`
+fn next_line_start(text: &str, offset: usize) -> usize {
+    text[offset..].find('\n').map_or(text.len(), |i| offset + i + 1)
+}
+
+/// A line holding nothing but a comment
+fn is_comment_line(line: &str) -> bool {
+    let line = line.trim();
+    line.starts_with("//") || (line.starts_with("/*") && line.ends_with("*/"))
+}
+
+/// Where an element's text begins: its first byte, or the start of the
+/// comment lines directly above it when it sits on its own line
+fn leading_start(text: &str, element: &Element) -> usize {
+    let mut start = line_start(text, element.start);
+    if !text[start..element.start].trim().is_empty() {
+        return element.start;
+    }
+    while start > 0 {
+        let above = line_start(text, start - 1);
+        if !is_comment_line(&text[above..start]) {
+            break;
+        }
+        start = above;
+    }
+    start
+}
+
+/// Where an element's text ends: past its comma and any comment that
+/// follows on the same line, but before the line break
+fn trailing_end(text: &str, element: &Element) -> usize {
+    let base = element.comma.map_or(element.end, |c| c + 1);
+    let rest = &text[base..];
+    let after_ws = base + rest.len() - rest.trim_start_matches([' ', '\t']).len();
+    let rest = &text[after_ws..];
+    let line_len = rest.find(['\r', '\n']).unwrap_or(rest.len());
+    if rest.starts_with("//") || (rest.starts_with("/*") && rest[..line_len].contains("*/")) {
+        match rest.strip_prefix("/*") {
+            Some(block) => after_ws + 2 + block.find("*/").unwrap_or(0) + 2,
+            None => after_ws + line_len,
+        }
+    } else {
+        base
+    }
+}
+
+/// The element is alone on its line(s), comments aside
+fn on_own_line(text: &str, element: &Element) -> bool {
+    let end = trailing_end(text, element);
+    let line_end = text[end..].find('\n').map_or(text.len(), |i| end + i);
+    text[line_start(text, element.start)..element.start].trim().is_empty() && text[end..line_end].trim().is_empty()
+}
+
+/// Serialize a value pretty-printed, with continuation lines indented by `base_indent`
+fn render(value: &serde_json::Value, base_indent: &str, style: &Style) -> Result<String, String> {
+    let mut buf = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(style.indent_unit.as_bytes());
+    let mut serializer = serde_json::Serializer::with_formatter(&mut buf, formatter);
+    value.serialize(&mut serializer).map_err(|e| e.to_string())?;
+    let pretty = String::from_utf8(buf).map_err(|e| e.to_string())?;
+
+    Ok(pretty.lines()
+        .enumerate()
+        .map(|(i, line)| if i == 0 { line.to_string() } else { format!("{}{}", base_indent, line) })
+        .collect::<Vec<_>>()
+        .join(style.newline))
+}
+
+fn splice(text: &str, start: usize, end: usize, replacement: &str) -> String {
+    format!("{}{}{}", &text[..start], replacement, &text[end..])
+}
+
+// ============================================================================
+// JSON Pointers
+// ============================================================================
+
+/// Split an RFC 6901 JSON pointer into unescaped tokens
+pub fn parse_pointer(pointer: &str) -> Result<Vec<String>, String> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    if !pointer.starts_with('/') {
+        return Err(format!("Invalid JSON pointer: {}", pointer));
+    }
+    Ok(pointer[1..].split('/').map(|t| t.replace("~1", "/").replace("~0", "~")).collect())
+}
+
+/// Build a JSON pointer from raw (unescaped) tokens
+pub fn build_pointer(tokens: &[&str]) -> String {
+    tokens.iter().map(|t| format!("/{}", t.replace('~', "~0").replace('/', "~1"))).collect()
+}
+
+enum Lookup<'n> {
+    Found(&'n Node),
+    /// `tokens[depth]` does not exist inside `parent`
+    Missing { parent: &'n Node, depth: usize },
+}
+
+fn lookup<'n>(root: &'n Node, tokens: &[String]) -> Result<Lookup<'n>, String> {
+    let mut node = root;
+    for (depth, token) in tokens.iter().enumerate() {
+        let child = match &node.kind {
+            NodeKind::Object(members) => members.iter().rev().find(|m| &m.key == token).map(|m| &m.value),
+            NodeKind::Array(items) => match token.parse::<usize>() {
+                Ok(idx) => items.get(idx),
+                Err(_) if token == "-" => None,
+                Err(_) => return Err(format!("Invalid array index \"{}\"", token)),
+            },
+            NodeKind::Scalar => {
+                return Err(format!("Cannot descend into \"{}\": parent is not an object or array", token));
+            }
+        };
+        match child {
+            Some(child) => node = child,
+            None => return Ok(Lookup::Missing { parent: node, depth }),
+        }
+    }
+    Ok(Lookup::Found(node))
+}
+
+// ============================================================================
+// Edits
+// ============================================================================
+
+/// Insert an element at the end of an object or array node
+fn insert_into(text: &str, container: &Node, key: Option<&str>, value: &serde_json::Value, style: &Style) -> Result<String, String> {
+    let elements = container.elements();
+    let open = container.start;
+    let close = container.end - 1;
+
+    let entry = |indent: &str| -> Result<String, String> {
+        let rendered = render(value, indent, style)?;
+        Ok(match key {
+            Some(k) => format!("{}: {}", serde_json::to_string(k).map_err(|e| e.to_string())?, rendered),
+            None => rendered,
+        })
+    };
+
+    match (elements.first(), elements.last()) {
+        (Some(first), Some(last)) => {
+            let multiline = text[open + 1..first.start].contains('\n');
+            match (multiline, last.comma) {
+                // Keep a trailing comma style: the new entry gets one too
+                (true, Some(_)) => {
+                    let indent = line_indent(text, first.start);
+                    let at = trailing_end(text, last);
+                    Ok(splice(text, at, at, &format!("{}{}{},", style.newline, indent, entry(indent)?)))
+                }
+                // New line after any comment trailing the last element; its comma goes before the comment
+                (true, None) => {
+                    let indent = line_indent(text, first.start);
+                    let at = trailing_end(text, last);
+                    let text = splice(text, at, at, &format!("{}{}{}", style.newline, indent, entry(indent)?));
+                    Ok(splice(&text, last.end, last.end, ","))
+                }
+                (false, Some(comma)) => Ok(splice(text, comma + 1, comma + 1, &format!(" {},", entry("")?))),
+                (false, None) => Ok(splice(text, last.end, last.end, &format!(", {}", entry("")?))),
+            }
+        }
+        _ => {
+            let parent_indent = line_indent(text, open);
+            let indent = format!("{}{}", parent_indent, style.indent_unit);
+            let inner = &text[open + 1..close];
+            if inner.trim().is_empty() {
+                let body = format!("{}{}{}{}{}", style.newline, indent, entry(&indent)?, style.newline, parent_indent);
+                Ok(splice(text, open + 1, close, &body))
+            } else {
+                // Only comments inside: keep them and add the entry below
+                let at = open + 1 + inner.trim_end().len();
+                let body = format!("{}{}{}{}{}", style.newline, indent, entry(&indent)?, style.newline, parent_indent);
+                Ok(splice(text, at, close, &body))
+            }
+        }
+    }
+}
+
+/// Set the value at `pointer`, creating missing parent objects as needed
+pub fn set(text: &str, pointer: &str, value: &serde_json::Value) -> Result<String, String> {
+    let style = Style::detect(text);
+    let tokens = parse_pointer(pointer)?;
+    let root = Parser::new(text).parse_document()?;
+
+    match lookup(&root, &tokens)? {
+        Lookup::Found(node) => {
+            let rendered = render(value, line_indent(text, node.start), &style)?;
+            Ok(splice(text, node.start, node.end, &rendered))
+        }
+        Lookup::Missing { parent, depth } => {
+            // Wrap the value for every missing level below the parent; an
+            // append token ("-") creates an array, anything else an object
+            let mut nested = value.clone();
+            for token in tokens[depth + 1..].iter().rev() {
+                nested = if token == "-" {
+                    serde_json::Value::Array(vec![nested])
+                } else {
+                    let mut map = serde_json::Map::new();
+                    map.insert(token.clone(), nested);
+                    serde_json::Value::Object(map)
+                };
+            }
+            match &parent.kind {
+                NodeKind::Object(_) => insert_into(text, parent, Some(&tokens[depth]), &nested, &style),
+                NodeKind::Array(items) => {
+                    let token = &tokens[depth];
+                    if token != "-" && token.parse::<usize>().ok() != Some(items.len()) {
+                        return Err(format!("Array index {} is out of bounds", token));
+                    }
+                    insert_into(text, parent, None, &nested, &style)
+                }
+                NodeKind::Scalar => Err("Cannot insert into a scalar value".to_string()),
+            }
+        }
+    }
+}
+
+/// Append a value to the array at `pointer`, creating the array if missing
+pub fn append(text: &str, pointer: &str, value: &serde_json::Value) -> Result<String, String> {
+    set(text, &format!("{}/-", pointer), value)
+}
+
+/// Remove the value at `pointer`, including its key and separating comma
+pub fn remove(text: &str, pointer: &str) -> Result<String, String> {
+    let tokens = parse_pointer(pointer)?;
+    let (last, parent_tokens) = tokens.split_last().ok_or("Cannot remove the document root")?;
+    let root = Parser::new(text).parse_document()?;
+
+    let parent = match lookup(&root, parent_tokens)? {
+        Lookup::Found(node) => node,
+        Lookup::Missing { .. } => return Err(format!("Path not found: {}", pointer)),
+    };
+    let index = match &parent.kind {
+        NodeKind::Object(members) => members.iter().rposition(|m| &m.key == last),
+        NodeKind::Array(items) => last.parse::<usize>().ok().filter(|i| *i < items.len()),
+        NodeKind::Scalar => None,
+    }
+    .ok_or_else(|| format!("Path not found: {}", pointer))?;
+
+    let elements = parent.elements();
+    let element = &elements[index];
+    let is_last = index + 1 == elements.len();
+
+    if on_own_line(text, element) {
+        // Drop the element's lines along with the comments attached to it
+        let start = line_start(text, leading_start(text, element));
+        let end = next_line_start(text, trailing_end(text, element));
+        let mut updated = splice(text, start, end, "");
+        // Removing the last element leaves the previous one's comma dangling,
+        // unless the list already used trailing commas
+        if is_last && element.comma.is_none() && index > 0 {
+            if let Some(comma) = elements[index - 1].comma {
+                updated = splice(&updated, comma, comma + 1, "");
+            }
+        }
+        // An emptied container collapses to {} / []
+        let close = parent.end - 1 - (text.len() - updated.len());
+        if elements.len() == 1 && updated[parent.start + 1..close].trim().is_empty() {
+            updated = splice(&updated, parent.start + 1, close, "");
+        }
+        return Ok(updated);
+    }
+
+    if elements.len() == 1 {
+        return Ok(splice(text, parent.start + 1, parent.end - 1, ""));
+    }
+    if !is_last {
+        Ok(splice(text, element.start, elements[index + 1].start, ""))
+    } else {
+        Ok(splice(text, elements[index - 1].end, element.end, ""))
+    }
+}
+
+/// Move an element of the array at `pointer` from one index to another,
+/// keeping its text exactly as written. Elements on their own lines take
+/// their comments along and commas are re-placed; inline elements swap
+/// values and leave the separators where they are.
+pub fn move_element(text: &str, pointer: &str, from: usize, to: usize) -> Result<String, String> {
+    let tokens = parse_pointer(pointer)?;
+    let root = Parser::new(text).parse_document()?;
+    let array = match lookup(&root, &tokens)? {
+        Lookup::Found(node) if matches!(node.kind, NodeKind::Array(_)) => node,
+        _ => return Err(format!("No array at {}", pointer)),
+    };
+    let elements = array.elements();
+    let count = elements.len();
+    if from >= count || to >= count {
+        return Err(format!("Index out of range ({} elements)", count));
+    }
+    if from == to {
+        return Ok(text.to_string());
+    }
+
+    let own_lines = elements.iter().all(|e| on_own_line(text, e));
+    let trailing_comma = elements.last().is_some_and(|e| e.comma.is_some());
+    // Each element's span, and its text split around the comma: (span, before, after)
+    let pieces: Vec<((usize, usize), &str, &str)> = elements.iter()
+        .map(|e| {
+            if own_lines {
+                let start = line_start(text, leading_start(text, e));
+                let end = next_line_start(text, trailing_end(text, e));
+                let after_comma = e.comma.map_or(e.end, |c| c + 1);
+                ((start, end), &text[start..e.end], &text[after_comma..end])
+            } else {
+                ((e.start, e.end), &text[e.start..e.end], "")
+            }
+        })
+        .collect();
+
+    let mut order: Vec<usize> = (0..count).collect();
+    let moved = order.remove(from);
+    order.insert(to, moved);
+
+    let mut out = text[..pieces[0].0 .0].to_string();
+    for (slot, &index) in order.iter().enumerate() {
+        if slot > 0 {
+            // Whatever separated the previous slot from this one stays in place
+            out.push_str(&text[pieces[slot - 1].0 .1..pieces[slot].0 .0]);
+        }
+        let (_, before, after) = pieces[index];
+        out.push_str(before);
+        if own_lines && (slot + 1 < count || trailing_comma) {
+            out.push(',');
+        }
+        out.push_str(after);
+    }
+    out.push_str(&text[pieces[count - 1].0 .1..]);
+    Ok(out)
+}
+
+/// Parse the document (JSON or JSONC) into a serde value
+pub fn to_value(text: &str) -> Result<serde_json::Value, String> {
+    Parser::new(text).parse_document()?;
+    serde_json::from_str(&strip_comments(text)).map_err(|e| e.to_string())
+}
+
+/// Read the value at `pointer`, if present
+pub fn get(text: &str, pointer: &str) -> Result<Option<serde_json::Value>, String> {
+    Ok(to_value(text)?.pointer(pointer).cloned())
+}
+
+// ============================================================================
+// File Helpers
+// ============================================================================
+
+/// Resolve symlinks so edits land in the link target instead of replacing the link
+fn resolve_write_target(path: &Path) -> Result<PathBuf, String> {
+    if path.is_symlink() {
+        fs::canonicalize(path).map_err(|e| format!("Failed to resolve symlink {}: {}", path.display(), e))
+    } else {
+        Ok(path.to_path_buf())
+    }
+}
+
+/// Atomically replace `path` with `updated`, refusing if the file on disk no
+/// longer matches `original` (None means the file did not exist)
+pub fn write_if_unchanged(path: &Path, original: Option<&str>, updated: &str) -> Result<(), String> {
+    let target = resolve_write_target(path)?;
+    let current = fs::read_to_string(&target).ok();
+    if current.as_deref() != original {
+        return Err(format!("{} was modified by another process; reload and try again", path.display()));
+    }
+
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let file_name = target.file_name().ok_or("Invalid file path")?.to_string_lossy();
+    let tmp_path = target.with_file_name(format!(".{}.agent-studio.tmp", file_name));
+
+    fs::write(&tmp_path, updated).map_err(|e| format!("Failed to write {}: {}", tmp_path.display(), e))?;
+    if let Ok(metadata) = fs::metadata(&target) {
+        let _ = fs::set_permissions(&tmp_path, metadata.permissions());
+    }
+    fs::rename(&tmp_path, &target).map_err(|e| {
+        let _ = fs::remove_file(&tmp_path);
+        format!("Failed to replace {}: {}", target.display(), e)
+    })
+}
+
+/// Read a JSON file, apply `edit` to its text and write it back atomically.
+/// A missing file is edited as an empty object.
+pub fn edit_file<F>(path: &Path, edit: F) -> Result<(), String>
+where
+    F: FnOnce(&str) -> Result<String, String>,
+{
+    let original = match fs::read_to_string(path) {
+        Ok(content) => Some(content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+    let source = match original.as_deref() {
+        Some(content) if !content.trim().is_empty() => content.to_string(),
+        _ => "{}\n".to_string(),
+    };
+
+    let updated = edit(&source)?;
+    if original.as_deref() == Some(updated.as_str()) {
+        return Ok(());
+    }
+    to_value(&updated).map_err(|e| format!("Edit produced invalid JSON: {}", e))?;
+    write_if_unchanged(path, original.as_deref(), &updated)
+}
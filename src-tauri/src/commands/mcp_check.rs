@@ -0,0 +1,246 @@
+// ============================================================================
+// Agent Studio - MCP Config Checks
+// Static resolvability checks for discovered MCP servers: commands on PATH,
+// unset environment variable placeholders and unknown transport types
+// ============================================================================
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use super::hook_audit::{find_on_path, is_executable};
+use super::mcp_probe::expand_placeholders;
+use super::{generate_id, get_home_dir, McpServerEntity, SettingsEntity, Severity};
+
+/// `type` values Claude Code accepts in .mcp.json / ~/.claude.json
+const CLAUDE_TRANSPORTS: &[&str] = &["stdio", "http", "sse"];
+
+/// `type` values OpenCode accepts under `mcp`
+const OPENCODE_TRANSPORTS: &[&str] = &["local", "remote"];
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct McpFinding {
+    pub id: String,
+    pub kind: String,  // "command_not_found", "command_not_executable", "unset_env_var", "unknown_transport", "incomplete_config"
+    pub severity: Severity,
+    pub title: String,
+    pub description: String,
+    pub file: String,
+    pub server_id: String,
+    pub server_name: String,
+    pub scope: String,
+    pub tool: String,
+    pub field: Option<String>,  // e.g. "args[2]", "env.GITHUB_TOKEN", "headers.Authorization"
+    pub plugin_name: Option<String>,
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+/// The project a server belongs to: the directory holding .mcp.json / opencode.json
+fn server_project_dir(server: &McpServerEntity) -> Option<PathBuf> {
+    if server.scope != "project" && server.scope != "local" {
+        return None;
+    }
+    if let Some(project) = &server.project_path {
+        return Some(PathBuf::from(project));
+    }
+    let dir = Path::new(&server.source_path).parent()?;
+    match dir.file_name().and_then(|n| n.to_str()) {
+        Some(".claude") | Some(".opencode") => dir.parent().map(Path::to_path_buf),
+        _ => Some(dir.to_path_buf()),
+    }
+}
+
+/// Variables defined by `env` in the settings files that apply to a server
+fn settings_env_vars(settings: &[SettingsEntity], project_dir: Option<&Path>) -> HashSet<String> {
+    settings.iter()
+        .filter(|s| s.base.tool == "claude")
+        .filter(|s| {
+            s.base.scope == "global"
+                || project_dir.is_some_and(|dir| s.base.project_path.as_deref().map(Path::new) == Some(dir))
+        })
+        .filter_map(|s| s.parsed.as_ref()?.get("env")?.as_object())
+        .flat_map(|env| env.keys().cloned())
+        .collect()
+}
+
+/// Every string in the config that undergoes placeholder expansion, with its field label
+fn expandable_fields(server: &McpServerEntity) -> Vec<(String, &str)> {
+    let config = &server.config;
+    let mut fields = Vec::new();
+    if let Some(command) = &config.command {
+        fields.push(("command".to_string(), command.as_str()));
+    }
+    for (i, arg) in config.args.iter().flatten().enumerate() {
+        fields.push((format!("args[{}]", i), arg.as_str()));
+    }
+    if let Some(url) = &config.url {
+        fields.push(("url".to_string(), url.as_str()));
+    }
+    for (key, value) in config.env.iter().flatten() {
+        fields.push((format!("env.{}", key), value.as_str()));
+    }
+    for (key, value) in config.headers.iter().flatten() {
+        fields.push((format!("headers.{}", key), value.as_str()));
+    }
+    fields
+}
+
+static OPENCODE_PLACEHOLDER: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\{env:([^}]+)\}").unwrap());
+
+/// Unset variables referenced in `text`. Claude Code uses `${VAR}` / `${VAR:-default}`,
+/// OpenCode uses `{env:VAR}` (which silently becomes an empty string).
+fn unset_placeholders(text: &str, tool: &str, is_set: &dyn Fn(&str) -> bool) -> Vec<String> {
+    if tool == "opencode" {
+        return OPENCODE_PLACEHOLDER.captures_iter(text)
+            .map(|c| c[1].trim().to_string())
+            .filter(|name| !is_set(name))
+            .collect();
+    }
+    expand_placeholders(text, &|name| is_set(name).then(String::new)).1
+}
+
+/// (kind, severity, title, description) if the server's command can't be run
+fn check_command(command: &str, project_dir: Option<&Path>, home: Option<&Path>) -> Option<(&'static str, Severity, String, String)> {
+    let path = Path::new(command);
+    let resolved = if path.is_absolute() {
+        Some(path.to_path_buf())
+    } else if command.contains('/') || command.contains('\\') {
+        project_dir.map(|dir| dir.join(command.trim_start_matches("./")))
+    } else {
+        return (!find_on_path(command, home)).then(|| (
+            "command_not_found",
+            Severity::Warning,
+            format!("`{}` is not on PATH", command),
+            format!("The server will fail to start unless `{}` is installed", command),
+        ));
+    };
+
+    let path = resolved?;
+    if !path.exists() {
+        Some((
+            "command_not_found",
+            Severity::Error,
+            format!("Command not found: {}", command),
+            format!("{} does not exist", path.display()),
+        ))
+    } else if !is_executable(&path) {
+        Some((
+            "command_not_executable",
+            Severity::Error,
+            format!("Command is not executable: {}", command),
+            format!("{} exists but is not executable (chmod +x)", path.display()),
+        ))
+    } else {
+        None
+    }
+}
+
+fn check_server(server: &McpServerEntity, settings: &[SettingsEntity], home: Option<&Path>) -> Vec<(&'static str, Severity, String, String, Option<String>)> {
+    let mut issues = Vec::new();
+    let config = &server.config;
+    let project_dir = server_project_dir(server);
+
+    // Transport type
+    let known = if server.tool == "opencode" { OPENCODE_TRANSPORTS } else { CLAUDE_TRANSPORTS };
+    if let Some(kind) = config.transport_type.as_deref().filter(|t| !known.contains(t)) {
+        issues.push((
+            "unknown_transport",
+            Severity::Error,
+            format!("Unknown transport type \"{}\"", kind),
+            format!("Expected one of: {}", known.join(", ")),
+            Some("type".to_string()),
+        ));
+    }
+    let needs_command = matches!(server.transport.as_str(), "stdio" | "local");
+    let needs_url = matches!(server.transport.as_str(), "http" | "sse" | "remote");
+    if config.command.is_none() && config.url.is_none() {
+        issues.push((
+            "incomplete_config",
+            Severity::Error,
+            "Server has neither a command nor a url".to_string(),
+            "Add a `command` for a local (stdio) server or a `url` for a remote one".to_string(),
+            None,
+        ));
+    } else if needs_command && config.command.is_none() {
+        issues.push((
+            "incomplete_config",
+            Severity::Error,
+            format!("{} server has no command", server.transport),
+            "Local servers are started from `command`".to_string(),
+            Some("command".to_string()),
+        ));
+    } else if needs_url && config.url.is_none() {
+        issues.push((
+            "incomplete_config",
+            Severity::Error,
+            format!("{} server has no url", server.transport),
+            "Remote servers are reached through `url`".to_string(),
+            Some("url".to_string()),
+        ));
+    }
+
+    // Placeholders with no value (process env or settings `env`)
+    let settings_env = settings_env_vars(settings, project_dir.as_deref());
+    let is_set = |name: &str| std::env::var_os(name).is_some() || settings_env.contains(name);
+    let mut reported = HashSet::new();
+    for (field, text) in expandable_fields(server) {
+        for var in unset_placeholders(text, &server.tool, &is_set) {
+            if reported.insert(var.clone()) {
+                issues.push((
+                    "unset_env_var",
+                    Severity::Warning,
+                    if server.tool == "opencode" { format!("{{env:{}}} is not set", var) } else { format!("${{{}}} is not set", var) },
+                    format!("{} references {} but it is not in the environment or settings `env`", field, var),
+                    Some(field.clone()),
+                ));
+            }
+        }
+    }
+
+    // Command resolution, after expanding whatever placeholders can be expanded
+    if let Some(command) = &config.command {
+        let lookup = |name: &str| std::env::var(name).ok();
+        let (expanded, missing) = expand_placeholders(command, &lookup);
+        if missing.is_empty() && !expanded.is_empty() {
+            if let Some((kind, severity, title, description)) = check_command(&expanded, project_dir.as_deref(), home) {
+                issues.push((kind, severity, title, description, Some("command".to_string())));
+            }
+        }
+    }
+
+    issues
+}
+
+/// Check every discovered MCP server config
+pub(crate) fn check_mcp_servers_internal(servers: &[McpServerEntity], settings: &[SettingsEntity]) -> Vec<McpFinding> {
+    let home = get_home_dir();
+    let mut findings = Vec::new();
+    for server in servers {
+        for (kind, severity, title, description, field) in check_server(server, settings, home.as_deref()) {
+            findings.push(McpFinding {
+                id: generate_id("mcpcheck", &format!("{}#{}#{}", server.id, kind, description)),
+                kind: kind.to_string(),
+                severity,
+                title,
+                description,
+                file: server.source_path.clone(),
+                server_id: server.id.clone(),
+                server_name: server.name.clone(),
+                scope: server.scope.clone(),
+                tool: server.tool.clone(),
+                field,
+                plugin_name: server.plugin_name.clone(),
+            });
+        }
+    }
+    findings
+}
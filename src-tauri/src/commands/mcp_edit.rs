@@ -0,0 +1,418 @@
+// ============================================================================
+// Agent Studio - MCP Server Editing
+// Write MCP server entries into Claude Code and OpenCode config files,
+// converting between their schemas
+// ============================================================================
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::json_edit;
+use super::{get_home_dir, opencode_server_config, McpServerConfig, McpServerEntity};
+
+/// Keys of an entry the editor manages; anything else (OpenCode's `enabled`
+/// or `timeout`, for instance) is left alone on update
+const CLAUDE_KEYS: &[&str] = &["type", "command", "args", "env", "url", "headers"];
+const OPENCODE_KEYS: &[&str] = &["type", "command", "args", "environment", "env", "url", "headers"];
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct McpWriteResult {
+    pub name: String,
+    pub tool: String,
+    pub scope: String,
+    pub file: String,
+    pub config: serde_json::Value,  // The entry as written, in the target tool's schema
+    pub warnings: Vec<String>,
+}
+
+/// A config file and the JSON pointer of the object holding its servers
+pub(crate) struct McpLocation {
+    pub file: PathBuf,
+    pub container: Vec<String>,
+}
+
+impl McpLocation {
+    pub fn pointer(&self, name: &str) -> String {
+        let mut tokens: Vec<&str> = self.container.iter().map(String::as_str).collect();
+        tokens.push(name);
+        json_edit::build_pointer(&tokens)
+    }
+}
+
+// ============================================================================
+// Locations
+// ============================================================================
+
+fn require_project(project_path: Option<&str>, scope: &str) -> Result<PathBuf, String> {
+    project_path
+        .map(PathBuf::from)
+        .ok_or_else(|| format!("A project path is required for {} scope", scope))
+}
+
+/// opencode.json, or opencode.jsonc when that's the one that exists
+fn opencode_config_file(dir: &Path) -> PathBuf {
+    let json = dir.join("opencode.json");
+    let jsonc = dir.join("opencode.jsonc");
+    if !json.exists() && jsonc.exists() { jsonc } else { json }
+}
+
+/// Resolve where servers for a tool and scope are stored:
+/// - claude/user: ~/.claude.json `mcpServers`
+/// - claude/project: <project>/.mcp.json `mcpServers`
+/// - claude/local: ~/.claude.json `projects[<project>].mcpServers`
+/// - opencode/global: ~/.config/opencode/opencode.json(c) `mcp`
+/// - opencode/project: <project>/opencode.json(c) (or .opencode/) `mcp`
+pub(crate) fn mcp_location(tool: &str, scope: &str, project_path: Option<&str>) -> Result<McpLocation, String> {
+    let home = get_home_dir().ok_or("Could not find home directory")?;
+    match (tool, scope) {
+        ("claude", "user") | ("claude", "global") => Ok(McpLocation {
+            file: home.join(".claude.json"),
+            container: vec!["mcpServers".to_string()],
+        }),
+        ("claude", "project") => Ok(McpLocation {
+            file: require_project(project_path, scope)?.join(".mcp.json"),
+            container: vec!["mcpServers".to_string()],
+        }),
+        ("claude", "local") => {
+            let project = require_project(project_path, scope)?;
+            Ok(McpLocation {
+                file: home.join(".claude.json"),
+                container: vec!["projects".to_string(), project.to_string_lossy().to_string(), "mcpServers".to_string()],
+            })
+        }
+        ("opencode", "user") | ("opencode", "global") => Ok(McpLocation {
+            file: opencode_config_file(&home.join(".config").join("opencode")),
+            container: vec!["mcp".to_string()],
+        }),
+        ("opencode", "project") => {
+            let project = require_project(project_path, scope)?;
+            let root = opencode_config_file(&project);
+            let nested = opencode_config_file(&project.join(".opencode"));
+            // Prefer the file that already exists, defaulting to the project root
+            let file = if !root.exists() && nested.exists() { nested } else { root };
+            Ok(McpLocation { file, container: vec!["mcp".to_string()] })
+        }
+        ("opencode", _) => Err(format!("OpenCode has no \"{}\" scope (use global or project)", scope)),
+        ("claude", _) => Err(format!("Claude Code has no \"{}\" scope (use user, project or local)", scope)),
+        _ => Err(format!("Unknown tool \"{}\"", tool)),
+    }
+}
+
+/// The location a discovered server was read from. Local-scope servers live
+/// under `projects[...]` in ~/.claude.json, and older .mcp.json files list
+/// servers at the top level instead of under `mcpServers`.
+pub(crate) fn entity_location(server: &McpServerEntity, text: &str) -> Result<McpLocation, String> {
+    if server.is_from_plugin {
+        return Err(format!(
+            "\"{}\" is provided by plugin {} and can't be edited here",
+            server.name,
+            server.plugin_name.as_deref().unwrap_or("?")
+        ));
+    }
+    let file = PathBuf::from(&server.source_path);
+    if server.tool == "claude" && server.scope == "local" {
+        // Use the projects[...] key exactly as Claude Code wrote it
+        let project = server.project_path.as_deref().ok_or("Local MCP server has no project path")?;
+        let projects = json_edit::get(text, "/projects")?.unwrap_or_default();
+        let key = projects.as_object()
+            .and_then(|p| p.keys().find(|k| k.trim_end_matches('/') == project.trim_end_matches('/')).cloned())
+            .ok_or_else(|| format!("{} has no entry for {}", file.display(), project))?;
+        return Ok(McpLocation { file, container: vec!["projects".to_string(), key, "mcpServers".to_string()] });
+    }
+    let key = if server.tool == "opencode" { "mcp" } else { "mcpServers" };
+    let location = McpLocation { file, container: vec![key.to_string()] };
+    if server.tool == "claude"
+        && json_edit::get(text, &location.pointer(&server.name))?.is_none()
+        && json_edit::get(text, &json_edit::build_pointer(&[&server.name]))?.is_some()
+    {
+        return Ok(McpLocation { file: location.file, container: Vec::new() });
+    }
+    Ok(location)
+}
+
+// ============================================================================
+// Schema Conversion
+// ============================================================================
+
+/// Rewrite env placeholders for the target tool: Claude Code expands `${VAR}`
+/// and `${VAR:-default}`, OpenCode expands `{env:VAR}` and has no defaults
+fn convert_placeholders(text: &str, target_tool: &str, warnings: &mut Vec<String>) -> String {
+    if target_tool == "opencode" {
+        let re = Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)(?::-([^}]*))?\}").unwrap();
+        re.replace_all(text, |caps: &regex::Captures| {
+            if let Some(default) = caps.get(2) {
+                let warning = format!("OpenCode has no placeholder defaults; dropped \"{}\" for {}", default.as_str(), &caps[1]);
+                if !warnings.contains(&warning) {
+                    warnings.push(warning);
+                }
+            }
+            format!("{{env:{}}}", &caps[1])
+        }).to_string()
+    } else {
+        let re = Regex::new(r"\{env:([^}]+)\}").unwrap();
+        re.replace_all(text, |caps: &regex::Captures| format!("${{{}}}", caps[1].trim())).to_string()
+    }
+}
+
+/// Whether a server runs as a local process (stdio / OpenCode "local")
+fn is_local(transport: &str, config: &McpServerConfig) -> bool {
+    match transport {
+        "stdio" | "local" => true,
+        "http" | "sse" | "remote" => false,
+        _ => config.command.is_some(),
+    }
+}
+
+/// Build an entry in the target tool's schema from a (tool-neutral) config
+pub(crate) fn convert_config(
+    config: &McpServerConfig,
+    transport: &str,
+    target_tool: &str,
+    warnings: &mut Vec<String>,
+) -> Result<serde_json::Value, String> {
+    let mut convert = |text: &str| convert_placeholders(text, target_tool, warnings);
+    let args: Vec<String> = config.args.iter().flatten().map(|a| convert(a)).collect();
+    let env: HashMap<String, String> = config.env.iter().flatten().map(|(k, v)| (k.clone(), convert(v))).collect();
+    let headers: HashMap<String, String> = config.headers.iter().flatten().map(|(k, v)| (k.clone(), convert(v))).collect();
+    let mut entry = serde_json::Map::new();
+
+    if is_local(transport, config) {
+        let command = config.command.as_deref().map(&mut convert).ok_or("Local MCP server has no command")?;
+        if target_tool == "opencode" {
+            // OpenCode takes the command and its arguments as one array
+            let mut argv = vec![command];
+            argv.extend(args);
+            entry.insert("type".to_string(), "local".into());
+            entry.insert("command".to_string(), serde_json::json!(argv));
+            if !env.is_empty() {
+                entry.insert("environment".to_string(), serde_json::json!(sorted(env)));
+            }
+        } else {
+            entry.insert("type".to_string(), "stdio".into());
+            entry.insert("command".to_string(), command.into());
+            entry.insert("args".to_string(), serde_json::json!(args));
+            if !env.is_empty() {
+                entry.insert("env".to_string(), serde_json::json!(sorted(env)));
+            }
+        }
+    } else {
+        let url = config.url.as_deref().map(&mut convert).ok_or("Remote MCP server has no url")?;
+        let remote_type = match (target_tool, transport) {
+            ("opencode", _) => "remote",
+            (_, "sse") => "sse",
+            _ => "http",
+        };
+        if target_tool == "opencode" && transport == "sse" {
+            warnings.push("OpenCode remote servers try streamable HTTP first and fall back to SSE".to_string());
+        }
+        entry.insert("type".to_string(), remote_type.into());
+        entry.insert("url".to_string(), url.into());
+        if !headers.is_empty() {
+            entry.insert("headers".to_string(), serde_json::json!(sorted(headers)));
+        }
+    }
+    Ok(serde_json::Value::Object(entry))
+}
+
+/// Transport implied by a config that may not spell out its `type`
+pub(crate) fn config_transport(config: &McpServerConfig) -> String {
+    config.transport_type.clone().unwrap_or_else(|| {
+        if config.command.is_some() { "stdio" } else { "http" }.to_string()
+    })
+}
+
+/// Read a raw entry the way discovery does
+fn raw_config(tool: &str, raw: &serde_json::Value) -> McpServerConfig {
+    if tool == "opencode" {
+        return opencode_server_config(raw);
+    }
+    serde_json::from_value(raw.clone()).unwrap_or(McpServerConfig {
+        transport_type: None,
+        command: None,
+        args: None,
+        url: None,
+        env: None,
+        headers: None,
+    })
+}
+
+/// Apply a converted entry on top of the existing one, keeping unmanaged keys
+/// and key order, and not adding defaults the original left implicit
+fn merge_entry(current: &serde_json::Value, entry: &serde_json::Value, tool: &str) -> serde_json::Value {
+    let managed = if tool == "opencode" { OPENCODE_KEYS } else { CLAUDE_KEYS };
+    let mut merged = current.as_object().cloned().unwrap_or_default();
+    let entry = entry.as_object().cloned().unwrap_or_default();
+    for key in managed {
+        if !entry.contains_key(*key) {
+            merged.shift_remove(*key);
+        }
+    }
+    for (key, value) in entry {
+        let implicit = (tool == "claude" && key == "type" && value == "stdio")
+            || value.as_array().is_some_and(|a| a.is_empty())
+            || value.as_object().is_some_and(|o| o.is_empty());
+        if implicit && !merged.contains_key(&key) {
+            continue;
+        }
+        merged.insert(key, value);
+    }
+    serde_json::Value::Object(merged)
+}
+
+/// Deterministic key order for maps written to disk
+fn sorted(map: HashMap<String, String>) -> std::collections::BTreeMap<String, String> {
+    map.into_iter().collect()
+}
+
+// ============================================================================
+// Writes
+// ============================================================================
+
+/// Server names are used as tool prefixes (mcp__<name>__tool), so keep them simple
+pub(crate) fn validate_server_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(format!("Invalid MCP server name \"{}\": use letters, digits, hyphens and underscores", name));
+    }
+    Ok(())
+}
+
+/// The entry currently on disk for `server`, refusing if it was removed or
+/// changed since the server was discovered
+fn current_entry(text: &str, location: &McpLocation, server: &McpServerEntity) -> Result<serde_json::Value, String> {
+    let current = json_edit::get(text, &location.pointer(&server.name))?
+        .ok_or_else(|| format!("\"{}\" no longer exists in {}; reload and try again", server.name, location.file.display()))?;
+    let on_disk = serde_json::to_value(raw_config(&server.tool, &current)).map_err(|e| e.to_string())?;
+    let loaded = serde_json::to_value(&server.config).map_err(|e| e.to_string())?;
+    if on_disk != loaded {
+        return Err(format!("\"{}\" was changed in {} since it was loaded; reload and try again", server.name, location.file.display()));
+    }
+    Ok(current)
+}
+
+/// Write `entry` under `name`, refusing to replace an existing server unless `overwrite`
+fn write_server(location: &McpLocation, name: &str, entry: &serde_json::Value, overwrite: bool) -> Result<(), String> {
+    let pointer = location.pointer(name);
+    json_edit::edit_file(&location.file, |text| {
+        if !overwrite && json_edit::get(text, &pointer)?.is_some() {
+            return Err(format!("An MCP server named \"{}\" already exists in {}", name, location.file.display()));
+        }
+        json_edit::set(text, &pointer, entry)
+    })
+}
+
+// ============================================================================
+// Public Tauri Commands
+// ============================================================================
+
+/// Copy a discovered MCP server into another tool and/or scope, converting
+/// between the Claude Code and OpenCode schemas. Fails on a name collision
+/// unless `overwrite` is set.
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn copy_mcp_server(
+    server: McpServerEntity,
+    target_tool: String,
+    target_scope: String,
+    project_path: Option<String>,
+    new_name: Option<String>,
+    overwrite: Option<bool>,
+) -> Result<McpWriteResult, String> {
+    let name = new_name.filter(|n| !n.trim().is_empty()).unwrap_or_else(|| server.name.clone());
+    validate_server_name(&name)?;
+    let location = mcp_location(&target_tool, &target_scope, project_path.as_deref())?;
+
+    let mut warnings = Vec::new();
+    let entry = convert_config(&server.config, &server.transport, &target_tool, &mut warnings)?;
+    write_server(&location, &name, &entry, overwrite.unwrap_or(false))?;
+
+    Ok(McpWriteResult {
+        name,
+        tool: target_tool,
+        scope: target_scope,
+        file: location.file.to_string_lossy().to_string(),
+        config: entry,
+        warnings,
+    })
+}
+
+/// Add a server to a tool's config for the given scope. Fails if the name is taken.
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn add_mcp_server(
+    tool: String,
+    scope: String,
+    project_path: Option<String>,
+    name: String,
+    config: McpServerConfig,
+) -> Result<McpWriteResult, String> {
+    validate_server_name(&name)?;
+    let location = mcp_location(&tool, &scope, project_path.as_deref())?;
+    let mut warnings = Vec::new();
+    let entry = convert_config(&config, &config_transport(&config), &tool, &mut warnings)?;
+    write_server(&location, &name, &entry, false)?;
+
+    Ok(McpWriteResult {
+        name,
+        tool,
+        scope,
+        file: location.file.to_string_lossy().to_string(),
+        config: entry,
+        warnings,
+    })
+}
+
+/// Replace a discovered server's config (and optionally rename it), patching
+/// only its entry. Fails if the entry changed on disk since discovery.
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn update_mcp_server(
+    server: McpServerEntity,
+    config: McpServerConfig,
+    new_name: Option<String>,
+) -> Result<McpWriteResult, String> {
+    let name = new_name.filter(|n| !n.trim().is_empty()).unwrap_or_else(|| server.name.clone());
+    validate_server_name(&name)?;
+    let mut warnings = Vec::new();
+    let entry = convert_config(&config, &config_transport(&config), &server.tool, &mut warnings)?;
+
+    let file = PathBuf::from(&server.source_path);
+    let mut written = serde_json::Value::Null;
+    json_edit::edit_file(&file, |text| {
+        let location = entity_location(&server, text)?;
+        let current = current_entry(text, &location, &server)?;
+        let merged = merge_entry(&current, &entry, &server.tool);
+        let mut text = text.to_string();
+        if name != server.name {
+            if json_edit::get(&text, &location.pointer(&name))?.is_some() {
+                return Err(format!("An MCP server named \"{}\" already exists in {}", name, file.display()));
+            }
+            text = json_edit::remove(&text, &location.pointer(&server.name))?;
+        }
+        let updated = json_edit::set(&text, &location.pointer(&name), &merged)?;
+        written = merged;
+        Ok(updated)
+    })?;
+
+    Ok(McpWriteResult {
+        name,
+        tool: server.tool.clone(),
+        scope: server.scope.clone(),
+        file: server.source_path.clone(),
+        config: written,
+        warnings,
+    })
+}
+
+/// Remove a discovered server from its config file. Fails if the entry changed
+/// on disk since discovery.
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn remove_mcp_server(server: McpServerEntity) -> Result<(), String> {
+    json_edit::edit_file(&PathBuf::from(&server.source_path), |text| {
+        let location = entity_location(&server, text)?;
+        current_entry(text, &location, &server)?;
+        json_edit::remove(text, &location.pointer(&server.name))
+    })
+}
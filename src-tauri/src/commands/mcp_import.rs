@@ -0,0 +1,398 @@
+// ============================================================================
+// Agent Studio - MCP Server Import
+// Read MCP servers configured for Claude Desktop, VS Code and Cursor and
+// import them into Claude Code or OpenCode config files
+// ============================================================================
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use super::json_edit;
+use super::mcp_edit::{config_transport, convert_config, mcp_location, validate_server_name, McpWriteResult};
+use super::{
+    claude_json_project_entry, discover_mcp_from_claude_json, discover_mcp_from_claude_json_project,
+    discover_mcp_from_opencode_json, discover_mcp_from_project, get_config_dir, get_home_dir, parse_json_file,
+    read_file_content, McpServerConfig, McpServerEntity,
+};
+
+/// Source apps the importer knows about
+const IMPORT_SOURCES: &[&str] = &["claude_desktop", "vscode", "cursor"];
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct McpImportConflict {
+    pub server_id: String,
+    pub tool: String,
+    pub scope: String,
+    pub source_path: String,
+    pub same_config: bool,  // The existing server already runs the same command / url
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct McpImportCandidate {
+    pub source: String,  // "claude_desktop", "vscode", "cursor"
+    pub source_path: String,
+    pub name: String,  // Name to import under; the frontend may change it before applying
+    pub original_name: String,
+    pub transport: String,
+    pub config: McpServerConfig,  // Tool-neutral, with Claude Code `${VAR}` placeholders
+    pub exists_in_target: bool,  // Applying would replace a server in the target file
+    pub conflicts: Vec<McpImportConflict>,  // Servers with the same name anywhere else
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct McpImportPreview {
+    pub target_tool: String,
+    pub target_scope: String,
+    pub target_file: String,
+    pub candidates: Vec<McpImportCandidate>,
+    pub scanned_files: Vec<String>,
+    pub errors: Vec<String>,  // Config files that exist but couldn't be read
+}
+
+/// A config file from another app and the pointer of the object holding its servers
+struct ImportFile {
+    source: &'static str,
+    path: PathBuf,
+    container: &'static [&'static str],
+    jsonc: bool,
+}
+
+// ============================================================================
+// Sources
+// ============================================================================
+
+/// Known config files, user-level first:
+/// - Claude Desktop: <config>/Claude/claude_desktop_config.json `mcpServers`
+/// - VS Code: <config>/Code/User/mcp.json `servers`, settings.json `mcp.servers`,
+///   <project>/.vscode/mcp.json `servers`
+/// - Cursor: ~/.cursor/mcp.json, <project>/.cursor/mcp.json `mcpServers`
+fn import_files(project_path: Option<&Path>) -> Vec<ImportFile> {
+    let mut files = Vec::new();
+    if let Some(config) = get_config_dir() {
+        files.push(ImportFile {
+            source: "claude_desktop",
+            path: config.join("Claude").join("claude_desktop_config.json"),
+            container: &["mcpServers"],
+            jsonc: false,
+        });
+        let vscode_user = config.join("Code").join("User");
+        files.push(ImportFile { source: "vscode", path: vscode_user.join("mcp.json"), container: &["servers"], jsonc: true });
+        files.push(ImportFile { source: "vscode", path: vscode_user.join("settings.json"), container: &["mcp", "servers"], jsonc: true });
+    }
+    if let Some(home) = get_home_dir() {
+        files.push(ImportFile {
+            source: "cursor",
+            path: home.join(".cursor").join("mcp.json"),
+            container: &["mcpServers"],
+            jsonc: false,
+        });
+    }
+    if let Some(project) = project_path {
+        files.push(ImportFile { source: "vscode", path: project.join(".vscode").join("mcp.json"), container: &["servers"], jsonc: true });
+        files.push(ImportFile { source: "cursor", path: project.join(".cursor").join("mcp.json"), container: &["mcpServers"], jsonc: false });
+    }
+    files
+}
+
+fn read_import_file(file: &ImportFile) -> Result<serde_json::Value, String> {
+    let parsed = if file.jsonc {
+        read_file_content(&file.path).and_then(|c| json_edit::to_value(&c).ok())
+    } else {
+        parse_json_file(&file.path)
+    };
+    parsed.ok_or_else(|| format!("Could not parse {}", file.path.display()))
+}
+
+/// Rewrite VS Code / Cursor variables into Claude Code placeholders:
+/// `${env:VAR}` becomes `${VAR}`, `${input:id}` (prompted by VS Code) becomes
+/// an env var, and workspace / home folders are resolved to absolute paths
+fn convert_variables(text: &str, project_path: Option<&Path>, home: Option<&Path>, warnings: &mut Vec<String>) -> String {
+    let re = Regex::new(r"\$\{(env|input|workspaceFolder|workspaceRoot|userHome)(?::([^}]+))?\}").unwrap();
+    let mut note = |warning: String| {
+        if !warnings.contains(&warning) {
+            warnings.push(warning);
+        }
+    };
+    re.replace_all(text, |caps: &regex::Captures| {
+        let arg = caps.get(2).map(|m| m.as_str().trim());
+        match (&caps[1], arg) {
+            ("env", Some(var)) => format!("${{{}}}", var),
+            ("input", Some(id)) => {
+                let var: String = id.chars()
+                    .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+                    .collect();
+                note(format!("VS Code prompts for input \"{}\"; it is read from ${{{}}} instead", id, var));
+                format!("${{{}}}", var)
+            }
+            ("workspaceFolder" | "workspaceRoot", None) => match project_path {
+                Some(project) => project.to_string_lossy().to_string(),
+                None => {
+                    note(format!("{} has no project to resolve to and was left as is", &caps[0]));
+                    caps[0].to_string()
+                }
+            },
+            ("userHome", None) => match home {
+                Some(home) => home.to_string_lossy().to_string(),
+                None => caps[0].to_string(),
+            },
+            _ => caps[0].to_string(),
+        }
+    }).to_string()
+}
+
+fn string_map(value: Option<&serde_json::Value>) -> Option<HashMap<String, String>> {
+    let map: HashMap<String, String> = value?.as_object()?
+        .iter()
+        .filter_map(|(k, v)| {
+            let text = match v {
+                serde_json::Value::String(s) => s.clone(),
+                serde_json::Value::Number(_) | serde_json::Value::Bool(_) => v.to_string(),
+                _ => return None,
+            };
+            Some((k.clone(), text))
+        })
+        .collect();
+    (!map.is_empty()).then_some(map)
+}
+
+/// Map a foreign entry onto the tool-neutral config. All three apps use
+/// `command`/`args`/`env` for local servers and `url`/`headers` for remote ones.
+fn import_config(
+    raw: &serde_json::Value,
+    project_path: Option<&Path>,
+    home: Option<&Path>,
+    warnings: &mut Vec<String>,
+) -> Result<McpServerConfig, String> {
+    let mut convert = |text: &str| convert_variables(text, project_path, home, warnings);
+    let command = raw.get("command").and_then(|c| c.as_str()).map(&mut convert);
+    let url = raw.get("url").and_then(|u| u.as_str()).map(&mut convert);
+    let args: Option<Vec<String>> = raw.get("args").and_then(|a| a.as_array()).map(|arr| {
+        arr.iter().filter_map(|a| a.as_str()).map(&mut convert).collect()
+    });
+    let env = string_map(raw.get("env")).map(|env| env.into_iter().map(|(k, v)| (k, convert(&v))).collect());
+    let headers = string_map(raw.get("headers")).map(|h| h.into_iter().map(|(k, v)| (k, convert(&v))).collect());
+
+    let transport_type = match raw.get("type").and_then(|t| t.as_str()) {
+        Some("stdio") => "stdio",
+        Some("sse") => "sse",
+        Some("http") | Some("streamable-http") => "http",
+        Some(other) => return Err(format!("Unsupported transport type \"{}\"", other)),
+        None if command.is_some() => "stdio",
+        // Cursor leaves `type` out and detects the transport; legacy SSE endpoints end in /sse
+        None if url.as_deref().is_some_and(|u| u.trim_end_matches('/').ends_with("/sse")) => "sse",
+        None if url.is_some() => "http",
+        None => return Err("Server has neither a command nor a url".to_string()),
+    };
+    if command.is_none() && transport_type == "stdio" {
+        return Err("stdio server has no command".to_string());
+    }
+    if url.is_none() && transport_type != "stdio" {
+        return Err(format!("{} server has no url", transport_type));
+    }
+
+    if raw.get("envFile").is_some() {
+        warnings.push("envFile is not supported; add its variables to env or settings".to_string());
+    }
+    if raw.get("cwd").is_some() {
+        warnings.push("cwd is not supported; the server starts in the project directory".to_string());
+    }
+    if raw.get("disabled").and_then(|d| d.as_bool()) == Some(true) {
+        warnings.push("The server is disabled in its source config".to_string());
+    }
+
+    Ok(McpServerConfig {
+        transport_type: Some(transport_type.to_string()),
+        command,
+        args,
+        url,
+        env,
+        headers,
+    })
+}
+
+// ============================================================================
+// Conflicts
+// ============================================================================
+
+/// Servers Claude Code and OpenCode already know about, user-level and for the project
+fn known_servers(project_path: Option<&Path>) -> Vec<McpServerEntity> {
+    let Some(home) = get_home_dir() else {
+        return Vec::new();
+    };
+    let mut servers = discover_mcp_from_claude_json(&home).unwrap_or_default();
+    servers.extend(discover_mcp_from_opencode_json(&home.join(".config").join("opencode"), "global", None).unwrap_or_default());
+    if let Some(project) = project_path {
+        let project_str = project.to_string_lossy().to_string();
+        let project = project.to_path_buf();
+        servers.extend(discover_mcp_from_project(&project).unwrap_or_default());
+        if let Some(claude_json) = parse_json_file(&home.join(".claude.json")) {
+            if let Some(entry) = claude_json_project_entry(&claude_json, &project_str) {
+                servers.extend(discover_mcp_from_claude_json_project(&home, &project_str, entry));
+            }
+        }
+        for dir in [project.join(".opencode"), project.clone()] {
+            servers.extend(discover_mcp_from_opencode_json(&dir, "project", Some(&project_str)).unwrap_or_default());
+        }
+    }
+    servers
+}
+
+/// Same command line or endpoint, ignoring env / headers
+fn same_server(a: &McpServerConfig, b: &McpServerConfig) -> bool {
+    match (&a.command, &b.command) {
+        (Some(ca), Some(cb)) => {
+            let args = |c: &McpServerConfig| c.args.clone().unwrap_or_default();
+            ca == cb && args(a) == args(b)
+        }
+        (None, None) => a.url.is_some() && a.url == b.url,
+        _ => false,
+    }
+}
+
+// ============================================================================
+// Public Tauri Commands
+// ============================================================================
+
+/// Read MCP servers from Claude Desktop, VS Code and Cursor and preview
+/// importing them into `target_tool`/`target_scope`. `sources` limits which
+/// apps are read ("claude_desktop", "vscode", "cursor"); all by default.
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn preview_mcp_import(
+    target_tool: String,
+    target_scope: String,
+    project_path: Option<String>,
+    sources: Option<Vec<String>>,
+) -> Result<McpImportPreview, String> {
+    if let Some(unknown) = sources.iter().flatten().find(|s| !IMPORT_SOURCES.contains(&s.as_str())) {
+        return Err(format!("Unknown import source \"{}\"", unknown));
+    }
+    let location = mcp_location(&target_tool, &target_scope, project_path.as_deref())?;
+    let project = project_path.as_deref().map(Path::new);
+    let home = get_home_dir();
+    let target_text = std::fs::read_to_string(&location.file).ok();
+    let known = known_servers(project);
+
+    let mut candidates = Vec::new();
+    let mut scanned_files = Vec::new();
+    let mut errors = Vec::new();
+    for file in import_files(project) {
+        if sources.as_ref().is_some_and(|s| !s.iter().any(|s| s == file.source)) || !file.path.exists() {
+            continue;
+        }
+        let source_path = file.path.to_string_lossy().to_string();
+        scanned_files.push(source_path.clone());
+        let json = match read_import_file(&file) {
+            Ok(json) => json,
+            Err(e) => {
+                errors.push(e);
+                continue;
+            }
+        };
+        let servers = file.container.iter().try_fold(&json, |value, key| value.get(key)).and_then(|s| s.as_object());
+        for (name, raw) in servers.into_iter().flatten() {
+            let mut warnings = Vec::new();
+            let config = match import_config(raw, project, home.as_deref(), &mut warnings) {
+                Ok(config) => config,
+                Err(e) => {
+                    errors.push(format!("{} in {}: {}", name, source_path, e));
+                    continue;
+                }
+            };
+            if validate_server_name(name).is_err() {
+                warnings.push("The name has characters Claude Code and OpenCode don't allow; rename it before importing".to_string());
+            }
+            let exists_in_target = target_text.as_deref()
+                .and_then(|text| json_edit::get(text, &location.pointer(name)).ok().flatten())
+                .is_some();
+            let conflicts = known.iter()
+                .filter(|s| &s.name == name)
+                .map(|s| McpImportConflict {
+                    server_id: s.id.clone(),
+                    tool: s.tool.clone(),
+                    scope: s.scope.clone(),
+                    source_path: s.source_path.clone(),
+                    same_config: same_server(&s.config, &config),
+                })
+                .collect();
+            candidates.push(McpImportCandidate {
+                source: file.source.to_string(),
+                source_path: source_path.clone(),
+                name: name.clone(),
+                original_name: name.clone(),
+                transport: config_transport(&config),
+                config,
+                exists_in_target,
+                conflicts,
+                warnings,
+            });
+        }
+    }
+
+    Ok(McpImportPreview {
+        target_tool,
+        target_scope,
+        target_file: location.file.to_string_lossy().to_string(),
+        candidates,
+        scanned_files,
+        errors,
+    })
+}
+
+/// Import the chosen candidates into the target config in one write: either
+/// every server is added or, on any collision or error, none are. Servers
+/// already in the target are only replaced with `overwrite`.
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn apply_mcp_import(
+    candidates: Vec<McpImportCandidate>,
+    target_tool: String,
+    target_scope: String,
+    project_path: Option<String>,
+    overwrite: Option<bool>,
+) -> Result<Vec<McpWriteResult>, String> {
+    if candidates.is_empty() {
+        return Ok(Vec::new());
+    }
+    let location = mcp_location(&target_tool, &target_scope, project_path.as_deref())?;
+    let overwrite = overwrite.unwrap_or(false);
+
+    let mut names = HashSet::new();
+    let mut results = Vec::new();
+    for candidate in &candidates {
+        validate_server_name(&candidate.name)?;
+        if !names.insert(candidate.name.as_str()) {
+            return Err(format!("\"{}\" is selected more than once; rename one of them", candidate.name));
+        }
+        let mut warnings = candidate.warnings.clone();
+        let entry = convert_config(&candidate.config, &candidate.transport, &target_tool, &mut warnings)
+            .map_err(|e| format!("{}: {}", candidate.name, e))?;
+        results.push(McpWriteResult {
+            name: candidate.name.clone(),
+            tool: target_tool.clone(),
+            scope: target_scope.clone(),
+            file: location.file.to_string_lossy().to_string(),
+            config: entry,
+            warnings,
+        });
+    }
+
+    json_edit::edit_file(&location.file, |text| {
+        let mut text = text.to_string();
+        for result in &results {
+            let pointer = location.pointer(&result.name);
+            if !overwrite && json_edit::get(&text, &pointer)?.is_some() {
+                return Err(format!("An MCP server named \"{}\" already exists in {}", result.name, location.file.display()));
+            }
+            text = json_edit::set(&text, &pointer, &result.config)?;
+        }
+        Ok(text)
+    })?;
+
+    Ok(results)
+}
@@ -0,0 +1,781 @@
+// ============================================================================
+// Agent Studio - MCP Server Probe
+// Start (or connect to) an MCP server, run the initialize handshake and list
+// the tools, prompts and resources it offers
+// ============================================================================
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+
+use super::McpServerEntity;
+
+/// Protocol revision sent in `initialize`; servers answer with the one they speak
+pub(crate) const PROTOCOL_VERSION: &str = "2025-06-18";
+
+const DEFAULT_PROBE_TIMEOUT_MS: u64 = 30_000;
+
+/// Cap on captured stderr so a chatty server can't grow the result unbounded
+const MAX_STDERR_BYTES: usize = 64 * 1024;
+
+/// Cap on `nextCursor` pages fetched per list call
+const MAX_LIST_PAGES: usize = 20;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Where a probe stopped
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum McpProbeStage {
+    /// Spawning the process or opening the connection
+    Connect,
+    Initialize,
+    ListTools,
+    ListPrompts,
+    ListResources,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct McpToolInfo {
+    pub name: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub input_schema: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct McpPromptArgument {
+    pub name: String,
+    pub description: Option<String>,
+    pub required: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct McpPromptInfo {
+    pub name: String,
+    pub description: Option<String>,
+    pub arguments: Vec<McpPromptArgument>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct McpResourceInfo {
+    pub uri: String,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub mime_type: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct McpProbeResult {
+    pub server_name: String,
+    pub transport: String,  // Transport actually used: "stdio", "http" or "sse"
+    pub ok: bool,
+    pub failed_stage: Option<McpProbeStage>,
+    pub error: Option<String>,
+    pub protocol_version: Option<String>,
+    pub server_info: Option<serde_json::Value>,  // { name, version, title? }
+    pub capabilities: Option<serde_json::Value>,
+    pub instructions: Option<String>,
+    pub tools: Vec<McpToolInfo>,
+    pub prompts: Vec<McpPromptInfo>,
+    pub resources: Vec<McpResourceInfo>,
+    pub stderr: String,
+    pub exit_code: Option<i32>,
+    pub unresolved_vars: Vec<String>,  // ${VAR} placeholders with no value and no default
+    pub warnings: Vec<String>,
+    pub initialize_ms: Option<u64>,  // From spawn/connect to the initialize response
+    pub duration_ms: u64,
+}
+
+// ============================================================================
+// Placeholder Expansion
+// ============================================================================
+
+/// Expand `${VAR}` and `${VAR:-default}` the way Claude Code does for .mcp.json.
+/// Unset variables without a default are left in place and returned.
+pub(crate) fn expand_placeholders(text: &str, lookup: &dyn Fn(&str) -> Option<String>) -> (String, Vec<String>) {
+    let re = regex::Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)(?::-([^}]*))?\}").unwrap();
+    let mut missing = Vec::new();
+    let expanded = re.replace_all(text, |caps: &regex::Captures| {
+        match (lookup(&caps[1]), caps.get(2)) {
+            (Some(value), _) => value,
+            (None, Some(default)) => default.as_str().to_string(),
+            (None, None) => {
+                missing.push(caps[1].to_string());
+                caps[0].to_string()
+            }
+        }
+    });
+    (expanded.to_string(), missing)
+}
+
+/// Server config after placeholder expansion
+struct ResolvedServer {
+    command: Option<String>,
+    args: Vec<String>,
+    env: HashMap<String, String>,
+    url: Option<String>,
+    headers: HashMap<String, String>,
+    unresolved: Vec<String>,
+}
+
+fn resolve_server(server: &McpServerEntity) -> ResolvedServer {
+    let lookup = |name: &str| std::env::var(name).ok();
+    let mut unresolved = Vec::new();
+    let mut expand = |text: &str| {
+        let (value, missing) = expand_placeholders(text, &lookup);
+        for var in missing {
+            if !unresolved.contains(&var) {
+                unresolved.push(var);
+            }
+        }
+        value
+    };
+
+    let config = &server.config;
+    ResolvedServer {
+        command: config.command.as_deref().map(&mut expand),
+        args: config.args.iter().flatten().map(|a| expand(a)).collect(),
+        env: config.env.iter().flatten().map(|(k, v)| (k.clone(), expand(v))).collect(),
+        url: config.url.as_deref().map(&mut expand),
+        headers: config.headers.iter().flatten().map(|(k, v)| (k.clone(), expand(v))).collect(),
+        unresolved,
+    }
+}
+
+// ============================================================================
+// JSON-RPC Helpers
+// ============================================================================
+
+fn request_message(id: u64, method: &str, params: serde_json::Value) -> serde_json::Value {
+    serde_json::json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+}
+
+fn notification_message(method: &str) -> serde_json::Value {
+    serde_json::json!({ "jsonrpc": "2.0", "method": method })
+}
+
+/// The result (or error) if `message` is the response to request `id`
+fn match_response(message: &serde_json::Value, id: u64) -> Option<Result<serde_json::Value, String>> {
+    if message.get("method").is_some() || message.get("id").and_then(|i| i.as_u64()) != Some(id) {
+        return None;
+    }
+    if let Some(error) = message.get("error") {
+        let code = error.get("code").and_then(|c| c.as_i64()).unwrap_or(0);
+        let text = error.get("message").and_then(|m| m.as_str()).unwrap_or("unknown error");
+        return Some(Err(format!("JSON-RPC error {}: {}", code, text)));
+    }
+    Some(Ok(message.get("result").cloned().unwrap_or(serde_json::Value::Null)))
+}
+
+/// Answer a request the server sends us mid-probe (ping, roots/list, ...)
+fn reply_to_server_request(message: &serde_json::Value) -> Option<serde_json::Value> {
+    let id = message.get("id")?.clone();
+    let method = message.get("method")?.as_str()?;
+    Some(match method {
+        "ping" => serde_json::json!({ "jsonrpc": "2.0", "id": id, "result": {} }),
+        "roots/list" => serde_json::json!({ "jsonrpc": "2.0", "id": id, "result": { "roots": [] } }),
+        _ => serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": -32601, "message": format!("{} is not supported by the probe", method) }
+        }),
+    })
+}
+
+/// reqwest's Display hides the cause ("connection refused", DNS failure, ...)
+fn describe_error(error: &reqwest::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = std::error::Error::source(error);
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    if text.len() <= max {
+        return text.to_string();
+    }
+    let mut end = max;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}…", &text[..end])
+}
+
+// ============================================================================
+// Server-Sent Events
+// ============================================================================
+
+struct SseEvent {
+    event: String,
+    data: String,
+}
+
+/// Incremental SSE parser over a streaming HTTP response
+struct SseReader {
+    response: reqwest::Response,
+    buffer: Vec<u8>,
+}
+
+impl SseReader {
+    fn new(response: reqwest::Response) -> Self {
+        Self { response, buffer: Vec::new() }
+    }
+
+    async fn next_event(&mut self) -> Result<Option<SseEvent>, String> {
+        loop {
+            if let Some(pos) = self.buffer.windows(2).position(|w| w == b"\n\n") {
+                let block: Vec<u8> = self.buffer.drain(..pos + 2).collect();
+                let block = String::from_utf8_lossy(&block);
+                let mut event = String::new();
+                let mut data = Vec::new();
+                for line in block.lines() {
+                    if let Some(value) = line.strip_prefix("event:") {
+                        event = value.trim().to_string();
+                    } else if let Some(value) = line.strip_prefix("data:") {
+                        data.push(value.strip_prefix(' ').unwrap_or(value).to_string());
+                    }
+                }
+                if event.is_empty() && data.is_empty() {
+                    continue;  // Comment or keep-alive
+                }
+                return Ok(Some(SseEvent { event, data: data.join("\n") }));
+            }
+            match self.response.chunk().await.map_err(|e| format!("Event stream failed: {}", describe_error(&e)))? {
+                // CRLF and LF line endings are both allowed; normalize to LF
+                Some(bytes) => self.buffer.extend(bytes.iter().filter(|b| **b != b'\r')),
+                None => return Ok(None),
+            }
+        }
+    }
+
+    /// Next JSON-RPC message on the stream
+    async fn next_message(&mut self) -> Result<Option<serde_json::Value>, String> {
+        while let Some(event) = self.next_event().await? {
+            if event.event.is_empty() || event.event == "message" {
+                return serde_json::from_str(&event.data)
+                    .map(Some)
+                    .map_err(|e| format!("Server sent invalid JSON: {}", e));
+            }
+        }
+        Ok(None)
+    }
+}
+
+// ============================================================================
+// Connections
+// ============================================================================
+
+struct StdioConnection {
+    child: tokio::process::Child,
+    stdin: tokio::process::ChildStdin,
+    stdout: tokio::io::Lines<BufReader<tokio::process::ChildStdout>>,
+    stderr: Arc<Mutex<String>>,
+    stderr_task: tokio::task::JoinHandle<()>,
+    exit_code: Option<i32>,
+}
+
+/// Streamable HTTP: every message is a POST, replies come back as JSON or SSE
+struct HttpConnection {
+    client: reqwest::Client,
+    url: String,
+    headers: reqwest::header::HeaderMap,
+    session_id: Option<String>,
+    protocol_version: Option<String>,
+}
+
+/// Legacy HTTP+SSE: a long-lived GET stream plus a POST endpoint it announces
+struct SseConnection {
+    client: reqwest::Client,
+    post_url: String,
+    headers: reqwest::header::HeaderMap,
+    events: SseReader,
+}
+
+enum Connection {
+    Stdio(StdioConnection),
+    Http(HttpConnection),
+    Sse(SseConnection),
+}
+
+fn header_map(headers: &HashMap<String, String>) -> Result<reqwest::header::HeaderMap, String> {
+    let mut map = reqwest::header::HeaderMap::new();
+    for (key, value) in headers {
+        let name = reqwest::header::HeaderName::from_bytes(key.as_bytes())
+            .map_err(|_| format!("Invalid header name \"{}\"", key))?;
+        let value = reqwest::header::HeaderValue::from_str(value)
+            .map_err(|_| format!("Invalid value for header \"{}\"", key))?;
+        map.insert(name, value);
+    }
+    Ok(map)
+}
+
+impl StdioConnection {
+    fn spawn(resolved: &ResolvedServer, cwd: Option<&PathBuf>) -> Result<Self, String> {
+        let command = resolved.command.as_deref().ok_or("Server has no command")?;
+        let mut cmd = tokio::process::Command::new(command);
+        cmd.args(&resolved.args)
+            .envs(&resolved.env)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        if let Some(dir) = cwd {
+            cmd.current_dir(dir);
+        }
+        let mut child = cmd.spawn().map_err(|e| format!("Failed to start `{}`: {}", command, e))?;
+
+        let stdin = child.stdin.take().ok_or("Failed to open server stdin")?;
+        let stdout = BufReader::new(child.stdout.take().ok_or("Failed to open server stdout")?).lines();
+        let mut stderr_pipe = child.stderr.take().ok_or("Failed to open server stderr")?;
+        let stderr = Arc::new(Mutex::new(String::new()));
+        let sink = stderr.clone();
+        let stderr_task = tokio::spawn(async move {
+            let mut buf = [0u8; 4096];
+            while let Ok(n) = stderr_pipe.read(&mut buf).await {
+                if n == 0 {
+                    break;
+                }
+                let mut captured = sink.lock().unwrap();
+                if captured.len() < MAX_STDERR_BYTES {
+                    captured.push_str(&String::from_utf8_lossy(&buf[..n]));
+                }
+            }
+        });
+
+        Ok(Self { child, stdin, stdout, stderr, stderr_task, exit_code: None })
+    }
+
+    async fn send(&mut self, message: &serde_json::Value) -> Result<(), String> {
+        let mut line = serde_json::to_string(message).map_err(|e| e.to_string())?;
+        line.push('\n');
+        self.stdin.write_all(line.as_bytes()).await
+            .and(self.stdin.flush().await)
+            .map_err(|e| format!("Failed to write to server stdin: {}", e))
+    }
+
+    async fn receive(&mut self, warnings: &mut Vec<String>) -> Result<serde_json::Value, String> {
+        loop {
+            match self.stdout.next_line().await {
+                Ok(Some(line)) if line.trim().is_empty() => continue,
+                Ok(Some(line)) => match serde_json::from_str(&line) {
+                    Ok(message) => return Ok(message),
+                    // Logging to stdout corrupts the protocol stream; clients skip such lines
+                    Err(_) => {
+                        let warning = format!("Server wrote non-JSON output to stdout: {}", truncate(&line, 200));
+                        if !warnings.contains(&warning) {
+                            warnings.push(warning);
+                        }
+                    }
+                },
+                Ok(None) => {
+                    let status = tokio::time::timeout(Duration::from_secs(2), self.child.wait()).await;
+                    self.exit_code = status.ok().and_then(|s| s.ok()).and_then(|s| s.code());
+                    return Err(match self.exit_code {
+                        Some(code) => format!("Server exited with code {} before responding", code),
+                        None => "Server closed stdout before responding".to_string(),
+                    });
+                }
+                Err(e) => return Err(format!("Failed to read server stdout: {}", e)),
+            }
+        }
+    }
+
+    /// Close stdin, give the server a moment to exit, then kill it
+    async fn shutdown(mut self) -> (Option<i32>, String) {
+        drop(self.stdin);
+        if self.exit_code.is_none() {
+            match tokio::time::timeout(Duration::from_millis(500), self.child.wait()).await {
+                Ok(Ok(status)) => self.exit_code = status.code(),
+                _ => {
+                    let _ = self.child.kill().await;
+                }
+            }
+        }
+        let _ = tokio::time::timeout(Duration::from_secs(1), self.stderr_task).await;
+        let stderr = self.stderr.lock().unwrap().clone();
+        (self.exit_code, stderr)
+    }
+}
+
+impl HttpConnection {
+    async fn post(&mut self, message: &serde_json::Value) -> Result<reqwest::Response, String> {
+        let mut request = self.client.post(&self.url)
+            .headers(self.headers.clone())
+            .header(reqwest::header::ACCEPT, "application/json, text/event-stream")
+            .json(message);
+        if let Some(session) = &self.session_id {
+            request = request.header("Mcp-Session-Id", session);
+        }
+        if let Some(version) = &self.protocol_version {
+            request = request.header("MCP-Protocol-Version", version);
+        }
+        let response = request.send().await.map_err(|e| format!("Request to {} failed: {}", self.url, describe_error(&e)))?;
+        if let Some(session) = response.headers().get("mcp-session-id").and_then(|v| v.to_str().ok()) {
+            self.session_id = Some(session.to_string());
+        }
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(format!("HTTP {} from {}: {}", status, self.url, truncate(body.trim(), 500)));
+        }
+        Ok(response)
+    }
+
+    async fn request(&mut self, id: u64, message: &serde_json::Value) -> Result<serde_json::Value, String> {
+        let response = self.post(message).await?;
+        let is_stream = response.headers().get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("text/event-stream"));
+
+        if !is_stream {
+            let body: serde_json::Value = response.json().await.map_err(|e| format!("Server sent invalid JSON: {}", e))?;
+            let messages = match body {
+                serde_json::Value::Array(batch) => batch,
+                single => vec![single],
+            };
+            return messages.iter()
+                .find_map(|m| match_response(m, id))
+                .unwrap_or_else(|| Err("Response did not contain a reply to the request".to_string()));
+        }
+
+        let mut events = SseReader::new(response);
+        while let Some(message) = events.next_message().await? {
+            if let Some(result) = match_response(&message, id) {
+                return result;
+            }
+            if let Some(reply) = reply_to_server_request(&message) {
+                self.post(&reply).await?;
+            }
+        }
+        Err("Event stream ended before the server responded".to_string())
+    }
+}
+
+impl SseConnection {
+    async fn open(client: reqwest::Client, url: &str, headers: reqwest::header::HeaderMap) -> Result<Self, String> {
+        let response = client.get(url)
+            .headers(headers.clone())
+            .header(reqwest::header::ACCEPT, "text/event-stream")
+            .send()
+            .await
+            .map_err(|e| format!("Failed to connect to {}: {}", url, describe_error(&e)))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(format!("HTTP {} from {}: {}", status, url, truncate(body.trim(), 500)));
+        }
+
+        // The first `endpoint` event tells us where to POST messages
+        let mut events = SseReader::new(response);
+        let endpoint = loop {
+            match events.next_event().await? {
+                Some(event) if event.event == "endpoint" => break event.data,
+                Some(_) => continue,
+                None => return Err("Event stream closed before the server sent its endpoint".to_string()),
+            }
+        };
+        let post_url = reqwest::Url::parse(url)
+            .and_then(|base| base.join(endpoint.trim()))
+            .map_err(|e| format!("Invalid endpoint \"{}\": {}", endpoint, e))?;
+
+        Ok(Self { client, post_url: post_url.to_string(), headers, events })
+    }
+
+    async fn post(&self, message: &serde_json::Value) -> Result<(), String> {
+        let response = self.client.post(&self.post_url)
+            .headers(self.headers.clone())
+            .json(message)
+            .send()
+            .await
+            .map_err(|e| format!("Request to {} failed: {}", self.post_url, describe_error(&e)))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(format!("HTTP {} from {}: {}", status, self.post_url, truncate(body.trim(), 500)));
+        }
+        Ok(())
+    }
+}
+
+impl Connection {
+    fn transport(&self) -> &'static str {
+        match self {
+            Connection::Stdio(_) => "stdio",
+            Connection::Http(_) => "http",
+            Connection::Sse(_) => "sse",
+        }
+    }
+
+    async fn request(&mut self, id: u64, method: &str, params: serde_json::Value, warnings: &mut Vec<String>) -> Result<serde_json::Value, String> {
+        let message = request_message(id, method, params);
+        match self {
+            Connection::Stdio(conn) => {
+                conn.send(&message).await?;
+                loop {
+                    let incoming = conn.receive(warnings).await?;
+                    if let Some(result) = match_response(&incoming, id) {
+                        return result;
+                    }
+                    if let Some(reply) = reply_to_server_request(&incoming) {
+                        conn.send(&reply).await?;
+                    }
+                }
+            }
+            Connection::Http(conn) => conn.request(id, &message).await,
+            Connection::Sse(conn) => {
+                conn.post(&message).await?;
+                while let Some(incoming) = conn.events.next_message().await? {
+                    if let Some(result) = match_response(&incoming, id) {
+                        return result;
+                    }
+                    if let Some(reply) = reply_to_server_request(&incoming) {
+                        conn.post(&reply).await?;
+                    }
+                }
+                Err("Event stream ended before the server responded".to_string())
+            }
+        }
+    }
+
+    async fn notify(&mut self, method: &str) -> Result<(), String> {
+        let message = notification_message(method);
+        match self {
+            Connection::Stdio(conn) => conn.send(&message).await,
+            Connection::Http(conn) => conn.post(&message).await.map(|_| ()),
+            Connection::Sse(conn) => conn.post(&message).await,
+        }
+    }
+
+    /// Returns the exit code and stderr for stdio servers
+    async fn close(self) -> (Option<i32>, String) {
+        match self {
+            Connection::Stdio(conn) => conn.shutdown().await,
+            Connection::Http(conn) => {
+                // Politely end the session; servers that don't support it answer 405
+                if let Some(session) = &conn.session_id {
+                    let _ = conn.client.delete(&conn.url)
+                        .headers(conn.headers.clone())
+                        .header("Mcp-Session-Id", session)
+                        .send()
+                        .await;
+                }
+                (None, String::new())
+            }
+            Connection::Sse(_) => (None, String::new()),
+        }
+    }
+}
+
+// ============================================================================
+// Probe
+// ============================================================================
+
+async fn with_deadline<T>(deadline: Instant, what: &str, fut: impl std::future::Future<Output = Result<T, String>>) -> Result<T, String> {
+    let remaining = deadline.saturating_duration_since(Instant::now());
+    tokio::time::timeout(remaining, fut).await
+        .unwrap_or_else(|_| Err(format!("Timed out waiting for {}", what)))
+}
+
+async fn connect(
+    server: &McpServerEntity,
+    resolved: &ResolvedServer,
+    cwd: Option<&PathBuf>,
+) -> Result<Connection, String> {
+    if resolved.command.is_some() {
+        return StdioConnection::spawn(resolved, cwd).map(Connection::Stdio);
+    }
+    let url = resolved.url.clone().ok_or("Server has neither a command nor a url")?;
+    let client = reqwest::Client::new();
+    let headers = header_map(&resolved.headers)?;
+    if server.transport == "sse" {
+        return SseConnection::open(client, &url, headers).await.map(Connection::Sse);
+    }
+    Ok(Connection::Http(HttpConnection { client, url, headers, session_id: None, protocol_version: None }))
+}
+
+/// Fetch every page of a list method
+async fn list_all(
+    conn: &mut Connection,
+    next_id: &mut u64,
+    method: &str,
+    key: &str,
+    deadline: Instant,
+    warnings: &mut Vec<String>,
+) -> Result<Vec<serde_json::Value>, String> {
+    let mut items = Vec::new();
+    let mut cursor: Option<String> = None;
+    for _ in 0..MAX_LIST_PAGES {
+        let params = match &cursor {
+            Some(c) => serde_json::json!({ "cursor": c }),
+            None => serde_json::json!({}),
+        };
+        *next_id += 1;
+        let id = *next_id;
+        let result = with_deadline(deadline, method, conn.request(id, method, params, warnings)).await?;
+        items.extend(result.get(key).and_then(|v| v.as_array()).cloned().unwrap_or_default());
+        cursor = result.get("nextCursor").and_then(|c| c.as_str()).map(String::from);
+        if cursor.is_none() {
+            return Ok(items);
+        }
+    }
+    warnings.push(format!("{} returned more than {} pages; the list is truncated", method, MAX_LIST_PAGES));
+    Ok(items)
+}
+
+fn str_field(value: &serde_json::Value, key: &str) -> Option<String> {
+    value.get(key).and_then(|v| v.as_str()).map(String::from)
+}
+
+/// Initialize and list everything the server advertises
+async fn handshake(
+    conn: &mut Connection,
+    deadline: Instant,
+    started: Instant,
+    result: &mut McpProbeResult,
+) -> Result<(), (McpProbeStage, String)> {
+    let params = serde_json::json!({
+        "protocolVersion": PROTOCOL_VERSION,
+        "capabilities": { "roots": { "listChanged": false } },
+        "clientInfo": { "name": "agent-studio", "version": env!("CARGO_PKG_VERSION") },
+    });
+    let init = with_deadline(deadline, "initialize", conn.request(1, "initialize", params, &mut result.warnings))
+        .await
+        .map_err(|e| (McpProbeStage::Initialize, e))?;
+    result.initialize_ms = Some(started.elapsed().as_millis() as u64);
+    result.protocol_version = str_field(&init, "protocolVersion");
+    result.server_info = init.get("serverInfo").cloned();
+    result.capabilities = init.get("capabilities").cloned();
+    result.instructions = str_field(&init, "instructions");
+    if let Connection::Http(http) = conn {
+        http.protocol_version = result.protocol_version.clone();
+    }
+    conn.notify("notifications/initialized").await.map_err(|e| (McpProbeStage::Initialize, e))?;
+
+    let has = |capability: &str| result.capabilities.as_ref().is_some_and(|c| c.get(capability).is_some());
+    let (has_tools, has_prompts, has_resources) = (has("tools"), has("prompts"), has("resources"));
+    let mut next_id = 1;
+
+    if has_tools {
+        let tools = list_all(conn, &mut next_id, "tools/list", "tools", deadline, &mut result.warnings)
+            .await
+            .map_err(|e| (McpProbeStage::ListTools, e))?;
+        result.tools = tools.iter().filter_map(|t| Some(McpToolInfo {
+            name: str_field(t, "name")?,
+            title: str_field(t, "title"),
+            description: str_field(t, "description"),
+            input_schema: t.get("inputSchema").cloned(),
+        })).collect();
+    }
+    if has_prompts {
+        let prompts = list_all(conn, &mut next_id, "prompts/list", "prompts", deadline, &mut result.warnings)
+            .await
+            .map_err(|e| (McpProbeStage::ListPrompts, e))?;
+        result.prompts = prompts.iter().filter_map(|p| Some(McpPromptInfo {
+            name: str_field(p, "name")?,
+            description: str_field(p, "description"),
+            arguments: p.get("arguments").and_then(|a| a.as_array()).into_iter().flatten()
+                .filter_map(|a| Some(McpPromptArgument {
+                    name: str_field(a, "name")?,
+                    description: str_field(a, "description"),
+                    required: a.get("required").and_then(|r| r.as_bool()).unwrap_or(false),
+                }))
+                .collect(),
+        })).collect();
+    }
+    if has_resources {
+        let resources = list_all(conn, &mut next_id, "resources/list", "resources", deadline, &mut result.warnings)
+            .await
+            .map_err(|e| (McpProbeStage::ListResources, e))?;
+        result.resources = resources.iter().filter_map(|r| Some(McpResourceInfo {
+            uri: str_field(r, "uri")?,
+            name: str_field(r, "name"),
+            description: str_field(r, "description"),
+            mime_type: str_field(r, "mimeType"),
+        })).collect();
+    }
+    Ok(())
+}
+
+async fn probe_server(server: &McpServerEntity, cwd: Option<PathBuf>, timeout: Duration) -> McpProbeResult {
+    let started = Instant::now();
+    let deadline = started + timeout;
+    let resolved = resolve_server(server);
+    let mut result = McpProbeResult {
+        server_name: server.name.clone(),
+        transport: server.transport.clone(),
+        ok: false,
+        failed_stage: None,
+        error: None,
+        protocol_version: None,
+        server_info: None,
+        capabilities: None,
+        instructions: None,
+        tools: Vec::new(),
+        prompts: Vec::new(),
+        resources: Vec::new(),
+        stderr: String::new(),
+        exit_code: None,
+        unresolved_vars: resolved.unresolved.clone(),
+        warnings: Vec::new(),
+        initialize_ms: None,
+        duration_ms: 0,
+    };
+
+    let connected = with_deadline(deadline, "connection", connect(server, &resolved, cwd.as_ref())).await;
+    let mut conn = match connected {
+        Ok(conn) => conn,
+        Err(e) => {
+            result.failed_stage = Some(McpProbeStage::Connect);
+            result.error = Some(e);
+            result.duration_ms = started.elapsed().as_millis() as u64;
+            return result;
+        }
+    };
+    result.transport = conn.transport().to_string();
+
+    let outcome = handshake(&mut conn, deadline, started, &mut result).await;
+    let (exit_code, stderr) = conn.close().await;
+    result.exit_code = exit_code;
+    result.stderr = stderr;
+    match outcome {
+        Ok(()) => result.ok = true,
+        Err((stage, error)) => {
+            result.failed_stage = Some(stage);
+            result.error = Some(error);
+        }
+    }
+    result.duration_ms = started.elapsed().as_millis() as u64;
+    result
+}
+
+// ============================================================================
+// Public Tauri Commands
+// ============================================================================
+
+/// Start or connect to an MCP server, run the initialize handshake and list its
+/// tools, prompts and resources. Startup failures are reported in the result
+/// (with the stage and stderr); only a server with no command or url is an error.
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn probe_mcp_server(
+    server: McpServerEntity,
+    project_path: Option<String>,
+    timeout_ms: Option<u64>,
+) -> Result<McpProbeResult, String> {
+    if server.config.command.is_none() && server.config.url.is_none() {
+        return Err(format!("MCP server \"{}\" has neither a command nor a url", server.name));
+    }
+    let cwd = project_path.map(PathBuf::from).filter(|p| p.is_dir());
+    let timeout = Duration::from_millis(timeout_ms.unwrap_or(DEFAULT_PROBE_TIMEOUT_MS));
+    Ok(probe_server(&server, cwd, timeout).await)
+}
@@ -7,9 +7,30 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::hash::{Hash, Hasher};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
+use frontmatter::parse_frontmatter;
+
+pub mod fixes;
+pub mod frontmatter;
+pub mod frontmatter_lint;
+pub mod health;
+pub mod hook_audit;
+pub mod hook_runner;
+pub mod hooks;
+pub mod json_edit;
+pub mod mcp_check;
+pub mod mcp_edit;
+pub mod mcp_import;
+pub mod mcp_probe;
+pub mod opencode_config;
+pub mod permissions;
+pub mod plugin_authoring;
+pub mod plugins;
+pub mod secret_scan;
+pub mod settings_edit;
+
 // ============================================================================
 // Type Definitions
 // ============================================================================
@@ -26,6 +47,12 @@ pub struct BaseEntity {
     pub content: Option<String>,
     pub last_modified: u64,
     pub tool: String,  // "claude" or "opencode"
+    #[serde(default)]
+    pub is_from_plugin: bool,
+    #[serde(default)]
+    pub plugin_name: Option<String>,
+    #[serde(default)]
+    pub read_only: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -54,6 +81,9 @@ pub struct AgentEntity {
     #[serde(rename = "type")]
     pub entity_type: String,  // "agent"
     pub frontmatter: Option<HashMap<String, serde_json::Value>>,
+    /// Set for agents defined inline in opencode.json: where the entry lives in `path`
+    #[serde(default)]
+    pub json_pointer: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -75,6 +105,9 @@ pub struct CommandEntity {
     pub entity_type: String,  // "command"
     pub namespace: Option<String>,
     pub frontmatter: Option<HashMap<String, serde_json::Value>>,
+    /// Set for commands defined inline in opencode.json: where the entry lives in `path`
+    #[serde(default)]
+    pub json_pointer: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -101,9 +134,15 @@ pub struct HookEntity {
     pub event: String,
     pub matcher: Option<String>,
     pub hooks: Vec<HookDefinition>,
-    pub source: String,  // "global", "project", "local"
+    pub source: String,  // "global", "project", "local", "plugin"
     pub source_path: String,
     pub tool: String,  // "claude" or "opencode"
+    #[serde(default)]
+    pub is_from_plugin: bool,
+    #[serde(default)]
+    pub plugin_name: Option<String>,
+    #[serde(default)]
+    pub read_only: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -120,6 +159,16 @@ pub struct PluginEntity {
     pub has_hooks: bool,
     pub has_mcp: bool,
     pub has_lsp: bool,
+    #[serde(default)]
+    pub marketplace: Option<String>,
+    /// Effective enabled state; plugins outside a marketplace are always loaded
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub enabled_state: Option<plugins::PluginEnabledState>,
+    /// Why the plugin's agents, commands, skills or hooks couldn't be read
+    #[serde(default)]
+    pub load_error: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -146,6 +195,27 @@ pub struct McpServerEntity {
     pub is_from_plugin: bool,
     pub plugin_name: Option<String>,
     pub tool: String,  // "claude" or "opencode"
+    #[serde(default)]
+    pub project_path: Option<String>,
+    #[serde(default)]
+    pub approval: Option<String>,  // .mcp.json servers: "approved", "rejected" or "pending"
+}
+
+/// Language server shipped by a plugin in .lsp.json (or plugin.json `lspServers`)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LspServerEntity {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub entity_type: String,  // "lsp"
+    pub name: String,
+    pub scope: String,
+    pub command: Option<String>,
+    pub args: Vec<String>,
+    pub extension_to_language: HashMap<String, String>,
+    pub config: serde_json::Value,
+    pub source_path: String,
+    pub plugin_name: String,
+    pub tool: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -155,6 +225,9 @@ pub struct DuplicateEntity {
     pub scope: String,
     pub project_path: Option<String>,
     pub precedence: u32,
+    /// Plugin-provided or defined inline in a config file; can't be renamed or deleted here
+    pub read_only: bool,
+    pub json_pointer: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -185,6 +258,15 @@ pub struct EntityCounts {
     pub mcp: u32,
 }
 
+/// Severity of an audit finding (mirrors HealthIssueSeverity on the frontend)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
 /// Status of a file: whether it's a file, symlink, or missing
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -233,6 +315,18 @@ pub struct ProjectInfo {
     pub has_opencode_json: bool,
     pub entity_counts: EntityCounts,
     pub config_state: Option<ConfigState>,
+    #[serde(default)]
+    pub local_state: Option<ProjectLocalState>,
+}
+
+/// Per-project state Claude Code keeps under `projects["/abs/path"]` in ~/.claude.json
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ProjectLocalState {
+    pub mcp_servers: Vec<String>,  // Names of local-scope servers
+    pub enabled_mcpjson_servers: Vec<String>,
+    pub disabled_mcpjson_servers: Vec<String>,
+    pub enable_all_project_mcp_servers: bool,
+    pub allowed_tools: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -247,8 +341,14 @@ pub struct DiscoveryResult {
     pub hooks: Vec<HookEntity>,
     pub plugins: Vec<PluginEntity>,
     pub mcp_servers: Vec<McpServerEntity>,
+    pub lsp_servers: Vec<LspServerEntity>,
     pub duplicates: Vec<DuplicateGroup>,
     pub symlinks: Vec<SymlinkInfo>,
+    pub hook_findings: Vec<hook_audit::HookFinding>,
+    pub mcp_findings: Vec<mcp_check::McpFinding>,
+    pub secret_findings: Vec<secret_scan::SecretFinding>,
+    pub frontmatter_findings: Vec<frontmatter_lint::FrontmatterFinding>,
+    pub health: health::HealthReport,
     pub discovered_at: u64,
 }
 
@@ -398,20 +498,6 @@ fn read_file_content(path: &PathBuf) -> Option<String> {
     }
 }
 
-fn parse_frontmatter(content: &str) -> (Option<HashMap<String, serde_json::Value>>, String) {
-    if content.starts_with("---") {
-        if let Some(end_idx) = content[3..].find("---") {
-            let yaml_str = &content[3..end_idx + 3];
-            let body = &content[end_idx + 6..].trim_start();
-            
-            if let Ok(frontmatter) = serde_yaml::from_str::<HashMap<String, serde_json::Value>>(yaml_str) {
-                return (Some(frontmatter), body.to_string());
-            }
-        }
-    }
-    (None, content.to_string())
-}
-
 fn parse_json_file(path: &PathBuf) -> Option<serde_json::Value> {
     read_file_content(path).and_then(|content| serde_json::from_str(&content).ok())
 }
@@ -420,31 +506,33 @@ fn parse_json_file(path: &PathBuf) -> Option<serde_json::Value> {
 // Discovery Commands
 // ============================================================================
 
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub fn get_home_directory() -> Result<String, String> {
     get_home_dir()
         .map(|p| p.to_string_lossy().to_string())
         .ok_or_else(|| "Could not find home directory".to_string())
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub fn get_config_directory() -> Result<String, String> {
     get_config_dir()
         .map(|p| p.to_string_lossy().to_string())
         .ok_or_else(|| "Could not find config directory".to_string())
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub fn get_global_claude_path() -> Result<String, String> {
     get_home_dir()
         .map(|p| p.join(".claude").to_string_lossy().to_string())
         .ok_or_else(|| "Could not find home directory".to_string())
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub fn discover_all(project_paths: Option<Vec<String>>) -> Result<DiscoveryResult, String> {
     let home = get_home_dir().ok_or("Could not find home directory")?;
     let global_claude_path = home.join(".claude");
+    // ~/.claude.json can be large; read it once for every project's local state
+    let claude_json = parse_json_file(&home.join(".claude.json"));
     
     let mut all_settings = Vec::new();
     let mut all_memory = Vec::new();
@@ -454,6 +542,7 @@ pub fn discover_all(project_paths: Option<Vec<String>>) -> Result<DiscoveryResul
     let mut all_hooks = Vec::new();
     let mut all_plugins = Vec::new();
     let mut all_mcp = Vec::new();
+    let mut all_lsp = Vec::new();
     let mut all_symlinks = Vec::new();
     let mut projects = Vec::new();
     
@@ -563,6 +652,25 @@ pub fn discover_all(project_paths: Option<Vec<String>>) -> Result<DiscoveryResul
         }
     }
 
+    // Agents, commands and instruction files declared in opencode.json itself;
+    // inline entries share the config file's path, so they're keyed by id
+    let inline = opencode_config::discover_opencode_inline(&global_opencode_path, &global_opencode_path, "global", None);
+    for a in inline.agents {
+        if seen_agent_paths.insert(a.base.id.clone()) {
+            all_agents.push(a);
+        }
+    }
+    for c in inline.commands {
+        if seen_command_paths.insert(c.base.id.clone()) {
+            all_commands.push(c);
+        }
+    }
+    for m in inline.instructions {
+        if seen_memory_paths.insert(m.base.path.clone()) {
+            all_memory.push(m);
+        }
+    }
+
     // Discover project entities - first scan for projects recursively in given directories
     if let Some(base_paths) = project_paths {
         // Use scan_projects to find all projects recursively
@@ -640,14 +748,32 @@ pub fn discover_all(project_paths: Option<Vec<String>>) -> Result<DiscoveryResul
                 }
             }
 
-            // MCP from .mcp.json
-            for m in discover_mcp_from_project(&project_path)? {
+            // MCP from .mcp.json, marked with whether the user approved each server
+            let project_entry = claude_json.as_ref().and_then(|c| claude_json_project_entry(c, &project_path_str));
+            let local_state = project_entry.map(project_local_state);
+            let approval_sources: Vec<serde_json::Value> = [
+                claude_dir.join("settings.local.json"),
+                claude_dir.join("settings.json"),
+                global_claude_path.join("settings.json"),
+            ].iter().filter_map(parse_json_file).chain(project_entry.cloned()).collect();
+            for mut m in discover_mcp_from_project(&project_path)? {
                 if seen_mcp_ids.insert(m.id.clone()) {
+                    m.approval = Some(mcpjson_approval(&m.name, &approval_sources).to_string());
                     counts.mcp += 1;
                     all_mcp.push(m);
                 }
             }
 
+            // Local-scope MCP servers from ~/.claude.json projects[...]
+            if let Some(entry) = project_entry {
+                for m in discover_mcp_from_claude_json_project(&home, &project_path_str, entry) {
+                    if seen_mcp_ids.insert(m.id.clone()) {
+                        counts.mcp += 1;
+                        all_mcp.push(m);
+                    }
+                }
+            }
+
             // ================================================================
             // Discover OpenCode entities for this project (.opencode/)
             // ================================================================
@@ -715,6 +841,29 @@ pub fn discover_all(project_paths: Option<Vec<String>>) -> Result<DiscoveryResul
                 }
             }
 
+            // Inline agents, commands and instructions from opencode.json (.opencode/ or project root)
+            for config_dir in [&opencode_dir, &project_path] {
+                let inline = opencode_config::discover_opencode_inline(config_dir, &project_path, "project", Some(&project_path_str));
+                for a in inline.agents {
+                    if seen_agent_paths.insert(a.base.id.clone()) {
+                        counts.agents += 1;
+                        all_agents.push(a);
+                    }
+                }
+                for c in inline.commands {
+                    if seen_command_paths.insert(c.base.id.clone()) {
+                        counts.commands += 1;
+                        all_commands.push(c);
+                    }
+                }
+                for m in inline.instructions {
+                    if seen_memory_paths.insert(m.base.path.clone()) {
+                        counts.memory += 1;
+                        all_memory.push(m);
+                    }
+                }
+            }
+
             projects.push(ProjectInfo {
                 path: project_path_str.clone(),
                 name: project_info.name.clone(),
@@ -727,10 +876,41 @@ pub fn discover_all(project_paths: Option<Vec<String>>) -> Result<DiscoveryResul
                 has_opencode_json: project_path.join("opencode.json").exists() || project_path.join("opencode.jsonc").exists() || opencode_dir.join("opencode.json").exists(),
                 entity_counts: counts,
                 config_state: Some(detect_config_state(&project_path)),
+                local_state,
             });
         }
     }
 
+    // Discover what each plugin contributes (read-only, included in duplicate detection)
+    let contents = collect_plugin_contents(&mut all_plugins);
+    let inactive_paths = contents.inactive_paths;
+    for a in contents.agents {
+        if seen_agent_paths.insert(a.base.path.clone()) {
+            all_agents.push(a);
+        }
+    }
+    for c in contents.commands {
+        if seen_command_paths.insert(c.base.path.clone()) {
+            all_commands.push(c);
+        }
+    }
+    for s in contents.skills {
+        if seen_skill_paths.insert(s.base.path.clone()) {
+            all_skills.push(s);
+        }
+    }
+    for h in contents.hooks {
+        if seen_hook_ids.insert(h.id.clone()) {
+            all_hooks.push(h);
+        }
+    }
+    for m in contents.mcp_servers {
+        if seen_mcp_ids.insert(m.id.clone()) {
+            all_mcp.push(m);
+        }
+    }
+    all_lsp.extend(contents.lsp_servers);
+
     // Collect symlinks
     for entity in &all_settings {
         if entity.base.is_symlink {
@@ -757,14 +937,26 @@ pub fn discover_all(project_paths: Option<Vec<String>>) -> Result<DiscoveryResul
     // ... similar for other entity types
 
     // Find duplicates
-    let duplicates = find_duplicates_internal(&all_agents, &all_skills, &all_commands)?;
+    let duplicates = find_duplicates_internal(&all_agents, &all_skills, &all_commands, &inactive_paths)?;
+
+    // Audit hook commands, including hooks shipped by plugins
+    let hook_findings = hook_audit::audit_hooks_internal(&all_hooks, &all_plugins);
+
+    // Check that MCP server commands and env placeholders resolve
+    let mcp_findings = mcp_check::check_mcp_servers_internal(&all_mcp, &all_settings);
+
+    // Literal tokens in MCP env / headers and settings `env`, flagged when git-tracked
+    let secret_findings = secret_scan::scan_secrets_internal(&all_mcp, &all_settings);
+
+    // YAML errors, missing / unknown keys, tools and models in agent, skill and command files
+    let frontmatter_findings = frontmatter_lint::lint_frontmatter_internal(&all_agents, &all_skills, &all_commands, &all_mcp);
 
     let now = SystemTime::now()
         .duration_since(SystemTime::UNIX_EPOCH)
         .unwrap_or_default()
         .as_millis() as u64;
 
-    Ok(DiscoveryResult {
+    let mut result = DiscoveryResult {
         global_config_path: global_claude_path.to_string_lossy().to_string(),
         projects,
         settings: all_settings,
@@ -775,10 +967,20 @@ pub fn discover_all(project_paths: Option<Vec<String>>) -> Result<DiscoveryResul
         hooks: all_hooks,
         plugins: all_plugins,
         mcp_servers: all_mcp,
+        lsp_servers: all_lsp,
         duplicates,
         symlinks: all_symlinks,
+        hook_findings,
+        mcp_findings,
+        secret_findings,
+        frontmatter_findings,
+        health: health::HealthReport::default(),
         discovered_at: now,
-    })
+    };
+
+    // Every check runs over the finished result, then project suppressions apply
+    result.health = health::run_health_checks_internal(&result);
+    Ok(result)
 }
 
 // ============================================================================
@@ -807,6 +1009,9 @@ fn discover_settings_internal(claude_dir: &PathBuf, scope: &str, project_path: O
                     content,
                     last_modified: get_last_modified(&global_settings_path),
                     tool: tool.to_string(),
+                    is_from_plugin: false,
+                    plugin_name: None,
+                    read_only: false,
                 },
                 entity_type: "settings".to_string(),
                 variant: "global".to_string(),
@@ -833,6 +1038,9 @@ fn discover_settings_internal(claude_dir: &PathBuf, scope: &str, project_path: O
                     content,
                     last_modified: get_last_modified(&project_settings_path),
                     tool: tool.to_string(),
+                    is_from_plugin: false,
+                    plugin_name: None,
+                    read_only: false,
                 },
                 entity_type: "settings".to_string(),
                 variant: "project".to_string(),
@@ -859,6 +1067,9 @@ fn discover_settings_internal(claude_dir: &PathBuf, scope: &str, project_path: O
                     content,
                     last_modified: get_last_modified(&local_settings_path),
                     tool: tool.to_string(),
+                    is_from_plugin: false,
+                    plugin_name: None,
+                    read_only: false,
                 },
                 entity_type: "settings".to_string(),
                 variant: "local".to_string(),
@@ -891,6 +1102,9 @@ fn discover_memory_internal(claude_dir: &PathBuf, base_path: &PathBuf, scope: &s
                 content,
                 last_modified: get_last_modified(&dotclaude_md_path),
                 tool: tool.to_string(),
+                is_from_plugin: false,
+                plugin_name: None,
+                read_only: false,
             },
             entity_type: "memory".to_string(),
             variant: "dotclaude".to_string(),
@@ -916,6 +1130,9 @@ fn discover_memory_internal(claude_dir: &PathBuf, base_path: &PathBuf, scope: &s
                     content,
                     last_modified: get_last_modified(&root_md_path),
                     tool: tool.to_string(),
+                    is_from_plugin: false,
+                    plugin_name: None,
+                    read_only: false,
                 },
                 entity_type: "memory".to_string(),
                 variant: "root".to_string(),
@@ -956,9 +1173,13 @@ fn discover_agents_internal(agents_dir: &PathBuf, scope: &str, project_path: Opt
                             content,
                             last_modified: get_last_modified(&path),
                             tool: tool.to_string(),
+                            is_from_plugin: false,
+                            plugin_name: None,
+                            read_only: false,
                         },
                         entity_type: "agent".to_string(),
                         frontmatter,
+                        json_pointer: None,
                     });
                 }
             }
@@ -1011,6 +1232,9 @@ fn discover_skills_internal(skills_dir: &PathBuf, scope: &str, project_path: Opt
                                 content,
                                 last_modified: get_last_modified(&skill_file),
                                 tool: tool.to_string(),
+                                is_from_plugin: false,
+                                plugin_name: None,
+                                read_only: false,
                             },
                             entity_type: "skill".to_string(),
                             skill_dir: skill_dir.to_string_lossy().to_string(),
@@ -1064,10 +1288,14 @@ fn discover_commands_internal(commands_dir: &PathBuf, scope: &str, project_path:
                                 content,
                                 last_modified: get_last_modified(&path),
                                 tool: tool.to_string(),
+                                is_from_plugin: false,
+                                plugin_name: None,
+                                read_only: false,
                             },
                             entity_type: "command".to_string(),
                             namespace: namespace.map(String::from),
                             frontmatter,
+                            json_pointer: None,
                         });
                     }
                 }
@@ -1108,6 +1336,9 @@ fn discover_plugins_internal(plugins_dir: &PathBuf, scope: &str, project_path: O
                                 content: read_file_content(&manifest_path),
                                 last_modified: get_last_modified(&manifest_path),
                                 tool: tool.to_string(),
+                                is_from_plugin: false,
+                                plugin_name: None,
+                                read_only: false,
                             },
                             entity_type: "plugin".to_string(),
                             plugin_dir: plugin_dir.to_string_lossy().to_string(),
@@ -1118,6 +1349,10 @@ fn discover_plugins_internal(plugins_dir: &PathBuf, scope: &str, project_path: O
                             has_hooks: plugin_dir.join("hooks").exists() || plugin_dir.join("hooks.json").exists(),
                             has_mcp: plugin_dir.join(".mcp.json").exists(),
                             has_lsp: plugin_dir.join(".lsp.json").exists(),
+                            marketplace: None,
+                            enabled: true,
+                            enabled_state: None,
+                            load_error: None,
                         });
                     }
                 }
@@ -1128,10 +1363,12 @@ fn discover_plugins_internal(plugins_dir: &PathBuf, scope: &str, project_path: O
     Ok(plugins)
 }
 
-/// Extract enabled plugins from settings.json (registry plugins like @claude-plugins-official)
-fn extract_enabled_plugins_from_settings(settings_path: &PathBuf, scope: &str) -> Result<Vec<PluginEntity>, String> {
-    // This is now handled by discover_installed_plugins which reads from installed_plugins.json
-    Ok(Vec::new())
+/// Read the `enabledPlugins` map ("name@marketplace" -> bool) from a settings file
+fn extract_enabled_plugins_from_settings(settings_path: &PathBuf) -> HashMap<String, bool> {
+    parse_json_file(settings_path)
+        .and_then(|settings| settings.get("enabledPlugins").and_then(|e| e.as_object()).cloned())
+        .map(|obj| obj.into_iter().filter_map(|(name, v)| v.as_bool().map(|b| (name, b))).collect())
+        .unwrap_or_default()
 }
 
 /// Discover installed plugins from ~/.claude/plugins/installed_plugins.json
@@ -1154,9 +1391,13 @@ fn discover_installed_plugins(home: &PathBuf) -> Result<Vec<PluginEntity>, Strin
                             let scope_str = installation.get("scope")
                                 .and_then(|s| s.as_str())
                                 .unwrap_or("user");
-                            let install_path = installation.get("installPath")
+                            // Without an install path there's nothing on disk to show
+                            let Some(install_path) = installation.get("installPath")
                                 .and_then(|p| p.as_str())
-                                .unwrap_or("");
+                                .filter(|p| !p.is_empty())
+                            else {
+                                continue;
+                            };
                             let version = installation.get("version")
                                 .and_then(|v| v.as_str())
                                 .unwrap_or("unknown");
@@ -1182,6 +1423,8 @@ fn discover_installed_plugins(home: &PathBuf) -> Result<Vec<PluginEntity>, Strin
                                 .and_then(|d| d.as_str())
                                 .map(String::from);
                             
+                            let enabled_state = plugins::resolve_plugin_enabled(plugin_full_name, project_path.as_deref());
+
                             plugins.push(PluginEntity {
                                 base: BaseEntity {
                                     id: generate_id("plugin", &format!("{}_{}", plugin_full_name, scope_str)),
@@ -1194,6 +1437,9 @@ fn discover_installed_plugins(home: &PathBuf) -> Result<Vec<PluginEntity>, Strin
                                     content: description,
                                     last_modified: 0,
                                     tool: "claude".to_string(),
+                                    is_from_plugin: false,
+                                    plugin_name: None,
+                                    read_only: false,
                                 },
                                 entity_type: "plugin".to_string(),
                                 plugin_dir: install_path.to_string(),
@@ -1204,6 +1450,10 @@ fn discover_installed_plugins(home: &PathBuf) -> Result<Vec<PluginEntity>, Strin
                                 has_hooks: install_path_buf.join("hooks").exists() || install_path_buf.join("hooks.json").exists(),
                                 has_mcp: install_path_buf.join(".mcp.json").exists(),
                                 has_lsp: install_path_buf.join(".lsp.json").exists(),
+                                marketplace: marketplace.clone(),
+                                enabled: enabled_state.enabled,
+                                enabled_state: Some(enabled_state),
+                                load_error: None,
                             });
                         }
                     }
@@ -1215,6 +1465,274 @@ fn discover_installed_plugins(home: &PathBuf) -> Result<Vec<PluginEntity>, Strin
     Ok(plugins)
 }
 
+/// Entities contributed by a single plugin
+#[derive(Default)]
+struct PluginContents {
+    agents: Vec<AgentEntity>,
+    commands: Vec<CommandEntity>,
+    skills: Vec<SkillEntity>,
+    hooks: Vec<HookEntity>,
+    mcp_servers: Vec<McpServerEntity>,
+    lsp_servers: Vec<LspServerEntity>,
+    /// Paths of agents, commands and skills from disabled plugins, which
+    /// Claude Code doesn't load and so can't shadow anything
+    inactive_paths: std::collections::HashSet<String>,
+}
+
+/// Directories for a plugin component: the default one plus any custom
+/// paths listed under the same key in plugin.json
+fn plugin_component_dirs(plugin: &PluginEntity, key: &str) -> Vec<PathBuf> {
+    // An empty dir would resolve against the process's working directory
+    if plugin.plugin_dir.is_empty() {
+        return Vec::new();
+    }
+    let plugin_dir = PathBuf::from(&plugin.plugin_dir);
+    let mut dirs = vec![plugin_dir.join(key)];
+    match plugin.manifest.as_ref().and_then(|m| m.get(key)) {
+        Some(serde_json::Value::String(path)) => dirs.push(plugin_dir.join(path)),
+        Some(serde_json::Value::Array(paths)) => {
+            dirs.extend(paths.iter().filter_map(|p| p.as_str()).map(|p| plugin_dir.join(p)));
+        }
+        _ => {}
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    dirs.into_iter().filter(|d| d.is_dir() && seen.insert(d.clone())).collect()
+}
+
+/// hooks.json files a plugin contributes: hooks/hooks.json, a root hooks.json,
+/// paths listed under the manifest's `hooks` key, or the manifest itself for inline hooks
+fn plugin_hook_files(plugin: &PluginEntity) -> Vec<PathBuf> {
+    if plugin.plugin_dir.is_empty() {
+        return Vec::new();
+    }
+    let plugin_dir = PathBuf::from(&plugin.plugin_dir);
+    let mut files = vec![plugin_dir.join("hooks").join("hooks.json"), plugin_dir.join("hooks.json")];
+
+    match plugin.manifest.as_ref().and_then(|m| m.get("hooks")) {
+        Some(serde_json::Value::String(path)) => files.push(plugin_dir.join(path)),
+        Some(serde_json::Value::Array(paths)) => {
+            files.extend(paths.iter().filter_map(|p| p.as_str()).map(|p| plugin_dir.join(p)));
+        }
+        Some(serde_json::Value::Object(_)) => files.push(plugin_dir.join(".claude-plugin").join("plugin.json")),
+        _ => {}
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    files.into_iter().filter(|f| f.is_file() && seen.insert(f.clone())).collect()
+}
+
+/// Replace ${CLAUDE_PLUGIN_ROOT} in every string of a plugin config value
+fn expand_plugin_root(value: &mut serde_json::Value, plugin_dir: &str) {
+    match value {
+        serde_json::Value::String(s) if s.contains("${CLAUDE_PLUGIN_ROOT}") => {
+            *s = s.replace("${CLAUDE_PLUGIN_ROOT}", plugin_dir);
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(|v| expand_plugin_root(v, plugin_dir)),
+        serde_json::Value::Object(map) => map.values_mut().for_each(|v| expand_plugin_root(v, plugin_dir)),
+        _ => {}
+    }
+}
+
+/// Server maps a plugin declares: the default file (e.g. .mcp.json) plus the
+/// manifest key, which may be a path, a list of paths or an inline object.
+/// Returns (source file, server map) pairs with ${CLAUDE_PLUGIN_ROOT} expanded.
+fn plugin_server_configs(plugin: &PluginEntity, file_name: &str, manifest_key: &str) -> Vec<(PathBuf, serde_json::Map<String, serde_json::Value>)> {
+    if plugin.plugin_dir.is_empty() {
+        return Vec::new();
+    }
+    let plugin_dir = PathBuf::from(&plugin.plugin_dir);
+    let manifest_path = plugin_dir.join(".claude-plugin").join("plugin.json");
+    let mut files = vec![plugin_dir.join(file_name)];
+    let mut configs = Vec::new();
+
+    match plugin.manifest.as_ref().and_then(|m| m.get(manifest_key)) {
+        Some(serde_json::Value::String(path)) => files.push(plugin_dir.join(path)),
+        Some(serde_json::Value::Array(paths)) => {
+            files.extend(paths.iter().filter_map(|p| p.as_str()).map(|p| plugin_dir.join(p)));
+        }
+        Some(serde_json::Value::Object(inline)) => configs.push((manifest_path, serde_json::Value::Object(inline.clone()))),
+        _ => {}
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    for file in files {
+        if seen.insert(file.clone()) {
+            if let Some(config) = parse_json_file(&file) {
+                configs.push((file, config));
+            }
+        }
+    }
+
+    configs.into_iter()
+        .filter_map(|(file, mut config)| {
+            expand_plugin_root(&mut config, &plugin.plugin_dir);
+            // Files may wrap servers in { "mcpServers": {...} } like a project .mcp.json
+            let servers = config.get(manifest_key).cloned().unwrap_or(config);
+            match servers {
+                serde_json::Value::Object(map) => Some((file, map)),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// MCP servers from a plugin's .mcp.json or plugin.json `mcpServers`
+fn discover_mcp_from_plugin(plugin: &PluginEntity) -> Vec<McpServerEntity> {
+    let mut servers = Vec::new();
+
+    for (source_path, map) in plugin_server_configs(plugin, ".mcp.json", "mcpServers") {
+        for (name, server_config) in map {
+            let transport = server_config.get("type")
+                .and_then(|t| t.as_str())
+                .unwrap_or_else(|| {
+                    if server_config.get("command").is_some() { "stdio" }
+                    else if server_config.get("url").is_some() { "http" }
+                    else { "unknown" }
+                })
+                .to_string();
+
+            servers.push(McpServerEntity {
+                id: generate_id("mcp", &format!("plugin_{}_{}", plugin.base.id, name)),
+                entity_type: "mcp".to_string(),
+                name,
+                scope: plugin.base.scope.clone(),
+                transport,
+                config: serde_json::from_value(server_config).unwrap_or(McpServerConfig {
+                    transport_type: None,
+                    command: None,
+                    args: None,
+                    url: None,
+                    env: None,
+                    headers: None,
+                }),
+                source_path: source_path.to_string_lossy().to_string(),
+                is_from_plugin: true,
+                plugin_name: Some(plugin.base.name.clone()),
+                tool: plugin.base.tool.clone(),
+                project_path: plugin.base.project_path.clone(),
+                approval: None,
+            });
+        }
+    }
+
+    servers
+}
+
+/// Language servers from a plugin's .lsp.json or plugin.json `lspServers`
+fn discover_lsp_from_plugin(plugin: &PluginEntity) -> Vec<LspServerEntity> {
+    let mut servers = Vec::new();
+
+    for (source_path, map) in plugin_server_configs(plugin, ".lsp.json", "lspServers") {
+        for (name, config) in map {
+            if !config.is_object() {
+                continue;
+            }
+            let args = config.get("args")
+                .and_then(|a| a.as_array())
+                .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+                .unwrap_or_default();
+            let extension_to_language = config.get("extensionToLanguage")
+                .and_then(|m| m.as_object())
+                .map(|obj| obj.iter().filter_map(|(ext, lang)| lang.as_str().map(|l| (ext.clone(), l.to_string()))).collect())
+                .unwrap_or_default();
+
+            servers.push(LspServerEntity {
+                id: generate_id("lsp", &format!("plugin_{}_{}", plugin.base.id, name)),
+                entity_type: "lsp".to_string(),
+                name,
+                scope: plugin.base.scope.clone(),
+                command: config.get("command").and_then(|c| c.as_str()).map(String::from),
+                args,
+                extension_to_language,
+                config,
+                source_path: source_path.to_string_lossy().to_string(),
+                plugin_name: plugin.base.name.clone(),
+                tool: plugin.base.tool.clone(),
+            });
+        }
+    }
+
+    servers
+}
+
+fn mark_plugin_owned(base: &mut BaseEntity, plugin: &PluginEntity) {
+    base.is_from_plugin = true;
+    base.plugin_name = Some(plugin.base.name.clone());
+    base.read_only = true;
+}
+
+/// Discover the agents, commands, skills and hooks a plugin ships, tagged with
+/// the plugin and marked read-only (they are overwritten on plugin update)
+fn discover_plugin_contents(plugin: &PluginEntity) -> Result<PluginContents, String> {
+    let scope = if matches!(plugin.base.scope.as_str(), "project" | "local") { "project" } else { "global" };
+    let project_path = plugin.base.project_path.as_deref();
+    let tool = plugin.base.tool.as_str();
+    let mut contents = PluginContents {
+        agents: Vec::new(),
+        commands: Vec::new(),
+        skills: Vec::new(),
+        hooks: Vec::new(),
+        mcp_servers: discover_mcp_from_plugin(plugin),
+        lsp_servers: discover_lsp_from_plugin(plugin),
+        ..Default::default()
+    };
+
+    for dir in plugin_component_dirs(plugin, "agents") {
+        for mut a in discover_agents_internal(&dir, scope, project_path, tool)? {
+            mark_plugin_owned(&mut a.base, plugin);
+            contents.agents.push(a);
+        }
+    }
+    for dir in plugin_component_dirs(plugin, "commands") {
+        for mut c in discover_commands_internal(&dir, scope, project_path, tool)? {
+            mark_plugin_owned(&mut c.base, plugin);
+            contents.commands.push(c);
+        }
+    }
+    for dir in plugin_component_dirs(plugin, "skills") {
+        for mut s in discover_skills_internal(&dir, scope, project_path, tool)? {
+            mark_plugin_owned(&mut s.base, plugin);
+            contents.skills.push(s);
+        }
+    }
+    for file in plugin_hook_files(plugin) {
+        for mut h in extract_hooks_internal(&file, "plugin", tool)? {
+            h.is_from_plugin = true;
+            h.plugin_name = Some(plugin.base.name.clone());
+            h.read_only = true;
+            contents.hooks.push(h);
+        }
+    }
+
+    Ok(contents)
+}
+
+/// Everything the plugins contribute, merged. One unreadable plugin is
+/// reported on that plugin (`load_error`) rather than failing the caller.
+fn collect_plugin_contents(plugins: &mut [PluginEntity]) -> PluginContents {
+    let mut merged = PluginContents::default();
+    for plugin in plugins {
+        match discover_plugin_contents(plugin) {
+            Ok(contents) => {
+                if !plugin.enabled {
+                    merged.inactive_paths.extend(contents.agents.iter().map(|a| a.base.path.clone())
+                        .chain(contents.commands.iter().map(|c| c.base.path.clone()))
+                        .chain(contents.skills.iter().map(|s| s.base.path.clone())));
+                }
+                merged.agents.extend(contents.agents);
+                merged.commands.extend(contents.commands);
+                merged.skills.extend(contents.skills);
+                merged.hooks.extend(contents.hooks);
+                merged.mcp_servers.extend(contents.mcp_servers);
+                merged.lsp_servers.extend(contents.lsp_servers);
+            }
+            Err(e) => plugin.load_error = Some(e),
+        }
+    }
+    merged
+}
+
 fn extract_hooks_internal(settings_path: &PathBuf, source: &str, tool: &str) -> Result<Vec<HookEntity>, String> {
     let mut hooks = Vec::new();
     
@@ -1258,6 +1776,9 @@ fn extract_hooks_internal(settings_path: &PathBuf, source: &str, tool: &str) ->
                                     source: source.to_string(),
                                     source_path: settings_path.to_string_lossy().to_string(),
                                     tool: tool.to_string(),
+                                    is_from_plugin: false,
+                                    plugin_name: None,
+                                    read_only: false,
                                 });
                             }
                         }
@@ -1304,6 +1825,8 @@ fn discover_mcp_from_claude_json(home: &PathBuf) -> Result<Vec<McpServerEntity>,
                         is_from_plugin: false,
                         plugin_name: None,
                         tool: "claude".to_string(),
+                        project_path: None,
+                        approval: None,
                     });
                 }
             }
@@ -1313,6 +1836,89 @@ fn discover_mcp_from_claude_json(home: &PathBuf) -> Result<Vec<McpServerEntity>,
     Ok(servers)
 }
 
+/// The `projects[...]` entry for a project in ~/.claude.json. Keys are absolute
+/// paths as Claude Code saw them, so tolerate a trailing slash.
+fn claude_json_project_entry<'a>(claude_json: &'a serde_json::Value, project_path: &str) -> Option<&'a serde_json::Value> {
+    let projects = claude_json.get("projects")?.as_object()?;
+    let trimmed = project_path.trim_end_matches('/');
+    projects.get(project_path)
+        .or_else(|| projects.get(trimmed))
+        .or_else(|| projects.iter().find(|(k, _)| k.trim_end_matches('/') == trimmed).map(|(_, v)| v))
+}
+
+fn string_list(value: &serde_json::Value, key: &str) -> Vec<String> {
+    value.get(key)
+        .and_then(|v| v.as_array())
+        .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+        .unwrap_or_default()
+}
+
+fn project_local_state(entry: &serde_json::Value) -> ProjectLocalState {
+    ProjectLocalState {
+        mcp_servers: entry.get("mcpServers")
+            .and_then(|m| m.as_object())
+            .map(|m| m.keys().cloned().collect())
+            .unwrap_or_default(),
+        enabled_mcpjson_servers: string_list(entry, "enabledMcpjsonServers"),
+        disabled_mcpjson_servers: string_list(entry, "disabledMcpjsonServers"),
+        enable_all_project_mcp_servers: entry.get("enableAllProjectMcpServers").and_then(|v| v.as_bool()).unwrap_or(false),
+        allowed_tools: string_list(entry, "allowedTools"),
+    }
+}
+
+/// Whether Claude Code will load a .mcp.json server. Approvals live in the
+/// ~/.claude.json project entry or in settings files; a rejection anywhere wins.
+fn mcpjson_approval(name: &str, sources: &[serde_json::Value]) -> &'static str {
+    let listed = |key: &str| sources.iter().any(|s| string_list(s, key).iter().any(|n| n == name));
+    if listed("disabledMcpjsonServers") {
+        "rejected"
+    } else if listed("enabledMcpjsonServers")
+        || sources.iter().any(|s| s.get("enableAllProjectMcpServers").and_then(|v| v.as_bool()) == Some(true))
+    {
+        "approved"
+    } else {
+        "pending"
+    }
+}
+
+/// Local-scope MCP servers (private to one project) from ~/.claude.json
+fn discover_mcp_from_claude_json_project(home: &std::path::Path, project_path: &str, entry: &serde_json::Value) -> Vec<McpServerEntity> {
+    let Some(mcp_servers) = entry.get("mcpServers").and_then(|m| m.as_object()) else {
+        return Vec::new();
+    };
+    let claude_json_path = home.join(".claude.json");
+    mcp_servers.iter().map(|(name, server_config)| {
+        let transport = server_config.get("type")
+            .and_then(|t| t.as_str())
+            .unwrap_or_else(|| {
+                if server_config.get("command").is_some() { "stdio" }
+                else if server_config.get("url").is_some() { "http" }
+                else { "unknown" }
+            });
+        McpServerEntity {
+            id: generate_id("mcp", &format!("local_{}_{}", project_path, name)),
+            entity_type: "mcp".to_string(),
+            name: name.clone(),
+            scope: "local".to_string(),
+            transport: transport.to_string(),
+            config: serde_json::from_value(server_config.clone()).unwrap_or(McpServerConfig {
+                transport_type: None,
+                command: None,
+                args: None,
+                url: None,
+                env: None,
+                headers: None,
+            }),
+            source_path: claude_json_path.to_string_lossy().to_string(),
+            is_from_plugin: false,
+            plugin_name: None,
+            tool: "claude".to_string(),
+            project_path: Some(project_path.to_string()),
+            approval: None,
+        }
+    }).collect()
+}
+
 fn discover_mcp_from_project(project_path: &PathBuf) -> Result<Vec<McpServerEntity>, String> {
     let mut servers = Vec::new();
     
@@ -1354,6 +1960,8 @@ fn discover_mcp_from_project(project_path: &PathBuf) -> Result<Vec<McpServerEnti
                         is_from_plugin: false,
                         plugin_name: None,
                         tool: "claude".to_string(),
+                        project_path: Some(project_path.to_string_lossy().to_string()),
+                        approval: None,
                     });
                 }
             }
@@ -1376,7 +1984,8 @@ fn discover_opencode_settings_internal(opencode_dir: &PathBuf, scope: &str, proj
     if json_path.exists() {
         let (is_symlink, symlink_target) = is_symlink_with_target(&json_path);
         let content = read_file_content(&json_path);
-        let parsed = content.as_ref().and_then(|c| serde_json::from_str(c).ok());
+        // OpenCode accepts comments in opencode.json too
+        let parsed = content.as_ref().and_then(|c| json_edit::to_value(c).ok());
         
         settings.push(SettingsEntity {
             base: BaseEntity {
@@ -1390,6 +1999,9 @@ fn discover_opencode_settings_internal(opencode_dir: &PathBuf, scope: &str, proj
                 content,
                 last_modified: get_last_modified(&json_path),
                 tool: "opencode".to_string(),
+                is_from_plugin: false,
+                plugin_name: None,
+                read_only: false,
             },
             entity_type: "settings".to_string(),
             variant: if scope == "global" { "global".to_string() } else { "project".to_string() },
@@ -1402,11 +2014,7 @@ fn discover_opencode_settings_internal(opencode_dir: &PathBuf, scope: &str, proj
     if jsonc_path.exists() {
         let (is_symlink, symlink_target) = is_symlink_with_target(&jsonc_path);
         let content = read_file_content(&jsonc_path);
-        // For JSONC, we try to strip comments before parsing
-        let parsed = content.as_ref().and_then(|c| {
-            let stripped = strip_json_comments(c);
-            serde_json::from_str(&stripped).ok()
-        });
+        let parsed = content.as_ref().and_then(|c| json_edit::to_value(c).ok());
         
         settings.push(SettingsEntity {
             base: BaseEntity {
@@ -1420,6 +2028,9 @@ fn discover_opencode_settings_internal(opencode_dir: &PathBuf, scope: &str, proj
                 content,
                 last_modified: get_last_modified(&jsonc_path),
                 tool: "opencode".to_string(),
+                is_from_plugin: false,
+                plugin_name: None,
+                read_only: false,
             },
             entity_type: "settings".to_string(),
             variant: if scope == "global" { "global".to_string() } else { "project".to_string() },
@@ -1452,6 +2063,9 @@ fn discover_opencode_memory_internal(opencode_dir: &PathBuf, base_path: &PathBuf
                 content,
                 last_modified: get_last_modified(&dotopencode_md_path),
                 tool: "opencode".to_string(),
+                is_from_plugin: false,
+                plugin_name: None,
+                read_only: false,
             },
             entity_type: "memory".to_string(),
             variant: "dotopencode".to_string(),
@@ -1476,6 +2090,9 @@ fn discover_opencode_memory_internal(opencode_dir: &PathBuf, base_path: &PathBuf
                 content,
                 last_modified: get_last_modified(&root_md_path),
                 tool: "opencode".to_string(),
+                is_from_plugin: false,
+                plugin_name: None,
+                read_only: false,
             },
             entity_type: "memory".to_string(),
             variant: "root".to_string(),
@@ -1485,6 +2102,40 @@ fn discover_opencode_memory_internal(opencode_dir: &PathBuf, base_path: &PathBuf
     Ok(memory)
 }
 
+/// Normalize an OpenCode `mcp` entry: `command` is an array holding the
+/// program and its arguments, and `environment` replaces `env`
+pub(crate) fn opencode_server_config(server_config: &serde_json::Value) -> McpServerConfig {
+    let command = server_config.get("command")
+        .and_then(|c| {
+            if c.is_array() {
+                c.as_array()
+                    .and_then(|arr| arr.first())
+                    .and_then(|v| v.as_str())
+                    .map(String::from)
+            } else {
+                c.as_str().map(String::from)
+            }
+        });
+
+    let args = server_config.get("command")
+        .and_then(|c| c.as_array())
+        .map(|arr| arr.iter().skip(1).filter_map(|v| v.as_str().map(String::from)).collect::<Vec<_>>())
+        .or_else(|| server_config.get("args").and_then(|a| serde_json::from_value(a.clone()).ok()));
+
+    let env = server_config.get("environment")
+        .or_else(|| server_config.get("env"))
+        .and_then(|e| serde_json::from_value(e.clone()).ok());
+
+    McpServerConfig {
+        transport_type: server_config.get("type").and_then(|t| t.as_str()).map(String::from),
+        command,
+        args,
+        url: server_config.get("url").and_then(|u| u.as_str()).map(String::from),
+        env,
+        headers: server_config.get("headers").and_then(|h| serde_json::from_value(h.clone()).ok()),
+    }
+}
+
 /// Discover MCP servers from OpenCode's opencode.json mcp configuration
 fn discover_mcp_from_opencode_json(config_dir: &PathBuf, scope: &str, project_path: Option<&str>) -> Result<Vec<McpServerEntity>, String> {
     let mut servers = Vec::new();
@@ -1493,18 +2144,15 @@ fn discover_mcp_from_opencode_json(config_dir: &PathBuf, scope: &str, project_pa
     let json_path = config_dir.join("opencode.json");
     let jsonc_path = config_dir.join("opencode.jsonc");
     
-    let (config_path, config) = if json_path.exists() {
-        (json_path.clone(), parse_json_file(&json_path))
+    let config_path = if json_path.exists() {
+        json_path
     } else if jsonc_path.exists() {
-        let content = read_file_content(&jsonc_path);
-        let parsed = content.and_then(|c| {
-            let stripped = strip_json_comments(&c);
-            serde_json::from_str(&stripped).ok()
-        });
-        (jsonc_path.clone(), parsed)
+        jsonc_path
     } else {
         return Ok(servers);
     };
+    // Both may carry comments
+    let config = read_file_content(&config_path).and_then(|c| json_edit::to_value(&c).ok());
     
     if let Some(config) = config {
         // OpenCode stores MCP servers under "mcp" key (not "mcpServers" like Claude)
@@ -1519,47 +2167,19 @@ fn discover_mcp_from_opencode_json(config_dir: &PathBuf, scope: &str, project_pa
                         else { "unknown" }
                     });
                 
-                // OpenCode uses "command" as array, Claude uses string
-                let command = server_config.get("command")
-                    .and_then(|c| {
-                        if c.is_array() {
-                            c.as_array()
-                                .and_then(|arr| arr.first())
-                                .and_then(|v| v.as_str())
-                                .map(String::from)
-                        } else {
-                            c.as_str().map(String::from)
-                        }
-                    });
-                
-                let args = server_config.get("command")
-                    .and_then(|c| c.as_array())
-                    .map(|arr| arr.iter().skip(1).filter_map(|v| v.as_str().map(String::from)).collect::<Vec<_>>())
-                    .or_else(|| server_config.get("args").and_then(|a| serde_json::from_value(a.clone()).ok()));
-                
-                // OpenCode uses "environment" instead of "env"
-                let env = server_config.get("environment")
-                    .or_else(|| server_config.get("env"))
-                    .and_then(|e| serde_json::from_value(e.clone()).ok());
-                
                 servers.push(McpServerEntity {
                     id: generate_id("mcp", &format!("opencode_{}_{}", scope, name)),
                     entity_type: "mcp".to_string(),
                     name: name.clone(),
                     scope: scope.to_string(),
                     transport: transport.to_string(),
-                    config: McpServerConfig {
-                        transport_type: Some(transport.to_string()),
-                        command,
-                        args,
-                        url: server_config.get("url").and_then(|u| u.as_str()).map(String::from),
-                        env,
-                        headers: server_config.get("headers").and_then(|h| serde_json::from_value(h.clone()).ok()),
-                    },
+                    config: opencode_server_config(server_config),
                     source_path: config_path.to_string_lossy().to_string(),
                     is_from_plugin: false,
                     plugin_name: None,
                     tool: "opencode".to_string(),
+                    project_path: project_path.map(String::from),
+                    approval: None,
                 });
             }
         }
@@ -1568,78 +2188,18 @@ fn discover_mcp_from_opencode_json(config_dir: &PathBuf, scope: &str, project_pa
     Ok(servers)
 }
 
-/// Strip comments from JSONC content (simple implementation)
-fn strip_json_comments(content: &str) -> String {
-    let mut result = String::new();
-    let mut chars = content.chars().peekable();
-    let mut in_string = false;
-    let mut escape_next = false;
-    
-    while let Some(c) = chars.next() {
-        if escape_next {
-            result.push(c);
-            escape_next = false;
-            continue;
-        }
-        
-        if c == '\\' && in_string {
-            result.push(c);
-            escape_next = true;
-            continue;
-        }
-        
-        if c == '"' && !escape_next {
-            in_string = !in_string;
-            result.push(c);
-            continue;
-        }
-        
-        if !in_string && c == '/' {
-            if let Some(&next) = chars.peek() {
-                if next == '/' {
-                    // Line comment - skip until newline
-                    chars.next();
-                    while let Some(&ch) = chars.peek() {
-                        if ch == '\n' {
-                            result.push('\n');
-                            chars.next();
-                            break;
-                        }
-                        chars.next();
-                    }
-                    continue;
-                } else if next == '*' {
-                    // Block comment - skip until */
-                    chars.next();
-                    while let Some(ch) = chars.next() {
-                        if ch == '*' {
-                            if let Some(&'/') = chars.peek() {
-                                chars.next();
-                                break;
-                            }
-                        }
-                    }
-                    continue;
-                }
-            }
-        }
-        
-        result.push(c);
-    }
-    
-    result
-}
-
 fn find_duplicates_internal(
     agents: &[AgentEntity],
     skills: &[SkillEntity],
     commands: &[CommandEntity],
+    inactive_paths: &std::collections::HashSet<String>,
 ) -> Result<Vec<DuplicateGroup>, String> {
     let mut duplicates = Vec::new();
+    let active = |base: &BaseEntity| !inactive_paths.contains(&base.path);
     
     // Find duplicate agents
     let mut agent_map: HashMap<String, Vec<&AgentEntity>> = HashMap::new();
-    for agent in agents {
+    for agent in agents.iter().filter(|a| active(&a.base)) {
         agent_map.entry(agent.base.name.clone()).or_default().push(agent);
     }
     for (name, entities) in agent_map {
@@ -1654,6 +2214,8 @@ fn find_duplicates_internal(
                         scope: e.base.scope.clone(),
                         project_path: e.base.project_path.clone(),
                         precedence: if e.base.scope == "project" { idx as u32 } else { (idx + 100) as u32 },
+                        read_only: e.base.read_only,
+                        json_pointer: e.json_pointer.clone(),
                     }
                 }).collect(),
             });
@@ -1662,7 +2224,7 @@ fn find_duplicates_internal(
     
     // Find duplicate skills
     let mut skill_map: HashMap<String, Vec<&SkillEntity>> = HashMap::new();
-    for skill in skills {
+    for skill in skills.iter().filter(|s| active(&s.base)) {
         skill_map.entry(skill.base.name.clone()).or_default().push(skill);
     }
     for (name, entities) in skill_map {
@@ -1677,6 +2239,8 @@ fn find_duplicates_internal(
                         scope: e.base.scope.clone(),
                         project_path: e.base.project_path.clone(),
                         precedence: if e.base.scope == "project" { idx as u32 } else { (idx + 100) as u32 },
+                        read_only: e.base.read_only,
+                        json_pointer: None,
                     }
                 }).collect(),
             });
@@ -1685,7 +2249,7 @@ fn find_duplicates_internal(
     
     // Find duplicate commands
     let mut command_map: HashMap<String, Vec<&CommandEntity>> = HashMap::new();
-    for command in commands {
+    for command in commands.iter().filter(|c| active(&c.base)) {
         command_map.entry(command.base.name.clone()).or_default().push(command);
     }
     for (name, entities) in command_map {
@@ -1700,6 +2264,8 @@ fn find_duplicates_internal(
                         scope: e.base.scope.clone(),
                         project_path: e.base.project_path.clone(),
                         precedence: if e.base.scope == "project" { idx as u32 } else { (idx + 100) as u32 },
+                        read_only: e.base.read_only,
+                        json_pointer: e.json_pointer.clone(),
                     }
                 }).collect(),
             });
@@ -1713,61 +2279,61 @@ fn find_duplicates_internal(
 // Public Tauri Commands
 // ============================================================================
 
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub fn discover_settings() -> Result<Vec<SettingsEntity>, String> {
     let home = get_home_dir().ok_or("Could not find home directory")?;
     let global_claude_path = home.join(".claude");
     discover_settings_internal(&global_claude_path, "global", None, "claude")
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub fn discover_memory() -> Result<Vec<MemoryEntity>, String> {
     let home = get_home_dir().ok_or("Could not find home directory")?;
     let global_claude_path = home.join(".claude");
     discover_memory_internal(&global_claude_path, &home, "global", None, "claude")
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub fn discover_agents() -> Result<Vec<AgentEntity>, String> {
     let home = get_home_dir().ok_or("Could not find home directory")?;
     let agents_dir = home.join(".claude").join("agents");
     discover_agents_internal(&agents_dir, "global", None, "claude")
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub fn discover_skills() -> Result<Vec<SkillEntity>, String> {
     let home = get_home_dir().ok_or("Could not find home directory")?;
     let skills_dir = home.join(".claude").join("skills");
     discover_skills_internal(&skills_dir, "global", None, "claude")
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub fn discover_commands() -> Result<Vec<CommandEntity>, String> {
     let home = get_home_dir().ok_or("Could not find home directory")?;
     let commands_dir = home.join(".claude").join("commands");
     discover_commands_internal(&commands_dir, "global", None, "claude")
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub fn discover_plugins() -> Result<Vec<PluginEntity>, String> {
     let home = get_home_dir().ok_or("Could not find home directory")?;
     let plugins_dir = home.join(".claude").join("plugins");
     discover_plugins_internal(&plugins_dir, "global", None, "claude")
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub fn discover_mcp_servers() -> Result<Vec<McpServerEntity>, String> {
     let home = get_home_dir().ok_or("Could not find home directory")?;
     discover_mcp_from_claude_json(&home)
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub fn extract_hooks(settings_path: String) -> Result<Vec<HookEntity>, String> {
     let path = PathBuf::from(&settings_path);
     extract_hooks_internal(&path, "global", "claude")
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub fn scan_projects(base_paths: Vec<String>) -> Result<Vec<ProjectInfo>, String> {
     let mut projects = Vec::new();
     let mut seen_paths = std::collections::HashSet::new();
@@ -1778,6 +2344,7 @@ pub fn scan_projects(base_paths: Vec<String>) -> Result<Vec<ProjectInfo>, String
     let plugins_path = get_home_dir()
         .map(|h| h.join(".claude").join("plugins"))
         .unwrap_or_default();
+    let claude_json = get_home_dir().and_then(|h| parse_json_file(&h.join(".claude.json")));
 
     // Directories to skip entirely (won't descend into these)
     let skip_dirs: std::collections::HashSet<&str> = [
@@ -1848,6 +2415,9 @@ pub fn scan_projects(base_paths: Vec<String>) -> Result<Vec<ProjectInfo>, String
             let name = path.file_name()
                 .map(|n| n.to_string_lossy().into_owned())
                 .unwrap_or_else(|| path.to_string_lossy().into_owned());
+            let local_state = claude_json.as_ref()
+                .and_then(|c| claude_json_project_entry(c, &path_str))
+                .map(project_local_state);
             
             projects.push(ProjectInfo {
                 path: path_str,
@@ -1870,6 +2440,7 @@ pub fn scan_projects(base_paths: Vec<String>) -> Result<Vec<ProjectInfo>, String
                     mcp: 0,
                 },
                 config_state: Some(detect_config_state(&path)),
+                local_state,
             });
         }
         
@@ -1887,19 +2458,31 @@ pub fn scan_projects(base_paths: Vec<String>) -> Result<Vec<ProjectInfo>, String
     Ok(projects)
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub fn find_duplicates() -> Result<Vec<DuplicateGroup>, String> {
     let home = get_home_dir().ok_or("Could not find home directory")?;
     let claude_dir = home.join(".claude");
     
-    let agents = discover_agents_internal(&claude_dir.join("agents"), "global", None, "claude")?;
-    let skills = discover_skills_internal(&claude_dir.join("skills"), "global", None, "claude")?;
-    let commands = discover_commands_internal(&claude_dir.join("commands"), "global", None, "claude")?;
+    let mut agents = discover_agents_internal(&claude_dir.join("agents"), "global", None, "claude")?;
+    let mut skills = discover_skills_internal(&claude_dir.join("skills"), "global", None, "claude")?;
+    let mut commands = discover_commands_internal(&claude_dir.join("commands"), "global", None, "claude")?;
+
+    // Plugin agents, skills and commands can shadow (or be shadowed by) the user's own
+    let mut plugins = discover_plugins_internal(&claude_dir.join("plugins"), "global", None, "claude")?;
+    plugins.extend(discover_installed_plugins(&home)?);
+    let contents = collect_plugin_contents(&mut plugins);
+    let mut seen_paths: std::collections::HashSet<String> = agents.iter().map(|a| a.base.path.clone())
+        .chain(skills.iter().map(|s| s.base.path.clone()))
+        .chain(commands.iter().map(|c| c.base.path.clone()))
+        .collect();
+    agents.extend(contents.agents.into_iter().filter(|a| seen_paths.insert(a.base.path.clone())));
+    skills.extend(contents.skills.into_iter().filter(|s| seen_paths.insert(s.base.path.clone())));
+    commands.extend(contents.commands.into_iter().filter(|c| seen_paths.insert(c.base.path.clone())));
     
-    find_duplicates_internal(&agents, &skills, &commands)
+    find_duplicates_internal(&agents, &skills, &commands, &contents.inactive_paths)
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub fn check_symlink(path: String) -> Result<Option<SymlinkInfo>, String> {
     let path_buf = PathBuf::from(&path);
 
@@ -1925,7 +2508,7 @@ pub fn check_symlink(path: String) -> Result<Option<SymlinkInfo>, String> {
 // Config State Commands (AGENTS.md / CLAUDE.md consistency)
 // ============================================================================
 
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub fn get_project_config_state(project_path: String) -> Result<ConfigState, String> {
     let path_buf = PathBuf::from(&project_path);
     if !path_buf.is_dir() {
@@ -1934,7 +2517,7 @@ pub fn get_project_config_state(project_path: String) -> Result<ConfigState, Str
     Ok(detect_config_state(&path_buf))
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub fn fix_project_config(project_path: String) -> Result<String, String> {
     let path_buf = PathBuf::from(&project_path);
     if !path_buf.is_dir() {
@@ -2007,12 +2590,12 @@ pub fn fix_project_config(project_path: String) -> Result<String, String> {
 // File Operations
 // ============================================================================
 
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub fn read_file(path: String) -> Result<String, String> {
     fs::read_to_string(&path).map_err(|e| e.to_string())
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub fn write_file(path: String, content: String) -> Result<(), String> {
     if let Some(parent) = PathBuf::from(&path).parent() {
         fs::create_dir_all(parent).map_err(|e| e.to_string())?;
@@ -2020,12 +2603,12 @@ pub fn write_file(path: String, content: String) -> Result<(), String> {
     fs::write(&path, content).map_err(|e| e.to_string())
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub fn file_exists(path: String) -> bool {
     PathBuf::from(&path).exists()
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub fn delete_file(path: String) -> Result<(), String> {
     let path = PathBuf::from(&path);
     if !path.exists() {
@@ -2034,7 +2617,7 @@ pub fn delete_file(path: String) -> Result<(), String> {
     fs::remove_file(&path).map_err(|e| e.to_string())
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub fn delete_directory(path: String) -> Result<(), String> {
     let path = PathBuf::from(&path);
     if !path.exists() {
@@ -2048,7 +2631,7 @@ pub fn delete_directory(path: String) -> Result<(), String> {
 // ============================================================================
 
 /// Copy an entity to a new location (global or project scope)
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub fn copy_entity(
     source_path: String,
     entity_type: String,
@@ -2145,7 +2728,7 @@ fn copy_dir_recursive(src: &std::path::Path, dst: &PathBuf) -> Result<(), String
 }
 
 /// Create a symlink from target to source
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub fn create_entity_symlink(
     source_path: String,
     entity_type: String,
@@ -2236,8 +2819,40 @@ pub fn create_entity_symlink(
     Ok(link_path.to_string_lossy().to_string())
 }
 
+/// Only an entity's own markdown file (or a link to one) may be renamed or
+/// deleted. Inline OpenCode agents and commands report opencode.json as their
+/// path, so without this the whole config file would go.
+fn ensure_entity_file(path: &Path) -> Result<(), String> {
+    if path.is_symlink() || path.extension().is_some_and(|ext| ext == "md") {
+        return Ok(());
+    }
+    Err(format!("{} is not an entity file; edit its definition in place instead", path.display()))
+}
+
+/// Refuse paths a plugin owns: anything under a `.claude/plugins` directory
+/// (the marketplace cache included) or an installed plugin's directory. Plugin
+/// files are replaced on update, so they are read-only here as well as in the UI.
+pub(crate) fn ensure_not_plugin_owned(path: &Path) -> Result<(), String> {
+    let resolved = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let in_plugins_dir = |p: &Path| p.ancestors().any(|dir| {
+        dir.file_name().is_some_and(|n| n == "plugins")
+            && dir.parent().and_then(Path::file_name).is_some_and(|n| n == ".claude")
+    });
+    let installed = get_home_dir()
+        .and_then(|home| discover_installed_plugins(&home).ok())
+        .unwrap_or_default();
+    let owned = [path, resolved.as_path()].into_iter().any(|p| {
+        // Every path starts with "", so an entry without a directory owns nothing
+        in_plugins_dir(p) || installed.iter().any(|plugin| !plugin.plugin_dir.is_empty() && p.starts_with(&plugin.plugin_dir))
+    });
+    if owned {
+        return Err(format!("{} belongs to a plugin and is read-only", path.display()));
+    }
+    Ok(())
+}
+
 /// Rename an entity (move to new name in same directory)
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub fn rename_entity(
     source_path: String,
     new_name: String,
@@ -2247,6 +2862,8 @@ pub fn rename_entity(
     if !source.exists() {
         return Err("Source file does not exist".to_string());
     }
+    ensure_entity_file(&source)?;
+    ensure_not_plugin_owned(&source)?;
     
     let parent = source.parent().ok_or("Invalid source path")?;
     
@@ -2286,7 +2903,7 @@ pub fn rename_entity(
 }
 
 /// Delete an entity (file or skill directory)
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub fn delete_entity(
     path: String,
     entity_type: String,
@@ -2296,6 +2913,8 @@ pub fn delete_entity(
     if !path.exists() && !path.is_symlink() {
         return Err("Entity does not exist".to_string());
     }
+    ensure_entity_file(&path)?;
+    ensure_not_plugin_owned(&path)?;
     
     // Handle symlinks
     if path.is_symlink() {
@@ -2315,7 +2934,7 @@ pub fn delete_entity(
 }
 
 /// Duplicate an entity within the same scope (creates a copy with new name)
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub fn duplicate_entity(
     source_path: String,
     entity_type: String,
@@ -2324,6 +2943,8 @@ pub fn duplicate_entity(
     if !source.exists() {
         return Err("Source file does not exist".to_string());
     }
+    // The copy would land inside the plugin
+    ensure_not_plugin_owned(&source)?;
     
     let parent = source.parent().ok_or("Invalid source path")?;
     
@@ -2379,17 +3000,17 @@ pub fn duplicate_entity(
 // Entity Creation
 // ============================================================================
 
-#[tauri::command]
-pub fn create_entity(
-    entity_type: String,
-    name: String,
-    scope: String,
-    project_path: Option<String>,
+/// Where a new entity would be written and its content (the given content or
+/// a starter template), without touching the filesystem
+pub(crate) fn entity_target(
+    entity_type: &str,
+    name: &str,
+    scope: &str,
+    project_path: Option<&str>,
     content: Option<String>,
-    tool: Option<String>,  // "claude" or "opencode"
-) -> Result<String, String> {
+    tool: &str,
+) -> Result<(PathBuf, String), String> {
     let home = get_home_dir().ok_or("Could not find home directory")?;
-    let tool = tool.unwrap_or_else(|| "claude".to_string());
     
     // Determine base directory based on tool
     let (config_dir_name, memory_file_name) = if tool == "opencode" {
@@ -2411,7 +3032,7 @@ pub fn create_entity(
             .ok_or("Project path required for project-scoped entities")?
     };
     
-    let (file_path, file_content) = match entity_type.as_str() {
+    let (file_path, file_content) = match entity_type {
         "agent" => {
             // OpenCode uses singular "agent", Claude uses plural "agents"
             let agents_dir = if tool == "opencode" { "agent" } else { "agents" };
@@ -2427,9 +3048,7 @@ pub fn create_entity(
         "skill" => {
             // OpenCode uses singular "skill", Claude uses plural "skills"
             let skills_dir = if tool == "opencode" { "skill" } else { "skills" };
-            let skill_dir = base_dir.join(skills_dir).join(&name);
-            fs::create_dir_all(&skill_dir).map_err(|e| e.to_string())?;
-            let path = skill_dir.join("SKILL.md");
+            let path = base_dir.join(skills_dir).join(name).join("SKILL.md");
             let content = content.unwrap_or_else(|| {
                 format!(
                     "---\nname: {}\ndescription: A custom skill\n---\n\n# {} Skill\n\n## When to use this skill\n\nUse this skill when...\n\n## Instructions\n\nFollow these steps...\n",
@@ -2467,6 +3086,20 @@ pub fn create_entity(
         }
         _ => return Err(format!("Unknown entity type: {}", entity_type)),
     };
+    Ok((file_path, file_content))
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn create_entity(
+    entity_type: String,
+    name: String,
+    scope: String,
+    project_path: Option<String>,
+    content: Option<String>,
+    tool: Option<String>,  // "claude" or "opencode"
+) -> Result<String, String> {
+    let tool = tool.unwrap_or_else(|| "claude".to_string());
+    let (file_path, file_content) = entity_target(&entity_type, &name, &scope, project_path.as_deref(), content, &tool)?;
     
     // Create parent directories
     if let Some(parent) = file_path.parent() {
@@ -2483,7 +3116,7 @@ pub fn create_entity(
 // Legacy Commands (backward compatibility)
 // ============================================================================
 
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub fn discover_configs(project_path: Option<String>) -> Result<DiscoveredConfigs, String> {
     let home = get_home_dir().ok_or("Could not find home directory")?;
     let config_dir = get_config_dir().ok_or("Could not find config directory")?;
@@ -2716,7 +3349,7 @@ fn discover_skills_legacy(dir: &PathBuf, tool: &str, scope: &str) -> Vec<AgentFi
     skills
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub fn create_agent(path: String, name: String, description: String, tools: Vec<String>, model: String, prompt: String) -> Result<(), String> {
     let frontmatter = format!(
         "---\nname: {}\ndescription: {}\ntools: {}\nmodel: {}\n---\n\n{}",
@@ -2730,7 +3363,7 @@ pub fn create_agent(path: String, name: String, description: String, tools: Vec<
     write_file(path, frontmatter)
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub fn create_skill(base_path: String, name: String, description: String, content: String) -> Result<String, String> {
     let skill_dir = PathBuf::from(&base_path).join(&name);
     fs::create_dir_all(&skill_dir).map_err(|e| e.to_string())?;
@@ -2747,7 +3380,7 @@ pub fn create_skill(base_path: String, name: String, description: String, conten
     Ok(skill_file.to_string_lossy().to_string())
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub fn delete_skill(path: String) -> Result<(), String> {
     let path = PathBuf::from(&path);
     
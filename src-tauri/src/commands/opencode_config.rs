@@ -0,0 +1,231 @@
+// ============================================================================
+// Agent Studio - OpenCode Inline Definitions
+// Agents and commands defined under `agent` / `command` in opencode.json,
+// and the instruction files its `instructions` globs pull in
+// ============================================================================
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::json_edit;
+use super::{
+    generate_id, get_home_dir, get_last_modified, is_symlink_with_target, read_file_content, AgentEntity,
+    BaseEntity, CommandEntity, MemoryEntity,
+};
+
+/// Directories never descended into by a `**` instruction glob
+const SKIPPED_DIRS: &[&str] = &["node_modules", ".git", "target", "dist", "build"];
+
+/// How deep a `**` glob searches below its starting directory
+const MAX_GLOB_DEPTH: usize = 8;
+
+/// Entities defined inside one opencode.json(c)
+#[derive(Default)]
+pub(crate) struct InlineDefinitions {
+    pub agents: Vec<AgentEntity>,
+    pub commands: Vec<CommandEntity>,
+    pub instructions: Vec<MemoryEntity>,
+}
+
+// ============================================================================
+// Glob Expansion
+// ============================================================================
+
+/// Match one path segment against a pattern with `*` and `?`
+fn segment_matches(pattern: &str, name: &str) -> bool {
+    let (p, n): (Vec<char>, Vec<char>) = (pattern.chars().collect(), name.chars().collect());
+    let (mut pi, mut ni) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while ni < n.len() {
+        match p.get(pi) {
+            Some('*') => {
+                backtrack = Some((pi, ni));
+                pi += 1;
+            }
+            Some(&c) if c == '?' || c == n[ni] => {
+                pi += 1;
+                ni += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    pi = star + 1;
+                    ni = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    p[pi..].iter().all(|&c| c == '*')
+}
+
+fn sorted_entries(dir: &Path) -> Vec<PathBuf> {
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|rd| rd.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default();
+    entries.sort();
+    entries
+}
+
+fn expand_segments(dir: &Path, segments: &[&str], depth: usize, out: &mut Vec<PathBuf>) {
+    let Some((segment, rest)) = segments.split_first() else {
+        if dir.is_file() {
+            out.push(dir.to_path_buf());
+        }
+        return;
+    };
+    match *segment {
+        "**" => {
+            // Zero directories, then one more level down
+            expand_segments(dir, rest, depth, out);
+            if depth < MAX_GLOB_DEPTH {
+                for entry in sorted_entries(dir) {
+                    let skipped = entry.file_name().is_some_and(|n| SKIPPED_DIRS.contains(&n.to_string_lossy().as_ref()));
+                    if entry.is_dir() && !entry.is_symlink() && !skipped {
+                        expand_segments(&entry, segments, depth + 1, out);
+                    }
+                }
+            }
+        }
+        "" | "." => expand_segments(dir, rest, depth, out),
+        _ if segment.contains(['*', '?']) => {
+            for entry in sorted_entries(dir) {
+                let name = entry.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                if segment_matches(segment, &name) {
+                    expand_segments(&entry, rest, depth, out);
+                }
+            }
+        }
+        _ => expand_segments(&dir.join(segment), rest, depth, out),
+    }
+}
+
+/// Files matching an `instructions` entry: a path or glob, relative to `base`,
+/// absolute or starting with `~/`
+fn expand_instruction(base: &Path, pattern: &str) -> Vec<PathBuf> {
+    let (root, pattern) = if let Some(rest) = pattern.strip_prefix("~/") {
+        match get_home_dir() {
+            Some(home) => (home, rest),
+            None => return Vec::new(),
+        }
+    } else if let Some(rest) = pattern.strip_prefix('/') {
+        (PathBuf::from("/"), rest)
+    } else {
+        (base.to_path_buf(), pattern)
+    };
+    let segments: Vec<&str> = pattern.split('/').collect();
+    let mut files = Vec::new();
+    expand_segments(&root, &segments, 0, &mut files);
+    files.dedup();
+    files
+}
+
+// ============================================================================
+// Discovery
+// ============================================================================
+
+/// The `agent`, `command` and `instructions` entries of the opencode.json(c)
+/// in `config_dir`. Relative instruction paths resolve against `base_path`
+/// (the project root, or the config directory for the global config).
+pub(crate) fn discover_opencode_inline(
+    config_dir: &Path,
+    base_path: &Path,
+    scope: &str,
+    project_path: Option<&str>,
+) -> InlineDefinitions {
+    let mut found = InlineDefinitions::default();
+    let json_path = config_dir.join("opencode.json");
+    let jsonc_path = config_dir.join("opencode.jsonc");
+    let config_path = if json_path.exists() { json_path } else { jsonc_path };
+    let Some(config) = read_file_content(&config_path).and_then(|c| json_edit::to_value(&c).ok()) else {
+        return found;
+    };
+    let file = config_path.to_string_lossy().to_string();
+    let last_modified = get_last_modified(&config_path);
+
+    // Inline entries live in the config file, so they're edited there rather than through the markdown editor
+    let base = |kind: &str, name: &str, pointer: &str, content: Option<String>| BaseEntity {
+        id: generate_id(kind, &format!("{}#{}", file, pointer)),
+        name: name.to_string(),
+        path: file.clone(),
+        scope: scope.to_string(),
+        project_path: project_path.map(String::from),
+        is_symlink: false,
+        symlink_target: None,
+        content,
+        last_modified,
+        tool: "opencode".to_string(),
+        is_from_plugin: false,
+        plugin_name: None,
+        read_only: true,
+    };
+    // Everything except the body key reads like markdown frontmatter
+    let split = |entry: &serde_json::Value, body_key: &str| -> (Option<String>, HashMap<String, serde_json::Value>) {
+        let mut fields: HashMap<String, serde_json::Value> = entry.as_object()
+            .map(|o| o.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+            .unwrap_or_default();
+        let body = fields.remove(body_key).and_then(|b| b.as_str().map(String::from));
+        (body, fields)
+    };
+
+    if let Some(agents) = config.get("agent").and_then(|a| a.as_object()) {
+        for (name, entry) in agents {
+            let pointer = json_edit::build_pointer(&["agent", name]);
+            let (prompt, fields) = split(entry, "prompt");
+            found.agents.push(AgentEntity {
+                base: base("agent", name, &pointer, prompt),
+                entity_type: "agent".to_string(),
+                frontmatter: Some(fields),
+                json_pointer: Some(pointer),
+            });
+        }
+    }
+
+    if let Some(commands) = config.get("command").and_then(|c| c.as_object()) {
+        for (name, entry) in commands {
+            let pointer = json_edit::build_pointer(&["command", name]);
+            let (template, fields) = split(entry, "template");
+            found.commands.push(CommandEntity {
+                base: base("command", name, &pointer, template),
+                entity_type: "command".to_string(),
+                namespace: None,
+                frontmatter: Some(fields),
+                json_pointer: Some(pointer),
+            });
+        }
+    }
+
+    let patterns = config.get("instructions").and_then(|i| i.as_array()).into_iter().flatten().filter_map(|p| p.as_str());
+    for pattern in patterns {
+        // Remote instructions are fetched by OpenCode at startup; there's no local file to show
+        if pattern.starts_with("http://") || pattern.starts_with("https://") {
+            continue;
+        }
+        for path in expand_instruction(base_path, pattern) {
+            let (is_symlink, symlink_target) = is_symlink_with_target(&path);
+            let name = path.strip_prefix(base_path).unwrap_or(&path).to_string_lossy().to_string();
+            found.instructions.push(MemoryEntity {
+                base: BaseEntity {
+                    id: generate_id("memory", &path.to_string_lossy()),
+                    name,
+                    path: path.to_string_lossy().to_string(),
+                    scope: scope.to_string(),
+                    project_path: project_path.map(String::from),
+                    is_symlink,
+                    symlink_target,
+                    content: read_file_content(&path),
+                    last_modified: get_last_modified(&path),
+                    tool: "opencode".to_string(),
+                    is_from_plugin: false,
+                    plugin_name: None,
+                    read_only: false,
+                },
+                entity_type: "memory".to_string(),
+                variant: "instructions".to_string(),
+            });
+        }
+    }
+
+    found
+}
@@ -0,0 +1,450 @@
+// ============================================================================
+// Agent Studio - Permissions Audit
+// Flags risky, dead or redundant entries in settings.json permission lists
+// ============================================================================
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use super::json_edit;
+use super::{discover_settings_internal, generate_id, get_home_dir, Severity, SettingsEntity};
+
+/// Built-in Claude Code tool names that may appear in permission rules
+pub(crate) const KNOWN_TOOLS: &[&str] = &[
+    "Agent", "Bash", "BashOutput", "Edit", "ExitPlanMode", "Glob", "Grep", "KillShell",
+    "LS", "MultiEdit", "NotebookEdit", "NotebookRead", "Read", "SlashCommand", "Skill",
+    "Task", "TodoWrite", "WebFetch", "WebSearch", "Write",
+];
+
+/// Tools that modify files; allowing them without a path specifier is broad
+const FILE_WRITE_TOOLS: &[&str] = &["Edit", "MultiEdit", "NotebookEdit", "Write"];
+
+/// Bash command prefixes that are destructive or reach outside the machine
+const DANGEROUS_BASH_PREFIXES: &[&str] = &[
+    "sudo", "rm", "curl", "wget", "chmod", "chown", "dd", "mkfs", "eval", "ssh", "scp",
+    "git push", "npm publish", "cargo publish",
+];
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// A single RFC 6902 style operation against a settings file
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum JsonPatchOp {
+    /// Fail the patch unless the value at `path` equals `value`
+    Test { path: String, value: serde_json::Value },
+    /// Set the value at `path`, creating missing parent objects
+    Add { path: String, value: serde_json::Value },
+    Remove { path: String },
+    Replace { path: String, value: serde_json::Value },
+}
+
+/// An automatically applicable fix for a permission finding
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PermissionFix {
+    pub description: String,
+    pub file: String,
+    pub ops: Vec<JsonPatchOp>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PermissionFinding {
+    pub id: String,
+    pub kind: String,  // "over_broad_allow", "bypass_mode", "shadowed_allow", "unreachable_rule", "duplicate_rule"
+    pub severity: Severity,
+    pub title: String,
+    pub description: String,
+    pub file: String,
+    pub layer: String,  // "global", "project", "local"
+    pub pointer: String,
+    pub rule: Option<String>,
+    pub fix: Option<PermissionFix>,
+}
+
+/// A permission rule such as `Bash(npm run test:*)` split into its parts
+struct PermissionRule<'a> {
+    tool: &'a str,
+    specifier: Option<&'a str>,
+}
+
+/// A rule together with where it was found
+struct LocatedRule<'a> {
+    rule: &'a str,
+    list: &'static str,
+    index: usize,
+    layer: &'a str,
+    file: &'a str,
+}
+
+// ============================================================================
+// Rule Parsing and Matching
+// ============================================================================
+
+fn parse_rule(rule: &str) -> Result<PermissionRule<'_>, String> {
+    let rule = rule.trim();
+    let (tool, specifier) = match rule.find('(') {
+        Some(open) => {
+            if !rule.ends_with(')') {
+                return Err("missing closing parenthesis".to_string());
+            }
+            (&rule[..open], Some(&rule[open + 1..rule.len() - 1]))
+        }
+        None => {
+            if rule.contains(')') {
+                return Err("unexpected closing parenthesis".to_string());
+            }
+            (rule, None)
+        }
+    };
+
+    if tool.is_empty() {
+        return Err("empty tool name".to_string());
+    }
+    if !tool.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        return Err(format!("invalid tool name \"{}\"", tool));
+    }
+    if specifier.is_some_and(|s| s.trim().is_empty()) {
+        return Err("empty specifier".to_string());
+    }
+
+    Ok(PermissionRule { tool, specifier })
+}
+
+fn is_known_tool(tool: &str) -> bool {
+    KNOWN_TOOLS.contains(&tool) || tool.starts_with("mcp__")
+}
+
+/// Whether a specifier matches every invocation of its tool
+fn is_wildcard_specifier(specifier: Option<&str>) -> bool {
+    match specifier {
+        None => true,
+        Some(s) => matches!(s.trim(), "*" | "**" | ":*" | "/**" | "./**" | "~/**"),
+    }
+}
+
+/// Strip trailing wildcard syntax (`:*`, `*`, `**`) from a specifier
+fn specifier_prefix(specifier: &str) -> &str {
+    let s = specifier.strip_suffix(":*").unwrap_or(specifier);
+    s.trim_end_matches('*')
+}
+
+/// Whether `deny` matches every invocation that `allow` matches
+fn rule_covers(deny: &PermissionRule, allow: &PermissionRule) -> bool {
+    if deny.tool != allow.tool {
+        return false;
+    }
+    match (deny.specifier, allow.specifier) {
+        (None, _) => true,
+        (Some(d), _) if is_wildcard_specifier(Some(d)) => true,
+        (Some(_), None) => false,
+        (Some(d), Some(a)) => {
+            if d == a {
+                return true;
+            }
+            if !d.ends_with('*') {
+                return false;
+            }
+            match specifier_prefix(a).strip_prefix(specifier_prefix(d)) {
+                None => false,
+                // `npm:*` is the npm command and its arguments, not every command starting "npm"
+                Some(rest) if d.ends_with(":*") => rest.is_empty() || rest.starts_with([' ', ':']),
+                Some(_) => true,
+            }
+        }
+    }
+}
+
+/// Find a dangerous Bash prefix that a specifier starts with, on a word boundary
+fn dangerous_bash_prefix(specifier: &str) -> Option<&'static str> {
+    let command = specifier_prefix(specifier).trim();
+    DANGEROUS_BASH_PREFIXES.iter().copied().find(|prefix| {
+        command == *prefix
+            || command.strip_prefix(prefix).is_some_and(|rest| rest.starts_with(' ') || rest.starts_with(':'))
+    })
+}
+
+/// Suggest a corrected rule for a rule that can never match
+fn suggest_rule_correction(rule: &str) -> Option<String> {
+    let trimmed = rule.trim();
+    let candidate = if trimmed.contains('(') && !trimmed.ends_with(')') {
+        format!("{})", trimmed)
+    } else {
+        trimmed.to_string()
+    };
+
+    let (tool, rest) = match candidate.find('(') {
+        Some(open) => (&candidate[..open], &candidate[open..]),
+        None => (candidate.as_str(), ""),
+    };
+    let known = KNOWN_TOOLS.iter().find(|t| t.eq_ignore_ascii_case(tool))?;
+    let corrected = format!("{}{}", known, rest);
+
+    match parse_rule(&corrected) {
+        Ok(_) if corrected != rule => Some(corrected),
+        _ => None,
+    }
+}
+
+// ============================================================================
+// Fix Helpers
+// ============================================================================
+
+fn rule_pointer(list: &str, index: usize) -> String {
+    json_edit::build_pointer(&["permissions", list, &index.to_string()])
+}
+
+fn remove_rule_fix(file: &str, pointer: &str, rule: &str, description: String) -> PermissionFix {
+    PermissionFix {
+        description,
+        file: file.to_string(),
+        ops: vec![
+            JsonPatchOp::Test { path: pointer.to_string(), value: serde_json::Value::String(rule.to_string()) },
+            JsonPatchOp::Remove { path: pointer.to_string() },
+        ],
+    }
+}
+
+/// Apply a patch operation to the text of a settings file without reformatting it
+pub(crate) fn apply_patch_op(text: &str, op: &JsonPatchOp) -> Result<String, String> {
+    match op {
+        JsonPatchOp::Test { path, value } => {
+            let current = json_edit::get(text, path)?
+                .ok_or_else(|| format!("Path not found: {}", path))?;
+            if &current != value {
+                return Err(format!("Value at {} has changed since the audit ran", path));
+            }
+            Ok(text.to_string())
+        }
+        JsonPatchOp::Add { path, value } => json_edit::set(text, path, value),
+        JsonPatchOp::Remove { path } => json_edit::remove(text, path),
+        JsonPatchOp::Replace { path, value } => {
+            if json_edit::get(text, path)?.is_none() {
+                return Err(format!("Path not found: {}", path));
+            }
+            json_edit::set(text, path, value)
+        }
+    }
+}
+
+// ============================================================================
+// Audit
+// ============================================================================
+
+fn string_list<'a>(permissions: &'a serde_json::Value, list: &str) -> Vec<&'a str> {
+    permissions.get(list)
+        .and_then(|v| v.as_array())
+        .map(|arr| arr.iter().filter_map(|v| v.as_str()).collect())
+        .unwrap_or_default()
+}
+
+/// Audit the permission rules of a set of settings layers.
+/// Layers should be ordered from least to most specific (global, project, local).
+fn audit_permissions_internal(layers: &[SettingsEntity]) -> Vec<PermissionFinding> {
+    let mut findings = Vec::new();
+    let mut rules: Vec<LocatedRule> = Vec::new();
+
+    let finding_id = |file: &str, pointer: &str, kind: &str| {
+        generate_id("perm", &format!("{}#{}#{}", file, pointer, kind))
+    };
+
+    for layer in layers {
+        if layer.base.tool != "claude" {
+            continue;
+        }
+        let permissions = match layer.parsed.as_ref().and_then(|p| p.get("permissions")) {
+            Some(p) => p,
+            None => continue,
+        };
+        let file = layer.base.path.as_str();
+
+        if permissions.get("defaultMode").and_then(|m| m.as_str()) == Some("bypassPermissions") {
+            let pointer = "/permissions/defaultMode".to_string();
+            findings.push(PermissionFinding {
+                id: finding_id(file, &pointer, "bypass_mode"),
+                kind: "bypass_mode".to_string(),
+                severity: Severity::Error,
+                title: "Permission prompts are bypassed".to_string(),
+                description: "defaultMode is bypassPermissions, so every tool call runs without asking".to_string(),
+                file: file.to_string(),
+                layer: layer.variant.clone(),
+                pointer: pointer.clone(),
+                rule: None,
+                fix: Some(PermissionFix {
+                    description: "Set defaultMode to \"default\"".to_string(),
+                    file: file.to_string(),
+                    ops: vec![JsonPatchOp::Replace {
+                        path: pointer,
+                        value: serde_json::Value::String("default".to_string()),
+                    }],
+                }),
+            });
+        }
+
+        for list in ["allow", "deny", "ask"] {
+            for (index, rule) in string_list(permissions, list).into_iter().enumerate() {
+                rules.push(LocatedRule { rule, list, index, layer: &layer.variant, file });
+            }
+        }
+    }
+
+    // Over-broad and unparseable rules
+    for located in &rules {
+        let pointer = rule_pointer(located.list, located.index);
+        let parsed = parse_rule(located.rule);
+
+        let unreachable_reason = match &parsed {
+            Err(reason) => Some(reason.clone()),
+            Ok(rule) if !is_known_tool(rule.tool) => Some(format!("unknown tool \"{}\"", rule.tool)),
+            Ok(_) => None,
+        };
+        if let Some(reason) = unreachable_reason {
+            let fix = suggest_rule_correction(located.rule).map(|corrected| PermissionFix {
+                description: format!("Replace with \"{}\"", corrected),
+                file: located.file.to_string(),
+                ops: vec![
+                    JsonPatchOp::Test { path: pointer.clone(), value: serde_json::Value::String(located.rule.to_string()) },
+                    JsonPatchOp::Replace { path: pointer.clone(), value: serde_json::Value::String(corrected) },
+                ],
+            });
+            findings.push(PermissionFinding {
+                id: finding_id(located.file, &pointer, "unreachable_rule"),
+                kind: "unreachable_rule".to_string(),
+                severity: if located.list == "deny" { Severity::Warning } else { Severity::Info },
+                title: format!("{} rule \"{}\" can never match", located.list, located.rule),
+                description: format!("The rule is ignored by Claude Code: {}", reason),
+                file: located.file.to_string(),
+                layer: located.layer.to_string(),
+                pointer,
+                rule: Some(located.rule.to_string()),
+                fix,
+            });
+            continue;
+        }
+
+        let rule = match parsed {
+            Ok(rule) => rule,
+            Err(_) => continue,
+        };
+        if located.list != "allow" {
+            continue;
+        }
+
+        let broad = if rule.tool == "Bash" && is_wildcard_specifier(rule.specifier) {
+            Some((Severity::Error, "Allows any shell command without asking".to_string()))
+        } else if FILE_WRITE_TOOLS.contains(&rule.tool) && is_wildcard_specifier(rule.specifier) {
+            Some((Severity::Warning, format!("Allows {} on any file without asking", rule.tool)))
+        } else if rule.tool == "Bash" {
+            rule.specifier.and_then(dangerous_bash_prefix).map(|prefix| {
+                (Severity::Warning, format!("Allows \"{}\" commands without asking", prefix))
+            })
+        } else if rule.tool == "WebFetch" && rule.specifier.is_none() {
+            Some((Severity::Info, "Allows fetching any URL without asking".to_string()))
+        } else {
+            None
+        };
+
+        if let Some((severity, description)) = broad {
+            findings.push(PermissionFinding {
+                id: finding_id(located.file, &pointer, "over_broad_allow"),
+                kind: "over_broad_allow".to_string(),
+                severity,
+                title: format!("Over-broad allow rule \"{}\"", located.rule),
+                description,
+                file: located.file.to_string(),
+                layer: located.layer.to_string(),
+                pointer: pointer.clone(),
+                rule: Some(located.rule.to_string()),
+                fix: Some(remove_rule_fix(located.file, &pointer, located.rule, format!("Remove \"{}\" from allow", located.rule))),
+            });
+        }
+    }
+
+    // Allow rules shadowed by a deny rule (deny wins regardless of layer)
+    let deny_rules: Vec<(&LocatedRule, PermissionRule)> = rules.iter()
+        .filter(|r| r.list == "deny")
+        .filter_map(|r| parse_rule(r.rule).ok().map(|p| (r, p)))
+        .collect();
+    for located in rules.iter().filter(|r| r.list == "allow") {
+        let allow = match parse_rule(located.rule) {
+            Ok(rule) => rule,
+            Err(_) => continue,
+        };
+        if let Some((deny_loc, _)) = deny_rules.iter().find(|(_, deny)| rule_covers(deny, &allow)) {
+            let pointer = rule_pointer(located.list, located.index);
+            findings.push(PermissionFinding {
+                id: finding_id(located.file, &pointer, "shadowed_allow"),
+                kind: "shadowed_allow".to_string(),
+                severity: Severity::Warning,
+                title: format!("Allow rule \"{}\" is shadowed", located.rule),
+                description: format!("Deny rule \"{}\" in {} settings takes precedence, so this allow has no effect", deny_loc.rule, deny_loc.layer),
+                file: located.file.to_string(),
+                layer: located.layer.to_string(),
+                pointer: pointer.clone(),
+                rule: Some(located.rule.to_string()),
+                fix: Some(remove_rule_fix(located.file, &pointer, located.rule, format!("Remove \"{}\" from allow", located.rule))),
+            });
+        }
+    }
+
+    // Duplicates within a file or across layers; the first occurrence is kept
+    let mut first_seen: HashMap<(&str, &str), &LocatedRule> = HashMap::new();
+    for located in &rules {
+        let key = (located.list, located.rule.trim());
+        match first_seen.get(&key) {
+            None => {
+                first_seen.insert(key, located);
+            }
+            Some(original) => {
+                let pointer = rule_pointer(located.list, located.index);
+                let where_defined = if original.file == located.file {
+                    "earlier in the same file".to_string()
+                } else {
+                    format!("in {} settings ({})", original.layer, original.file)
+                };
+                findings.push(PermissionFinding {
+                    id: finding_id(located.file, &pointer, "duplicate_rule"),
+                    kind: "duplicate_rule".to_string(),
+                    severity: Severity::Info,
+                    title: format!("Duplicate {} rule \"{}\"", located.list, located.rule),
+                    description: format!("The same rule is already defined {}", where_defined),
+                    file: located.file.to_string(),
+                    layer: located.layer.to_string(),
+                    pointer: pointer.clone(),
+                    rule: Some(located.rule.to_string()),
+                    fix: Some(remove_rule_fix(located.file, &pointer, located.rule, format!("Remove duplicate \"{}\"", located.rule))),
+                });
+            }
+        }
+    }
+
+    findings
+}
+
+// ============================================================================
+// Public Tauri Commands
+// ============================================================================
+
+/// Audit global settings plus the project and local layers of an optional project
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn audit_permissions(project_path: Option<String>) -> Result<Vec<PermissionFinding>, String> {
+    let home = get_home_dir().ok_or("Could not find home directory")?;
+    let mut layers = discover_settings_internal(&home.join(".claude"), "global", None, "claude")?;
+
+    if let Some(project) = project_path.as_deref() {
+        let claude_dir = PathBuf::from(project).join(".claude");
+        layers.extend(discover_settings_internal(&claude_dir, "project", Some(project), "claude")?);
+    }
+
+    Ok(audit_permissions_internal(&layers))
+}
+
+/// Apply the fix attached to a permission finding
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn apply_permission_fix(fix: PermissionFix) -> Result<(), String> {
+    json_edit::edit_file(&PathBuf::from(&fix.file), |text| {
+        fix.ops.iter().try_fold(text.to_string(), |current, op| apply_patch_op(&current, op))
+    })
+}
@@ -0,0 +1,511 @@
+// ============================================================================
+// Agent Studio - Plugin Authoring
+// Scaffold a Claude Code plugin, move existing entities into it, validate it
+// and generate its marketplace.json entry
+// ============================================================================
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::fixes::{self, FixOp};
+use super::frontmatter::parse_frontmatter;
+use super::hooks::HOOK_EVENTS;
+use super::json_edit;
+use super::permissions::JsonPatchOp;
+use super::{
+    discover_agents_internal, discover_commands_internal, discover_skills_internal, ensure_not_plugin_owned,
+    get_home_dir, parse_json_file, HookDefinition, HookEntity, Severity,
+};
+
+/// Keys Claude Code understands in .claude-plugin/plugin.json
+const MANIFEST_KEYS: &[&str] = &[
+    "name", "version", "description", "author", "homepage", "repository", "license", "keywords",
+    "commands", "agents", "skills", "hooks", "mcpServers", "lspServers", "outputStyles",
+];
+
+/// Manifest keys that point at component files or directories
+const PATH_KEYS: &[&str] = &["commands", "agents", "skills", "hooks", "mcpServers", "lspServers", "outputStyles"];
+
+/// Component directories that belong at the plugin root, not inside .claude-plugin/
+const COMPONENT_DIRS: &[&str] = &["commands", "agents", "skills", "hooks"];
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PluginValidationIssue {
+    pub severity: Severity,
+    pub path: String,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PluginValidationReport {
+    pub plugin_dir: String,
+    pub name: Option<String>,
+    pub valid: bool,  // No error-severity issues
+    pub issues: Vec<PluginValidationIssue>,
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+fn is_kebab_case(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('-')
+        && !name.ends_with('-')
+        && !name.contains("--")
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+fn is_semver(version: &str) -> bool {
+    let core = version.split(['-', '+']).next().unwrap_or("");
+    let parts: Vec<&str> = core.split('.').collect();
+    parts.len() == 3 && parts.iter().all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Where an agent, command or skill file belongs inside the plugin
+fn plugin_destination(plugin_dir: &Path, entity_path: &Path) -> Result<(PathBuf, PathBuf), String> {
+    let file_name = entity_path.file_name().ok_or("Invalid entity path")?;
+    if file_name == "SKILL.md" {
+        // Skills move as their whole directory
+        let skill_dir = entity_path.parent().ok_or("Invalid skill path")?;
+        let dir_name = skill_dir.file_name().ok_or("Invalid skill path")?;
+        return Ok((skill_dir.to_path_buf(), plugin_dir.join("skills").join(dir_name)));
+    }
+    let parent = entity_path.parent()
+        .and_then(|p| p.file_name())
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let component = match parent.as_str() {
+        "agents" | "agent" => "agents",
+        "commands" | "command" => "commands",
+        _ => return Err(format!("{} is not an agent, command or skill", entity_path.display())),
+    };
+    Ok((entity_path.to_path_buf(), plugin_dir.join(component).join(file_name)))
+}
+
+/// Matcher groups taken from one event of a settings file
+#[derive(Default)]
+struct TakenGroups {
+    /// How many matchers the event has
+    count: usize,
+    groups: Vec<(usize, serde_json::Value)>,
+}
+
+/// Plan moving settings hook matcher groups into the plugin: returns the
+/// plugin's `hooks` object and a patch per settings file removing the groups
+fn plan_hook_moves(hooks: &[HookEntity]) -> Result<(serde_json::Value, Vec<FixOp>), String> {
+    let mut plugin_hooks = serde_json::Map::new();
+    // settings file -> event -> groups taken
+    let mut taken: BTreeMap<String, BTreeMap<String, TakenGroups>> = BTreeMap::new();
+    let mut texts: HashMap<String, String> = HashMap::new();
+
+    for hook in hooks {
+        if hook.read_only {
+            return Err(format!("Hook {} is read-only and can't be moved", hook.event));
+        }
+        let settings_path = PathBuf::from(&hook.source_path);
+        ensure_not_plugin_owned(&settings_path)?;
+        if !texts.contains_key(&hook.source_path) {
+            let text = fs::read_to_string(&settings_path)
+                .map_err(|e| format!("Failed to read {}: {}", settings_path.display(), e))?;
+            texts.insert(hook.source_path.clone(), text);
+        }
+        let event_pointer = json_edit::build_pointer(&["hooks", &hook.event]);
+        let expected = serde_json::to_value(&hook.hooks).map_err(|e| e.to_string())?;
+        let groups = json_edit::get(&texts[&hook.source_path], &event_pointer)?
+            .and_then(|v| v.as_array().cloned())
+            .unwrap_or_default();
+
+        // Locate the matcher group by content since entities don't carry their index
+        let event_taken = taken.entry(hook.source_path.clone()).or_default()
+            .entry(hook.event.clone()).or_default();
+        event_taken.count = groups.len();
+        let (index, group) = groups.into_iter().enumerate()
+            .filter(|(i, _)| !event_taken.groups.iter().any(|(t, _)| t == i))
+            .find(|(_, group)| {
+                let matcher = group.get("matcher").and_then(|m| m.as_str());
+                let defs: Option<Vec<HookDefinition>> = group.get("hooks").and_then(|h| serde_json::from_value(h.clone()).ok());
+                matcher == hook.matcher.as_deref() && defs.and_then(|h| serde_json::to_value(h).ok()) == Some(expected.clone())
+            })
+            .ok_or_else(|| format!("Hook {} no longer exists in {}", hook.event, settings_path.display()))?;
+
+        if let serde_json::Value::Array(moved) = plugin_hooks.entry(hook.event.clone()).or_insert_with(|| serde_json::json!([])) {
+            moved.push(group.clone());
+        }
+        event_taken.groups.push((index, group));
+    }
+
+    let patches = taken.into_iter().map(|(file, events)| {
+        let mut ops = Vec::new();
+        for (event, TakenGroups { count, mut groups }) in events {
+            let pointer = |index: usize| json_edit::build_pointer(&["hooks", &event, &index.to_string()]);
+            // Highest index first so removing one doesn't shift the others
+            groups.sort_by_key(|(index, _)| std::cmp::Reverse(*index));
+            for (index, group) in &groups {
+                ops.push(JsonPatchOp::Test { path: pointer(*index), value: group.clone() });
+            }
+            for (index, _) in &groups {
+                ops.push(JsonPatchOp::Remove { path: pointer(*index) });
+            }
+            // As remove_hook_matcher does, drop an event left with no matchers
+            if groups.len() == count {
+                ops.push(JsonPatchOp::Remove { path: json_edit::build_pointer(&["hooks", &event]) });
+            }
+        }
+        FixOp::JsonPatch { file, ops }
+    }).collect();
+
+    Ok((serde_json::Value::Object(plugin_hooks), patches))
+}
+
+/// Component paths from the manifest (string or array of strings)
+fn manifest_paths(value: &serde_json::Value) -> Option<Vec<String>> {
+    match value {
+        serde_json::Value::String(s) => Some(vec![s.clone()]),
+        serde_json::Value::Array(items) => items.iter().map(|i| i.as_str().map(String::from)).collect(),
+        _ => None,
+    }
+}
+
+fn validate_manifest(manifest: &serde_json::Value, plugin_dir: &Path, manifest_path: &str, issues: &mut Vec<PluginValidationIssue>) {
+    let mut issue = |severity, message: String| issues.push(PluginValidationIssue { severity, path: manifest_path.to_string(), message });
+    let Some(obj) = manifest.as_object() else {
+        issue(Severity::Error, "plugin.json must contain a JSON object".to_string());
+        return;
+    };
+
+    match obj.get("name").and_then(|n| n.as_str()) {
+        None => issue(Severity::Error, "\"name\" is required".to_string()),
+        Some(name) if !is_kebab_case(name) => {
+            issue(Severity::Error, format!("\"name\" must be kebab-case (lowercase letters, digits and hyphens), got \"{}\"", name));
+        }
+        _ => {}
+    }
+    match obj.get("version") {
+        None => issue(Severity::Warning, "\"version\" is missing; installs are cached per version".to_string()),
+        Some(serde_json::Value::String(v)) if !is_semver(v) => issue(Severity::Warning, format!("\"version\" should be semver (e.g. 1.0.0), got \"{}\"", v)),
+        Some(serde_json::Value::String(_)) => {}
+        Some(_) => issue(Severity::Error, "\"version\" must be a string".to_string()),
+    }
+    match obj.get("description") {
+        None => issue(Severity::Info, "\"description\" is missing".to_string()),
+        Some(serde_json::Value::String(_)) => {}
+        Some(_) => issue(Severity::Error, "\"description\" must be a string".to_string()),
+    }
+    match obj.get("author") {
+        None | Some(serde_json::Value::String(_)) => {}
+        Some(serde_json::Value::Object(author)) if author.get("name").is_some_and(|n| n.is_string()) => {}
+        Some(_) => issue(Severity::Error, "\"author\" must be a string or an object with a \"name\"".to_string()),
+    }
+    for key in ["homepage", "repository", "license"] {
+        if obj.get(key).is_some_and(|v| !v.is_string()) {
+            issue(Severity::Error, format!("\"{}\" must be a string", key));
+        }
+    }
+    if let Some(keywords) = obj.get("keywords") {
+        if !keywords.as_array().is_some_and(|arr| arr.iter().all(|k| k.is_string())) {
+            issue(Severity::Error, "\"keywords\" must be an array of strings".to_string());
+        }
+    }
+    for key in obj.keys().filter(|k| !MANIFEST_KEYS.contains(&k.as_str())) {
+        issue(Severity::Warning, format!("Unknown manifest key \"{}\"", key));
+    }
+
+    // Custom component paths must be ./-relative and exist
+    for key in PATH_KEYS {
+        let Some(value) = obj.get(*key) else { continue };
+        if value.is_object() && matches!(*key, "hooks" | "mcpServers" | "lspServers") {
+            continue;  // Inline configuration
+        }
+        let Some(paths) = manifest_paths(value) else {
+            issue(Severity::Error, format!("\"{}\" must be a path or an array of paths", key));
+            continue;
+        };
+        for path in paths {
+            if !path.starts_with("./") {
+                issue(Severity::Error, format!("\"{}\" path \"{}\" must be relative and start with ./", key, path));
+            } else if !plugin_dir.join(&path).exists() {
+                issue(Severity::Error, format!("\"{}\" path \"{}\" does not exist", key, path));
+            }
+        }
+    }
+}
+
+/// Names of markdown components in a directory (file stem for commands, frontmatter name for agents)
+fn component_names(dir: &Path, use_frontmatter_name: bool) -> Vec<(String, PathBuf)> {
+    let mut names = Vec::new();
+    let Ok(entries) = fs::read_dir(dir) else { return names };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().is_none_or(|e| e != "md") {
+            continue;
+        }
+        let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+        let name = if use_frontmatter_name {
+            fs::read_to_string(&path).ok()
+                .and_then(|content| parse_frontmatter(&content).0)
+                .and_then(|fm| fm.get("name").and_then(|n| n.as_str()).map(String::from))
+                .unwrap_or(stem)
+        } else {
+            stem
+        };
+        names.push((name, path));
+    }
+    names
+}
+
+fn validate_plugin_internal(plugin_dir: &Path) -> PluginValidationReport {
+    let mut issues = Vec::new();
+    let display = |p: &Path| p.to_string_lossy().to_string();
+    let manifest_path = plugin_dir.join(".claude-plugin").join("plugin.json");
+    let manifest = parse_json_file(&manifest_path);
+
+    match &manifest {
+        Some(m) => validate_manifest(m, plugin_dir, &display(&manifest_path), &mut issues),
+        None => issues.push(PluginValidationIssue {
+            severity: Severity::Error,
+            path: display(&manifest_path),
+            message: if manifest_path.exists() { "plugin.json is not valid JSON" } else { "Missing .claude-plugin/plugin.json" }.to_string(),
+        }),
+    }
+
+    // Only plugin.json (and marketplace.json) belong inside .claude-plugin/
+    for dir in COMPONENT_DIRS {
+        let misplaced = plugin_dir.join(".claude-plugin").join(dir);
+        if misplaced.exists() {
+            issues.push(PluginValidationIssue {
+                severity: Severity::Error,
+                path: display(&misplaced),
+                message: format!("{}/ must be at the plugin root, not inside .claude-plugin/", dir),
+            });
+        }
+    }
+
+    let component_dirs = |key: &str| -> Vec<PathBuf> {
+        let mut dirs = vec![plugin_dir.join(key)];
+        if let Some(paths) = manifest.as_ref().and_then(|m| m.get(key)).and_then(manifest_paths) {
+            dirs.extend(paths.iter().map(|p| plugin_dir.join(p)));
+        }
+        dirs.into_iter().filter(|d| d.is_dir()).collect()
+    };
+
+    // Skills need a SKILL.md in each directory
+    for dir in component_dirs("skills") {
+        for entry in fs::read_dir(&dir).into_iter().flatten().flatten() {
+            if entry.path().is_dir() && !entry.path().join("SKILL.md").exists() {
+                issues.push(PluginValidationIssue {
+                    severity: Severity::Error,
+                    path: display(&entry.path()),
+                    message: "Skill directory has no SKILL.md".to_string(),
+                });
+            }
+        }
+    }
+
+    // hooks/hooks.json must parse and only use known events
+    let hooks_path = plugin_dir.join("hooks").join("hooks.json");
+    if hooks_path.exists() {
+        match parse_json_file(&hooks_path) {
+            None => issues.push(PluginValidationIssue {
+                severity: Severity::Error,
+                path: display(&hooks_path),
+                message: "hooks.json is not valid JSON".to_string(),
+            }),
+            Some(config) => match config.get("hooks").and_then(|h| h.as_object()) {
+                None => issues.push(PluginValidationIssue {
+                    severity: Severity::Error,
+                    path: display(&hooks_path),
+                    message: "hooks.json must contain a \"hooks\" object".to_string(),
+                }),
+                Some(events) => {
+                    for event in events.keys().filter(|e| !HOOK_EVENTS.contains(&e.as_str())) {
+                        issues.push(PluginValidationIssue {
+                            severity: Severity::Error,
+                            path: display(&hooks_path),
+                            message: format!("Unknown hook event \"{}\"", event),
+                        });
+                    }
+                }
+            },
+        }
+    }
+
+    // Name collisions inside the plugin and with the user's own entities
+    let home_claude = get_home_dir().map(|h| h.join(".claude"));
+    let mut check_collisions = |kind: &str, names: Vec<(String, PathBuf)>, existing: Vec<String>| {
+        let mut seen: HashMap<String, PathBuf> = HashMap::new();
+        for (name, path) in names {
+            if let Some(first) = seen.get(&name) {
+                issues.push(PluginValidationIssue {
+                    severity: Severity::Error,
+                    path: display(&path),
+                    message: format!("Duplicate {} \"{}\" (also defined in {})", kind, name, first.display()),
+                });
+            } else if existing.contains(&name) {
+                issues.push(PluginValidationIssue {
+                    severity: Severity::Warning,
+                    path: display(&path),
+                    message: format!("{} \"{}\" has the same name as one in ~/.claude; users must use the plugin-qualified name", kind, name),
+                });
+            }
+            seen.entry(name).or_insert(path);
+        }
+    };
+
+    let commands: Vec<_> = component_dirs("commands").iter().flat_map(|d| component_names(d, false)).collect();
+    let user_commands = home_claude.as_ref()
+        .and_then(|h| discover_commands_internal(&h.join("commands"), "global", None, "claude").ok())
+        .map(|cs| cs.into_iter().map(|c| c.base.name).collect())
+        .unwrap_or_default();
+    check_collisions("command", commands, user_commands);
+
+    let agents: Vec<_> = component_dirs("agents").iter().flat_map(|d| component_names(d, true)).collect();
+    let user_agents = home_claude.as_ref()
+        .and_then(|h| discover_agents_internal(&h.join("agents"), "global", None, "claude").ok())
+        .map(|a| a.into_iter().map(|a| a.base.name).collect())
+        .unwrap_or_default();
+    check_collisions("agent", agents, user_agents);
+
+    let skills: Vec<_> = component_dirs("skills").iter()
+        .flat_map(|d| fs::read_dir(d).into_iter().flatten().flatten())
+        .filter(|e| e.path().join("SKILL.md").exists())
+        .map(|e| (e.file_name().to_string_lossy().to_string(), e.path()))
+        .collect();
+    let user_skills = home_claude.as_ref()
+        .and_then(|h| discover_skills_internal(&h.join("skills"), "global", None, "claude").ok())
+        .map(|s| s.into_iter().map(|s| s.base.name).collect())
+        .unwrap_or_default();
+    check_collisions("skill", skills, user_skills);
+
+    PluginValidationReport {
+        plugin_dir: display(plugin_dir),
+        name: manifest.as_ref().and_then(|m| m.get("name")).and_then(|n| n.as_str()).map(String::from),
+        valid: !issues.iter().any(|i| i.severity == Severity::Error),
+        issues,
+    }
+}
+
+// ============================================================================
+// Public Tauri Commands
+// ============================================================================
+
+/// Scaffold a plugin at `<parent_dir>/<name>` and optionally move existing
+/// agents, commands, skills (by path) and settings hooks into it.
+/// Returns the plugin directory.
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn create_plugin(
+    parent_dir: String,
+    name: String,
+    description: String,
+    author: Option<String>,
+    entity_paths: Option<Vec<String>>,
+    hooks: Option<Vec<HookEntity>>,
+) -> Result<String, String> {
+    if !is_kebab_case(&name) {
+        return Err(format!("Plugin name must be kebab-case, got \"{}\"", name));
+    }
+    let plugin_dir = PathBuf::from(&parent_dir).join(&name);
+    if plugin_dir.exists() && fs::read_dir(&plugin_dir).map(|mut d| d.next().is_some()).unwrap_or(true) {
+        return Err(format!("{} already exists and is not empty", plugin_dir.display()));
+    }
+
+    // Check every move up front so a bad path doesn't leave a half-built plugin
+    let moves = entity_paths.unwrap_or_default().iter()
+        .map(|p| plugin_destination(&plugin_dir, Path::new(p)))
+        .collect::<Result<Vec<_>, _>>()?;
+    for (src, _) in &moves {
+        if !src.exists() {
+            return Err(format!("{} does not exist", src.display()));
+        }
+    }
+
+    let (plugin_hooks, hook_patches) = plan_hook_moves(&hooks.unwrap_or_default())?;
+
+    let mut manifest = serde_json::json!({
+        "name": name,
+        "version": "0.1.0",
+        "description": description,
+    });
+    if let Some(author) = author.filter(|a| !a.trim().is_empty()) {
+        manifest["author"] = serde_json::json!({ "name": author });
+    }
+
+    // One plan, so a failed move or settings edit puts everything back
+    let new_file = |path: PathBuf, value: serde_json::Value| FixOp::JsonPatch {
+        file: path.to_string_lossy().to_string(),
+        ops: vec![JsonPatchOp::Add { path: String::new(), value }],
+    };
+    let mut ops = vec![
+        new_file(plugin_dir.join(".claude-plugin").join("plugin.json"), manifest),
+        new_file(plugin_dir.join("hooks").join("hooks.json"), serde_json::json!({ "hooks": plugin_hooks })),
+    ];
+    ops.extend(moves.into_iter().map(|(src, dst)| FixOp::Rename {
+        from: src.to_string_lossy().to_string(),
+        to: dst.to_string_lossy().to_string(),
+    }));
+    ops.extend(hook_patches);
+
+    // The plan doesn't create empty component directories, so they're made
+    // up front and removed again if it fails (remove_dir leaves non-empty ones)
+    let mut created = Vec::new();
+    let result = std::iter::once(plugin_dir.clone())
+        .chain([".claude-plugin", "commands", "agents", "skills", "hooks"].map(|d| plugin_dir.join(d)))
+        .filter(|dir| !dir.exists())
+        .try_for_each(|dir| {
+            fs::create_dir_all(&dir).map_err(|e| format!("Failed to create directory: {}", e))?;
+            created.push(dir);
+            Ok(())
+        })
+        .and_then(|_| fixes::apply_ops(&ops));
+    if let Err(e) = result {
+        for dir in created.iter().rev() {
+            let _ = fs::remove_dir(dir);
+        }
+        return Err(e);
+    }
+
+    Ok(plugin_dir.to_string_lossy().to_string())
+}
+
+/// Check a plugin's manifest, component layout and name collisions
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn validate_plugin(plugin_dir: String) -> Result<PluginValidationReport, String> {
+    let dir = PathBuf::from(&plugin_dir);
+    if !dir.is_dir() {
+        return Err(format!("{} is not a directory", plugin_dir));
+    }
+    Ok(validate_plugin_internal(&dir))
+}
+
+/// Build the `plugins[]` entry for marketplace.json. With a marketplace
+/// directory the source is relative to it, otherwise the absolute plugin path.
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn generate_marketplace_entry(plugin_dir: String, marketplace_dir: Option<String>) -> Result<serde_json::Value, String> {
+    let dir = PathBuf::from(&plugin_dir);
+    let manifest = parse_json_file(&dir.join(".claude-plugin").join("plugin.json"))
+        .ok_or("Missing or invalid .claude-plugin/plugin.json")?;
+    let name = manifest.get("name").and_then(|n| n.as_str()).ok_or("plugin.json has no name")?;
+
+    let source = match marketplace_dir {
+        Some(root) => {
+            let relative = dir.strip_prefix(&root)
+                .map_err(|_| format!("{} is not inside the marketplace directory {}", plugin_dir, root))?;
+            format!("./{}", relative.to_string_lossy().replace('\\', "/"))
+        }
+        None => dir.to_string_lossy().to_string(),
+    };
+
+    let mut entry = serde_json::json!({ "name": name, "source": source });
+    for key in ["description", "version", "author", "homepage", "repository", "license", "keywords"] {
+        if let Some(value) = manifest.get(key) {
+            entry[key] = value.clone();
+        }
+    }
+    Ok(entry)
+}
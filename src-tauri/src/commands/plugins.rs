@@ -0,0 +1,500 @@
+// ============================================================================
+// Agent Studio - Plugin Management
+// Marketplaces, installation and enabling plugins across settings layers
+// ============================================================================
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::time::SystemTime;
+
+use super::json_edit;
+use super::settings_edit::settings_layer_path;
+use super::{copy_dir_recursive, extract_enabled_plugins_from_settings, get_home_dir, parse_json_file};
+
+/// Settings layers that can carry `enabledPlugins`, lowest precedence first
+const PLUGIN_LAYERS: &[&str] = &["user", "project", "local"];
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// An explicit `enabledPlugins` entry in one settings layer
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PluginEnablement {
+    pub layer: String,  // "user", "project", "local"
+    pub settings_path: String,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PluginEnabledState {
+    pub plugin: String,
+    /// Effective state: the highest-precedence layer that mentions the plugin wins
+    pub enabled: bool,
+    pub layers: Vec<PluginEnablement>,
+}
+
+// ============================================================================
+// Resolution
+// ============================================================================
+
+/// Resolve whether `name@marketplace` is enabled for a project (or user-wide
+/// when no project is given); plugins no layer mentions are disabled
+pub(crate) fn resolve_plugin_enabled(plugin: &str, project_path: Option<&str>) -> PluginEnabledState {
+    let mut layers = Vec::new();
+    for layer in PLUGIN_LAYERS {
+        if *layer != "user" && project_path.is_none() {
+            continue;
+        }
+        let Ok(path) = settings_layer_path(layer, project_path) else { continue };
+        if let Some(enabled) = extract_enabled_plugins_from_settings(&path).get(plugin) {
+            layers.push(PluginEnablement {
+                layer: layer.to_string(),
+                settings_path: path.to_string_lossy().to_string(),
+                enabled: *enabled,
+            });
+        }
+    }
+
+    PluginEnabledState {
+        plugin: plugin.to_string(),
+        enabled: layers.last().map(|l| l.enabled).unwrap_or(false),
+        layers,
+    }
+}
+
+fn validate_plugin_id(plugin: &str) -> Result<(), String> {
+    match plugin.split_once('@') {
+        Some((name, marketplace)) if !name.is_empty() && !marketplace.is_empty() && !marketplace.contains('@') => Ok(()),
+        _ => Err(format!("Plugin must be given as name@marketplace, got \"{}\"", plugin)),
+    }
+}
+
+/// Marketplace, plugin and version names each become one directory of the
+/// cache path, so none may contain a separator or be `.` / `..`
+fn validate_cache_segment(kind: &str, value: &str) -> Result<(), String> {
+    let mut components = Path::new(value).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(_)), None) if !value.contains(['/', '\\']) => Ok(()),
+        _ => Err(format!("Invalid plugin {} \"{}\": it must be a single path segment", kind, value)),
+    }
+}
+
+fn set_plugin_enabled(plugin: &str, layer: &str, project_path: Option<&str>, enabled: bool) -> Result<PluginEnabledState, String> {
+    validate_plugin_id(plugin)?;
+    if !PLUGIN_LAYERS.contains(&layer) && layer != "global" {
+        return Err(format!("Unknown settings layer: {}", layer));
+    }
+    let path = settings_layer_path(layer, project_path)?;
+    let pointer = json_edit::build_pointer(&["enabledPlugins", plugin]);
+    json_edit::edit_file(&path, |text| json_edit::set(text, &pointer, &serde_json::Value::Bool(enabled)))?;
+    Ok(resolve_plugin_enabled(plugin, project_path))
+}
+
+// ============================================================================
+// Public Tauri Commands
+// ============================================================================
+
+/// Effective enabled state of a plugin plus the layers that set it
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_plugin_enabled_state(plugin: String, project_path: Option<String>) -> Result<PluginEnabledState, String> {
+    validate_plugin_id(&plugin)?;
+    Ok(resolve_plugin_enabled(&plugin, project_path.as_deref()))
+}
+
+/// Set `enabledPlugins["name@marketplace"] = true` in a settings layer
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn enable_plugin(plugin: String, layer: String, project_path: Option<String>) -> Result<PluginEnabledState, String> {
+    set_plugin_enabled(&plugin, &layer, project_path.as_deref(), true)
+}
+
+/// Set `enabledPlugins["name@marketplace"] = false` in a settings layer,
+/// which also overrides an enable from a lower-precedence layer
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn disable_plugin(plugin: String, layer: String, project_path: Option<String>) -> Result<PluginEnabledState, String> {
+    set_plugin_enabled(&plugin, &layer, project_path.as_deref(), false)
+}
+
+// ============================================================================
+// Marketplaces
+// ============================================================================
+
+/// A plugin listed in a marketplace's .claude-plugin/marketplace.json
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MarketplacePlugin {
+    pub name: String,
+    pub full_name: String,  // "name@marketplace"
+    pub description: Option<String>,
+    pub version: Option<String>,
+    pub category: Option<String>,
+    pub author: Option<String>,
+    pub source: serde_json::Value,
+    pub source_kind: String,  // "local", "github", "git", "url"
+    /// Local sources can be installed straight from the marketplace checkout
+    pub installable: bool,
+    pub installed_scopes: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MarketplaceInfo {
+    pub name: String,
+    pub source: serde_json::Value,
+    pub install_location: String,
+    pub last_updated: Option<String>,
+    pub description: Option<String>,
+    pub owner: Option<String>,
+    pub plugins: Vec<MarketplacePlugin>,
+    /// Set when marketplace.json is missing or unreadable
+    pub error: Option<String>,
+}
+
+/// One entry of installed_plugins.json `plugins["name@marketplace"]`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginInstallation {
+    pub scope: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_path: Option<String>,
+    pub install_path: String,
+    pub version: String,
+    pub installed_at: String,
+    pub last_updated: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_commit_sha: Option<String>,
+    #[serde(default)]
+    pub is_local: bool,
+}
+
+fn plugins_dir() -> Result<PathBuf, String> {
+    let home = get_home_dir().ok_or("Could not find home directory")?;
+    Ok(home.join(".claude").join("plugins"))
+}
+
+/// Current UTC time as an ISO 8601 timestamp (e.g. 2025-01-31T12:00:00.000Z)
+fn iso8601_now() -> String {
+    let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default();
+    let secs = now.as_secs() as i64;
+    let (days, rem) = (secs.div_euclid(86_400), secs.rem_euclid(86_400));
+    // Civil date from days since the epoch (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year, month, day, rem / 3600, rem % 3600 / 60, rem % 60, now.subsec_millis()
+    )
+}
+
+fn person_name(value: Option<&serde_json::Value>) -> Option<String> {
+    match value? {
+        serde_json::Value::String(s) => Some(s.clone()),
+        other => other.get("name").and_then(|n| n.as_str()).map(String::from),
+    }
+}
+
+fn source_kind(source: &serde_json::Value) -> String {
+    match source {
+        serde_json::Value::String(_) => "local".to_string(),
+        other => other.get("source").and_then(|s| s.as_str()).unwrap_or("unknown").to_string(),
+    }
+}
+
+/// Read known_marketplaces.json as (name, entry) pairs
+fn known_marketplaces() -> Result<Vec<(String, serde_json::Value)>, String> {
+    let path = plugins_dir()?.join("known_marketplaces.json");
+    Ok(parse_json_file(&path)
+        .and_then(|v| v.as_object().cloned())
+        .map(|obj| obj.into_iter().collect())
+        .unwrap_or_default())
+}
+
+fn read_installed_plugins() -> Result<serde_json::Map<String, serde_json::Value>, String> {
+    let path = plugins_dir()?.join("installed_plugins.json");
+    Ok(parse_json_file(&path)
+        .and_then(|v| v.get("plugins").and_then(|p| p.as_object()).cloned())
+        .unwrap_or_default())
+}
+
+/// Load a marketplace checkout's manifest; returns (install location, marketplace.json)
+fn load_marketplace(name: &str) -> Result<(PathBuf, serde_json::Value), String> {
+    let (_, entry) = known_marketplaces()?
+        .into_iter()
+        .find(|(n, _)| n == name)
+        .ok_or_else(|| format!("Unknown marketplace: {}", name))?;
+    let location = entry.get("installLocation")
+        .and_then(|l| l.as_str())
+        .map(PathBuf::from)
+        .ok_or_else(|| format!("Marketplace {} has no install location", name))?;
+    let manifest_path = location.join(".claude-plugin").join("marketplace.json");
+    let manifest = parse_json_file(&manifest_path)
+        .ok_or_else(|| format!("Could not read {}", manifest_path.display()))?;
+    Ok((location, manifest))
+}
+
+/// Resolve a local plugin source against the marketplace root and `metadata.pluginRoot`
+fn resolve_local_source(root: &Path, manifest: &serde_json::Value, source: &str) -> PathBuf {
+    let explicit = source.starts_with("./") || source.starts_with("../") || source.starts_with('/') || source == ".";
+    let plugin_root = manifest.get("metadata")
+        .and_then(|m| m.get("pluginRoot"))
+        .and_then(|r| r.as_str())
+        .filter(|_| !explicit);
+    match plugin_root {
+        Some(plugin_root) => root.join(plugin_root).join(source),
+        None => root.join(source),
+    }
+}
+
+fn git_commit_sha(dir: &Path) -> Option<String> {
+    let output = std::process::Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string()).filter(|s| !s.is_empty())
+}
+
+fn installation_matches(installation: &serde_json::Value, scope: &str, project_path: Option<&str>) -> bool {
+    installation.get("scope").and_then(|s| s.as_str()) == Some(scope)
+        && installation.get("projectPath").and_then(|p| p.as_str()) == project_path
+}
+
+/// Apply a change to installed_plugins.json's `plugins` map, keeping other keys
+fn edit_installed_plugins(
+    update: impl FnOnce(&mut serde_json::Map<String, serde_json::Value>) -> Result<(), String>,
+) -> Result<(), String> {
+    let path = plugins_dir()?.join("installed_plugins.json");
+    json_edit::edit_file(&path, |text| {
+        let mut root: serde_json::Value = serde_json::from_str(text).map_err(|e| format!("Invalid installed_plugins.json: {}", e))?;
+        let obj = root.as_object_mut().ok_or("installed_plugins.json must contain an object")?;
+        obj.entry("version").or_insert(serde_json::json!(2));
+        let plugins = obj.entry("plugins").or_insert_with(|| serde_json::json!({}));
+        update(plugins.as_object_mut().ok_or("installed_plugins.json `plugins` must be an object")?)?;
+        serde_json::to_string_pretty(&root).map(|s| s + "\n").map_err(|e| e.to_string())
+    })
+}
+
+/// List every known marketplace and the plugins it offers
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn list_marketplaces() -> Result<Vec<MarketplaceInfo>, String> {
+    let installed = read_installed_plugins()?;
+    let mut marketplaces = Vec::new();
+
+    for (name, entry) in known_marketplaces()? {
+        let install_location = entry.get("installLocation").and_then(|l| l.as_str()).unwrap_or("").to_string();
+        let mut info = MarketplaceInfo {
+            name: name.clone(),
+            source: entry.get("source").cloned().unwrap_or(serde_json::Value::Null),
+            install_location,
+            last_updated: entry.get("lastUpdated").and_then(|l| l.as_str()).map(String::from),
+            description: None,
+            owner: None,
+            plugins: Vec::new(),
+            error: None,
+        };
+
+        match load_marketplace(&name) {
+            Ok((_, manifest)) => {
+                info.description = manifest.get("metadata")
+                    .and_then(|m| m.get("description"))
+                    .or_else(|| manifest.get("description"))
+                    .and_then(|d| d.as_str())
+                    .map(String::from);
+                info.owner = person_name(manifest.get("owner"));
+                for plugin in manifest.get("plugins").and_then(|p| p.as_array()).into_iter().flatten() {
+                    let Some(plugin_name) = plugin.get("name").and_then(|n| n.as_str()) else { continue };
+                    let full_name = format!("{}@{}", plugin_name, name);
+                    let source = plugin.get("source").cloned().unwrap_or(serde_json::Value::Null);
+                    let kind = source_kind(&source);
+                    let installed_scopes = installed.get(&full_name)
+                        .and_then(|i| i.as_array())
+                        .map(|arr| arr.iter().filter_map(|i| i.get("scope").and_then(|s| s.as_str()).map(String::from)).collect())
+                        .unwrap_or_default();
+                    info.plugins.push(MarketplacePlugin {
+                        name: plugin_name.to_string(),
+                        full_name,
+                        description: plugin.get("description").and_then(|d| d.as_str()).map(String::from),
+                        version: plugin.get("version").and_then(|v| v.as_str()).map(String::from),
+                        category: plugin.get("category").and_then(|c| c.as_str()).map(String::from),
+                        author: person_name(plugin.get("author")),
+                        installable: kind == "local",
+                        source,
+                        source_kind: kind,
+                        installed_scopes,
+                    });
+                }
+            }
+            Err(e) => info.error = Some(e),
+        }
+        marketplaces.push(info);
+    }
+
+    marketplaces.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(marketplaces)
+}
+
+/// Copy a plugin into the cache, replacing any earlier copy of the same version:
+/// a local marketplace can change a plugin without bumping its version. The new
+/// copy is staged next to the old one so a failed copy leaves the old one intact.
+fn replace_cached_copy(source: &Path, install_path: &Path) -> Result<(), String> {
+    let file_name = install_path.file_name().ok_or("Invalid install path")?.to_string_lossy();
+    let staging = install_path.with_file_name(format!(".{}.agent-studio.tmp", file_name));
+    let previous = install_path.with_file_name(format!(".{}.agent-studio.old", file_name));
+    for leftover in [&staging, &previous] {
+        if leftover.exists() {
+            fs::remove_dir_all(leftover).map_err(|e| format!("Failed to remove {}: {}", leftover.display(), e))?;
+        }
+    }
+    if let Err(e) = copy_dir_recursive(source, &staging) {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+
+    // Swap by renaming, so there's never a half-deleted copy at install_path
+    if install_path.exists() {
+        fs::rename(install_path, &previous).map_err(|e| format!("Failed to replace {}: {}", install_path.display(), e))?;
+    }
+    if let Err(e) = fs::rename(&staging, install_path) {
+        let _ = fs::rename(&previous, install_path);
+        let _ = fs::remove_dir_all(&staging);
+        return Err(format!("Failed to move {} into place: {}", install_path.display(), e));
+    }
+    let _ = fs::remove_dir_all(&previous);
+    Ok(())
+}
+
+/// Install a plugin from a local or git-checkout marketplace: copy it into the
+/// plugin cache, record it in installed_plugins.json and enable it for the scope
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn install_marketplace_plugin(
+    marketplace: String,
+    plugin: String,
+    scope: String,
+    project_path: Option<String>,
+) -> Result<PluginInstallation, String> {
+    if !PLUGIN_LAYERS.contains(&scope.as_str()) {
+        return Err(format!("Unknown install scope: {}", scope));
+    }
+    let project_path = if scope == "user" { None } else {
+        Some(project_path.ok_or("Project path required for project-scoped plugins")?)
+    };
+    let (root, manifest) = load_marketplace(&marketplace)?;
+    let entry = manifest.get("plugins")
+        .and_then(|p| p.as_array())
+        .and_then(|arr| arr.iter().find(|p| p.get("name").and_then(|n| n.as_str()) == Some(plugin.as_str())))
+        .ok_or_else(|| format!("Plugin {} not found in marketplace {}", plugin, marketplace))?;
+    let source = match entry.get("source") {
+        Some(serde_json::Value::String(source)) => resolve_local_source(&root, &manifest, source),
+        Some(other) => {
+            return Err(format!("Plugin source type \"{}\" must be installed with the Claude CLI", source_kind(other)));
+        }
+        None => return Err(format!("Plugin {} has no source", plugin)),
+    };
+    if !source.is_dir() {
+        return Err(format!("Plugin source not found: {}", source.display()));
+    }
+
+    let plugin_manifest = parse_json_file(&source.join(".claude-plugin").join("plugin.json"));
+    let version = entry.get("version")
+        .or_else(|| plugin_manifest.as_ref().and_then(|m| m.get("version")))
+        .and_then(|v| v.as_str())
+        .unwrap_or("unknown")
+        .to_string();
+    validate_cache_segment("marketplace", &marketplace)?;
+    validate_cache_segment("name", &plugin)?;
+    validate_cache_segment("version", &version)?;
+    let install_path = plugins_dir()?.join("cache").join(&marketplace).join(&plugin).join(&version);
+    replace_cached_copy(&source, &install_path)?;
+
+    let full_name = format!("{}@{}", plugin, marketplace);
+    let now = iso8601_now();
+    let installation = PluginInstallation {
+        scope: scope.clone(),
+        project_path: project_path.clone(),
+        install_path: install_path.to_string_lossy().to_string(),
+        version,
+        installed_at: now.clone(),
+        last_updated: now,
+        git_commit_sha: git_commit_sha(&source),
+        is_local: true,
+    };
+
+    let value = serde_json::to_value(&installation).map_err(|e| e.to_string())?;
+    edit_installed_plugins(|plugins| {
+        let list = plugins.entry(full_name.clone()).or_insert_with(|| serde_json::json!([]));
+        let arr = list.as_array_mut().ok_or("Invalid installed_plugins.json entry")?;
+        match arr.iter_mut().find(|i| installation_matches(i, &scope, project_path.as_deref())) {
+            Some(existing) => {
+                // Reinstall keeps the original install date
+                let installed_at = existing.get("installedAt").cloned();
+                *existing = value;
+                if let (Some(date), Some(obj)) = (installed_at, existing.as_object_mut()) {
+                    obj.insert("installedAt".to_string(), date);
+                }
+            }
+            None => arr.push(value),
+        }
+        Ok(())
+    })?;
+
+    set_plugin_enabled(&full_name, &scope, project_path.as_deref(), true)?;
+    Ok(installation)
+}
+
+/// Remove a plugin installation for a scope, its enabledPlugins entry and,
+/// once nothing references it, its cached copy
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn uninstall_marketplace_plugin(plugin: String, scope: String, project_path: Option<String>) -> Result<(), String> {
+    validate_plugin_id(&plugin)?;
+    let project_path = if scope == "user" { None } else { project_path };
+    let mut removed_path: Option<String> = None;
+
+    edit_installed_plugins(|plugins| {
+        let list = plugins.get_mut(&plugin)
+            .and_then(|l| l.as_array_mut())
+            .ok_or_else(|| format!("{} is not installed", plugin))?;
+        let index = list.iter()
+            .position(|i| installation_matches(i, &scope, project_path.as_deref()))
+            .ok_or_else(|| format!("{} is not installed for scope {}", plugin, scope))?;
+        let removed = list.remove(index);
+        removed_path = removed.get("installPath").and_then(|p| p.as_str()).map(String::from);
+        if list.is_empty() {
+            plugins.remove(&plugin);
+        }
+        Ok(())
+    })?;
+
+    // Drop the enabledPlugins entry this install added
+    if let Ok(path) = settings_layer_path(&scope, project_path.as_deref()) {
+        let pointer = json_edit::build_pointer(&["enabledPlugins", &plugin]);
+        json_edit::edit_file(&path, |text| match json_edit::get(text, &pointer)? {
+            Some(_) => json_edit::remove(text, &pointer),
+            None => Ok(text.to_string()),
+        })?;
+    }
+
+    // Only delete the cache directory when no other installation uses it
+    if let Some(install_path) = removed_path {
+        let still_used = read_installed_plugins()?.values()
+            .filter_map(|l| l.as_array())
+            .flatten()
+            .any(|i| i.get("installPath").and_then(|p| p.as_str()) == Some(install_path.as_str()));
+        let cache_dir = plugins_dir()?.join("cache");
+        let path = PathBuf::from(&install_path);
+        if !still_used && path.starts_with(&cache_dir) && path.is_dir() {
+            fs::remove_dir_all(&path).map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+        }
+    }
+
+    Ok(())
+}
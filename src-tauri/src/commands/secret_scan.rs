@@ -0,0 +1,510 @@
+// ============================================================================
+// Agent Studio - Secret Scanner
+// Find literal API tokens in MCP server env / headers and settings `env`,
+// and move them out of git-tracked files into untracked local config
+// ============================================================================
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use super::fixes::{self, FixOp};
+use super::json_edit;
+use super::mcp_edit::entity_location;
+use super::permissions::JsonPatchOp;
+use super::{generate_id, McpServerEntity, SettingsEntity, Severity};
+
+/// Well-known token formats, checked before the entropy heuristic
+const TOKEN_PATTERNS: &[(&str, &str)] = &[
+    ("GitHub token", r"\bgh[pousr]_[A-Za-z0-9]{36,}\b"),
+    ("GitHub fine-grained token", r"\bgithub_pat_[A-Za-z0-9_]{22,}\b"),
+    ("GitLab token", r"\bglpat-[A-Za-z0-9_-]{20,}\b"),
+    ("Anthropic API key", r"\bsk-ant-[A-Za-z0-9_-]{20,}"),
+    ("OpenAI API key", r"\bsk-(?:proj-|svcacct-)?[A-Za-z0-9_-]{20,}"),
+    ("AWS access key", r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b"),
+    ("Google API key", r"\bAIza[0-9A-Za-z_-]{35}\b"),
+    ("Slack token", r"\bxox[abposr]-[A-Za-z0-9-]{10,}"),
+    ("Stripe key", r"\b(?:sk|rk)_(?:live|test)_[0-9A-Za-z]{16,}\b"),
+    ("npm token", r"\bnpm_[A-Za-z0-9]{36}\b"),
+    ("Hugging Face token", r"\bhf_[A-Za-z0-9]{30,}\b"),
+    ("JSON Web Token", r"\beyJ[A-Za-z0-9_-]{8,}\.eyJ[A-Za-z0-9_-]{8,}\.[A-Za-z0-9_-]{8,}"),
+    ("Private key", r"-----BEGIN [A-Z ]*PRIVATE KEY-----"),
+];
+
+/// `${VAR}` / `{env:VAR}` references, which are never literal secrets
+static PLACEHOLDER: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\$\{[^}]*\}|\{env:[^}]*\}").unwrap());
+
+static TOKEN_REGEXES: LazyLock<Vec<(&str, Regex)>> = LazyLock::new(|| {
+    TOKEN_PATTERNS.iter().map(|(token_type, pattern)| (*token_type, Regex::new(pattern).unwrap())).collect()
+});
+
+static VAR_NAME: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^[A-Za-z_][A-Za-z0-9_]*$").unwrap());
+
+/// Key names that suggest the value is a credential, which lowers the entropy bar
+const SECRET_KEY_HINTS: &[&str] = &["TOKEN", "SECRET", "PASSWORD", "PASSWD", "API_KEY", "APIKEY", "AUTH", "CREDENTIAL", "PRIVATE"];
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SecretFinding {
+    pub id: String,
+    pub kind: String,  // "known_token", "high_entropy"
+    pub severity: Severity,
+    pub title: String,
+    pub description: String,
+    pub file: String,
+    pub pointer: String,  // JSON pointer of the value in `file`
+    pub field: String,  // e.g. "env.GITHUB_TOKEN", "headers.Authorization"
+    pub source: String,  // "mcp" or "settings"
+    pub entity_id: String,
+    pub server_name: Option<String>,
+    pub tool: String,
+    pub scope: String,
+    pub project_path: Option<String>,
+    pub token_type: String,  // e.g. "GitHub token", "High-entropy string"
+    pub preview: String,  // Masked, e.g. "ghp_…9f"
+    pub git_status: String,  // "tracked" or "untracked"
+    pub suggested_var: String,
+    pub fixable: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SecretFixResult {
+    pub variable: String,
+    pub source_file: String,
+    pub secret_file: String,  // Where the literal now lives
+    pub reference: Option<String>,  // The value left in the source file; None when the key was moved
+    pub gitignore_updated: Option<String>,  // .gitignore that gained an entry
+    pub warnings: Vec<String>,
+}
+
+// ============================================================================
+// Detection
+// ============================================================================
+
+/// Shannon entropy in bits per character
+fn shannon_entropy(text: &str) -> f64 {
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    for c in text.chars() {
+        *counts.entry(c).or_default() += 1;
+    }
+    let len = text.chars().count() as f64;
+    counts.values().map(|&n| {
+        let p = n as f64 / len;
+        -p * p.log2()
+    }).sum()
+}
+
+/// Whether a single word looks like a random credential rather than a word,
+/// path, URL or version
+fn is_high_entropy(word: &str, hinted: bool) -> bool {
+    let min_len = if hinted { 16 } else { 20 };
+    if word.len() < min_len || word.contains("://") || word.starts_with('/') || word.starts_with('.') {
+        return false;
+    }
+    // Passwords under a credential-looking key may contain any punctuation
+    if !hinted && !word.chars().all(|c| c.is_ascii_alphanumeric() || "+/=_-.".contains(c)) {
+        return false;
+    }
+    if !word.chars().any(|c| c.is_ascii_digit()) || !word.chars().any(|c| c.is_ascii_alphabetic()) {
+        return false;
+    }
+    let is_hex = word.chars().all(|c| c.is_ascii_hexdigit());
+    let threshold = match (is_hex, hinted) {
+        (true, _) => 3.0,
+        (false, true) => 3.5,
+        (false, false) => 4.0,
+    };
+    shannon_entropy(word) >= threshold
+}
+
+/// The literal secret in `value`, if any, as (kind, token type, literal).
+/// Text inside `${VAR}` / `{env:VAR}` references is never a literal.
+fn detect_secret(key: &str, value: &str) -> Option<(&'static str, &'static str, String)> {
+    let literal_text = PLACEHOLDER.replace_all(value, " ");
+
+    for (token_type, pattern) in TOKEN_REGEXES.iter() {
+        if let Some(m) = pattern.find(&literal_text) {
+            let literal = if *token_type == "Private key" { value.trim().to_string() } else { m.as_str().to_string() };
+            return Some(("known_token", token_type, literal));
+        }
+    }
+
+    let upper = key.to_ascii_uppercase();
+    let hinted = SECRET_KEY_HINTS.iter().any(|h| upper.contains(h));
+    literal_text.split_whitespace()
+        .map(|w| w.trim_matches(|c| c == '"' || c == '\'' || c == ','))
+        .find(|w| is_high_entropy(w, hinted))
+        .map(|w| ("high_entropy", "High-entropy string", w.to_string()))
+}
+
+/// Enough of a secret to recognise it without revealing it: at most about a
+/// quarter of the characters, and only a short prefix for short values
+fn mask(literal: &str) -> String {
+    let chars: Vec<char> = literal.chars().collect();
+    let (head, tail) = match chars.len() {
+        0..=8 => (0, 0),
+        9..=15 => (2, 0),
+        16..=23 => (4, 0),
+        _ => (4, 2),
+    };
+    let head: String = chars[..head].iter().collect();
+    let tail: String = chars[chars.len() - tail..].iter().collect();
+    format!("{}…{}", head, tail)
+}
+
+/// Environment variable name derived from arbitrary text
+fn env_var_name(parts: &[&str]) -> String {
+    let name = parts.join("_")
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+        .collect::<String>();
+    let name = name.split('_').filter(|s| !s.is_empty()).collect::<Vec<_>>().join("_");
+    if name.starts_with(|c: char| c.is_ascii_digit()) { format!("_{}", name) } else { name }
+}
+
+fn is_valid_var_name(name: &str) -> bool {
+    VAR_NAME.is_match(name)
+}
+
+// ============================================================================
+// Git
+// ============================================================================
+
+/// "tracked", "untracked" (would be picked up by `git add`), "ignored", or
+/// "none" when the file isn't inside a work tree. The file needn't exist yet.
+fn git_status(file: &Path) -> &'static str {
+    let Some(dir) = file.ancestors().skip(1).find(|d| d.is_dir()) else {
+        return "none";
+    };
+    let name = file.strip_prefix(dir).unwrap_or(file);
+    let git = |args: &[&str]| {
+        std::process::Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(args)
+            .arg("--")
+            .arg(name)
+            .output()
+            .ok()
+            .and_then(|o| o.status.code())
+    };
+    if git(&["ls-files", "--error-unmatch"]) == Some(0) {
+        return "tracked";
+    }
+    match git(&["check-ignore", "-q"]) {
+        Some(0) => "ignored",
+        Some(1) => "untracked",
+        _ => "none",
+    }
+}
+
+/// The .gitignore entry that keeps `file` out of git, if git would otherwise pick it up
+fn ignore_op(project: &Path, file: &Path) -> Result<Option<FixOp>, String> {
+    match git_status(file) {
+        "tracked" => return Err(format!("{} is tracked by git, so it can't hold secrets", file.display())),
+        "untracked" => {}
+        _ => return Ok(None),
+    }
+    let relative = file.strip_prefix(project).unwrap_or(file).to_string_lossy().replace('\\', "/");
+    Ok(Some(FixOp::AppendLine {
+        path: project.join(".gitignore").to_string_lossy().to_string(),
+        line: format!("/{}", relative),
+    }))
+}
+
+// ============================================================================
+// Scanning
+// ============================================================================
+
+/// Key of an OpenCode entry's environment map as written in the file
+fn raw_env_key(text: Option<&str>, entry_pointer: &str) -> &'static str {
+    let has = |key: &str| {
+        text.and_then(|t| json_edit::get(t, &format!("{}/{}", entry_pointer, key)).ok().flatten()).is_some()
+    };
+    if !has("environment") && has("env") { "env" } else { "environment" }
+}
+
+struct ScanTarget<'a> {
+    file: &'a str,
+    pointer: String,
+    field: String,
+    key: &'a str,
+    value: &'a str,
+    source: &'static str,
+    entity_id: &'a str,
+    server_name: Option<&'a str>,
+    tool: &'a str,
+    scope: &'a str,
+    project_path: Option<&'a str>,
+    suggested_var: String,
+    from_plugin: bool,
+}
+
+/// Scan MCP server env / headers and Claude Code settings `env` for literal
+/// secrets in files git tracks or would pick up
+pub(crate) fn scan_secrets_internal(servers: &[McpServerEntity], settings: &[SettingsEntity]) -> Vec<SecretFinding> {
+    let mut texts: HashMap<&str, Option<String>> = HashMap::new();
+    let mut targets = Vec::new();
+
+    for server in servers {
+        let text = texts.entry(server.source_path.as_str())
+            .or_insert_with(|| fs::read_to_string(&server.source_path).ok())
+            .as_deref();
+        let entry_pointer = text
+            .and_then(|t| entity_location(server, t).ok())
+            .map(|location| location.pointer(&server.name))
+            .unwrap_or_default();
+        let env_key = if server.tool == "opencode" { raw_env_key(text, &entry_pointer) } else { "env" };
+
+        let fields = server.config.env.iter().flatten().map(|(k, v)| (env_key, "env", k, v))
+            .chain(server.config.headers.iter().flatten().map(|(k, v)| ("headers", "headers", k, v)));
+        for (raw_key, label, key, value) in fields {
+            targets.push(ScanTarget {
+                file: &server.source_path,
+                pointer: format!("{}{}", entry_pointer, json_edit::build_pointer(&[raw_key, key])),
+                field: format!("{}.{}", label, key),
+                key,
+                value,
+                source: "mcp",
+                entity_id: &server.id,
+                server_name: Some(&server.name),
+                tool: &server.tool,
+                scope: &server.scope,
+                project_path: server.project_path.as_deref(),
+                suggested_var: if label == "env" { env_var_name(&[key]) } else { env_var_name(&[&server.name, key]) },
+                from_plugin: server.is_from_plugin,
+            });
+        }
+    }
+
+    for setting in settings.iter().filter(|s| s.base.tool == "claude") {
+        let Some(env) = setting.parsed.as_ref().and_then(|p| p.get("env")).and_then(|e| e.as_object()) else {
+            continue;
+        };
+        for (key, value) in env {
+            let Some(value) = value.as_str() else { continue };
+            targets.push(ScanTarget {
+                file: &setting.base.path,
+                pointer: json_edit::build_pointer(&["env", key]),
+                field: format!("env.{}", key),
+                key,
+                value,
+                source: "settings",
+                entity_id: &setting.base.id,
+                server_name: None,
+                tool: &setting.base.tool,
+                scope: &setting.variant,
+                project_path: setting.base.project_path.as_deref(),
+                suggested_var: key.clone(),
+                from_plugin: false,
+            });
+        }
+    }
+
+    let mut statuses: HashMap<&str, &'static str> = HashMap::new();
+    let mut findings = Vec::new();
+    for target in targets {
+        let Some((kind, token_type, literal)) = detect_secret(target.key, target.value) else {
+            continue;
+        };
+        // Literals in ignored files and outside repositories are where secrets belong
+        let git = *statuses.entry(target.file).or_insert_with(|| git_status(Path::new(target.file)));
+        let (severity, risk) = match git {
+            "tracked" => (Severity::Error, "is committed to git"),
+            "untracked" => (Severity::Warning, "is not ignored by git and will be committed with the file"),
+            _ => continue,
+        };
+        let owner = match target.server_name {
+            Some(name) => format!("MCP server \"{}\"", name),
+            None => format!("{} settings", target.scope),
+        };
+        findings.push(SecretFinding {
+            id: generate_id("secret", &format!("{}#{}", target.file, target.pointer)),
+            kind: kind.to_string(),
+            severity,
+            title: format!("{} in {}", token_type, target.field),
+            description: format!("{} of {} {}", target.field, owner, risk),
+            file: target.file.to_string(),
+            pointer: target.pointer.clone(),
+            field: target.field,
+            source: target.source.to_string(),
+            entity_id: target.entity_id.to_string(),
+            server_name: target.server_name.map(String::from),
+            tool: target.tool.to_string(),
+            scope: target.scope.to_string(),
+            project_path: target.project_path.map(String::from),
+            token_type: token_type.to_string(),
+            preview: mask(&literal),
+            git_status: git.to_string(),
+            suggested_var: target.suggested_var,
+            fixable: target.project_path.is_some() && !target.from_plugin && !target.pointer.is_empty(),
+        });
+    }
+    findings
+}
+
+// ============================================================================
+// Fixing
+// ============================================================================
+
+/// Value of `name` in a .env file, unquoted
+fn dotenv_value(content: &str, name: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        let line = line.trim().trim_start_matches("export ");
+        let (key, value) = line.split_once('=')?;
+        (key.trim() == name).then(|| {
+            let value = value.trim();
+            let unquoted = value.strip_prefix('"').and_then(|v| v.strip_suffix('"'))
+                .map(|v| v.replace("\\\"", "\"").replace("\\\\", "\\"))
+                .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')).map(String::from));
+            unquoted.unwrap_or_else(|| value.to_string())
+        })
+    })
+}
+
+/// The edit that stores `name=value` in the file that will hold the secret;
+/// None when it's already there
+fn store_op(secret_file: &Path, env_format: bool, name: &str, value: &str) -> Result<Option<FixOp>, String> {
+    let original = fs::read_to_string(secret_file).ok();
+    let path = secret_file.to_string_lossy().to_string();
+    if env_format {
+        match dotenv_value(original.as_deref().unwrap_or_default(), name) {
+            Some(existing) if existing == value => return Ok(None),
+            Some(_) => return Err(format!("{} already sets {} to a different value; pick another name", secret_file.display(), name)),
+            None => {}
+        }
+        let needs_quotes = value.chars().any(|c| c.is_whitespace() || "#\"'\\$".contains(c));
+        let line = if needs_quotes {
+            format!("{}=\"{}\"", name, value.replace('\\', "\\\\").replace('"', "\\\""))
+        } else {
+            format!("{}={}", name, value)
+        };
+        Ok(Some(FixOp::AppendLine { path, line }))
+    } else {
+        let pointer = json_edit::build_pointer(&["env", name]);
+        let existing = match original.as_deref().filter(|t| !t.trim().is_empty()) {
+            Some(text) => json_edit::get(text, &pointer)?,
+            None => None,
+        };
+        match existing {
+            Some(existing) if existing.as_str() == Some(value) => Ok(None),
+            Some(_) => Err(format!("{} already sets env.{} to a different value; pick another name", secret_file.display(), name)),
+            None => Ok(Some(FixOp::JsonPatch {
+                file: path,
+                ops: vec![JsonPatchOp::Add { path: pointer, value: serde_json::Value::String(value.to_string()) }],
+            })),
+        }
+    }
+}
+
+/// Everything extract_secret will do, as file operations
+pub(crate) struct SecretFixPlan {
+    pub variable: String,
+    pub secret_file: PathBuf,
+    pub reference: Option<String>,
+    pub gitignore: Option<String>,
+    pub ops: Vec<FixOp>,
+}
+
+/// Work out where a secret goes and the edits that move it there, without writing anything
+pub(crate) fn plan_secret_fix(finding: &SecretFinding, var_name: Option<String>) -> Result<SecretFixPlan, String> {
+    if !finding.fixable {
+        return Err(format!("{} can't be fixed automatically; move the secret by hand", finding.field));
+    }
+    let project = PathBuf::from(finding.project_path.as_deref().ok_or("Secret is not in a project file")?);
+    let source_file = PathBuf::from(&finding.file);
+    let text = fs::read_to_string(&source_file).map_err(|e| format!("Failed to read {}: {}", source_file.display(), e))?;
+    let value = json_edit::get(&text, &finding.pointer)?
+        .and_then(|v| v.as_str().map(String::from))
+        .ok_or_else(|| format!("{} no longer has {}; rescan and try again", source_file.display(), finding.field))?;
+    let key = finding.field.split_once('.').map(|(_, k)| k).unwrap_or(&finding.field);
+    let (_, _, literal) = detect_secret(key, &value)
+        .ok_or_else(|| format!("{} no longer contains a literal secret", finding.field))?;
+
+    let moves_key = finding.source == "settings";
+    let variable = if moves_key {
+        key.to_string()
+    } else {
+        var_name.filter(|n| !n.trim().is_empty()).unwrap_or_else(|| finding.suggested_var.clone())
+    };
+    if !is_valid_var_name(&variable) {
+        return Err(format!("\"{}\" is not a valid environment variable name", variable));
+    }
+
+    let opencode = finding.tool == "opencode";
+    let secret_file = if opencode { project.join(".env") } else { project.join(".claude").join("settings.local.json") };
+    if secret_file == source_file {
+        return Err(format!("{} is already the local secrets file", source_file.display()));
+    }
+    let ignore = ignore_op(&project, &secret_file)?;
+
+    // For settings `env` the whole value is the secret; for MCP fields only the literal part is
+    let stored = if moves_key { value.clone() } else { literal.clone() };
+    let store = store_op(&secret_file, opencode, &variable, &stored)?;
+
+    let reference = (!moves_key).then(|| {
+        let placeholder = if opencode { format!("{{env:{}}}", variable) } else { format!("${{{}}}", variable) };
+        value.replace(&literal, &placeholder)
+    });
+    let test = JsonPatchOp::Test { path: finding.pointer.clone(), value: serde_json::Value::String(value.clone()) };
+    let edit = match &reference {
+        Some(reference) => JsonPatchOp::Replace { path: finding.pointer.clone(), value: serde_json::Value::String(reference.clone()) },
+        None => JsonPatchOp::Remove { path: finding.pointer.clone() },
+    };
+
+    let gitignore = match &ignore {
+        Some(FixOp::AppendLine { path, .. }) => Some(path.clone()),
+        _ => None,
+    };
+    let mut ops: Vec<FixOp> = ignore.into_iter().chain(store).collect();
+    ops.push(FixOp::JsonPatch { file: finding.file.clone(), ops: vec![test, edit] });
+    Ok(SecretFixPlan { variable, secret_file, reference, gitignore, ops })
+}
+
+// ============================================================================
+// Public Tauri Commands
+// ============================================================================
+
+/// Scan the given MCP servers and settings for literal secrets
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn scan_secrets(servers: Vec<McpServerEntity>, settings: Vec<SettingsEntity>) -> Result<Vec<SecretFinding>, String> {
+    Ok(scan_secrets_internal(&servers, &settings))
+}
+
+/// Move a detected secret out of a git-tracked file:
+/// - Claude Code MCP env / headers: the literal goes to
+///   .claude/settings.local.json `env` and the value becomes `${VAR}`
+/// - OpenCode MCP env / headers: the literal goes to <project>/.env and the
+///   value becomes `{env:VAR}`
+/// - settings.json `env`: the key moves to settings.local.json as is
+///
+/// The file receiving the secret is added to .gitignore if git would pick it up.
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn extract_secret(finding: SecretFinding, var_name: Option<String>) -> Result<SecretFixResult, String> {
+    let plan = plan_secret_fix(&finding, var_name)?;
+    fixes::apply_ops(&plan.ops)?;
+    let opencode = finding.tool == "opencode";
+
+    let mut warnings = Vec::new();
+    if finding.git_status == "tracked" {
+        warnings.push("The secret is still in git history; rotate it with the issuer".to_string());
+    }
+    if opencode {
+        warnings.push("OpenCode only sees variables in its environment; load .env (direnv, dotenv) or start it from the project root".to_string());
+    }
+
+    Ok(SecretFixResult {
+        variable: plan.variable,
+        source_file: finding.file,
+        secret_file: plan.secret_file.to_string_lossy().to_string(),
+        reference: plan.reference,
+        gitignore_updated: plan.gitignore,
+        warnings,
+    })
+}
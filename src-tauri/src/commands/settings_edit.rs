@@ -0,0 +1,131 @@
+// ============================================================================
+// Agent Studio - Structured Settings Editing
+// Typed commands for permission rules, env vars and top-level settings keys
+// ============================================================================
+
+use std::path::PathBuf;
+
+use super::get_home_dir;
+use super::json_edit;
+
+/// Keys that are managed by dedicated commands instead of `set_settings_key`
+const MANAGED_KEYS: &[&str] = &["permissions", "env", "hooks"];
+
+/// Resolve the settings.json file for a layer ("global"/"user", "project", "local")
+pub(crate) fn settings_layer_path(layer: &str, project_path: Option<&str>) -> Result<PathBuf, String> {
+    match layer {
+        "global" | "user" => {
+            let home = get_home_dir().ok_or("Could not find home directory")?;
+            Ok(home.join(".claude").join("settings.json"))
+        }
+        "project" | "local" => {
+            let project = project_path.ok_or("Project path required for project-scoped settings")?;
+            let file_name = if layer == "local" { "settings.local.json" } else { "settings.json" };
+            Ok(PathBuf::from(project).join(".claude").join(file_name))
+        }
+        _ => Err(format!("Unknown settings layer: {}", layer)),
+    }
+}
+
+fn validate_permission_list(list: &str) -> Result<(), String> {
+    match list {
+        "allow" | "deny" | "ask" => Ok(()),
+        _ => Err(format!("Unknown permission list \"{}\" (expected allow, deny or ask)", list)),
+    }
+}
+
+// ============================================================================
+// Public Tauri Commands
+// ============================================================================
+
+/// Add a rule to permissions.allow/deny/ask; does nothing if it is already present
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn add_permission_rule(layer: String, project_path: Option<String>, list: String, rule: String) -> Result<(), String> {
+    validate_permission_list(&list)?;
+    let rule = rule.trim().to_string();
+    if rule.is_empty() {
+        return Err("Permission rule cannot be empty".to_string());
+    }
+    let path = settings_layer_path(&layer, project_path.as_deref())?;
+    let pointer = json_edit::build_pointer(&["permissions", &list]);
+
+    json_edit::edit_file(&path, |text| {
+        let existing = json_edit::get(text, &pointer)?;
+        if existing.as_ref().and_then(|v| v.as_array()).is_some_and(|arr| arr.iter().any(|v| v.as_str() == Some(rule.as_str()))) {
+            return Ok(text.to_string());
+        }
+        json_edit::append(text, &pointer, &serde_json::Value::String(rule.clone()))
+    })
+}
+
+/// Remove every occurrence of a rule from permissions.allow/deny/ask
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn remove_permission_rule(layer: String, project_path: Option<String>, list: String, rule: String) -> Result<(), String> {
+    validate_permission_list(&list)?;
+    let path = settings_layer_path(&layer, project_path.as_deref())?;
+    let pointer = json_edit::build_pointer(&["permissions", &list]);
+
+    json_edit::edit_file(&path, |text| {
+        let rules = json_edit::get(text, &pointer)?
+            .and_then(|v| v.as_array().cloned())
+            .unwrap_or_default();
+        let mut updated = text.to_string();
+        // Remove from the end so earlier indices stay valid
+        for (idx, value) in rules.iter().enumerate().rev() {
+            if value.as_str() == Some(rule.trim()) {
+                updated = json_edit::remove(&updated, &format!("{}/{}", pointer, idx))?;
+            }
+        }
+        Ok(updated)
+    })
+}
+
+/// Set an environment variable in the `env` block
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn set_settings_env(layer: String, project_path: Option<String>, name: String, value: String) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Environment variable name cannot be empty".to_string());
+    }
+    let path = settings_layer_path(&layer, project_path.as_deref())?;
+    let pointer = json_edit::build_pointer(&["env", &name]);
+    json_edit::edit_file(&path, |text| json_edit::set(text, &pointer, &serde_json::Value::String(value.clone())))
+}
+
+/// Remove an environment variable from the `env` block
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn unset_settings_env(layer: String, project_path: Option<String>, name: String) -> Result<(), String> {
+    let path = settings_layer_path(&layer, project_path.as_deref())?;
+    let pointer = json_edit::build_pointer(&["env", &name]);
+    json_edit::edit_file(&path, |text| match json_edit::get(text, &pointer)? {
+        Some(_) => json_edit::remove(text, &pointer),
+        None => Ok(text.to_string()),
+    })
+}
+
+/// Set a top-level settings key such as `model` or `includeCoAuthoredBy`
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn set_settings_key(layer: String, project_path: Option<String>, key: String, value: serde_json::Value) -> Result<(), String> {
+    if key.trim().is_empty() {
+        return Err("Settings key cannot be empty".to_string());
+    }
+    if MANAGED_KEYS.contains(&key.as_str()) {
+        return Err(format!("\"{}\" is edited with its dedicated commands", key));
+    }
+    let path = settings_layer_path(&layer, project_path.as_deref())?;
+    let pointer = json_edit::build_pointer(&[&key]);
+    json_edit::edit_file(&path, |text| json_edit::set(text, &pointer, &value))
+}
+
+/// Remove a top-level settings key
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn unset_settings_key(layer: String, project_path: Option<String>, key: String) -> Result<(), String> {
+    if MANAGED_KEYS.contains(&key.as_str()) {
+        return Err(format!("\"{}\" is edited with its dedicated commands", key));
+    }
+    let path = settings_layer_path(&layer, project_path.as_deref())?;
+    let pointer = json_edit::build_pointer(&[&key]);
+    json_edit::edit_file(&path, |text| match json_edit::get(text, &pointer)? {
+        Some(_) => json_edit::remove(text, &pointer),
+        None => Ok(text.to_string()),
+    })
+}
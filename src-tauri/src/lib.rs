@@ -4,12 +4,14 @@
 // Skills.sh integration for skill discovery, installation, and management
 // ============================================================================
 
-mod commands;
-mod skills;
+pub mod commands;
+pub mod mcp_server;
+pub mod skills;
 
 pub use commands::*;
 pub use skills::*;
 
+#[cfg(feature = "gui")]
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
@@ -31,14 +33,65 @@ pub fn run() {
             commands::discover_plugins,
             commands::discover_mcp_servers,
             commands::extract_hooks,
+
+            // Hook editing
+            commands::hooks::validate_hook,
+            commands::hooks::add_hook_matcher,
+            commands::hooks::update_hook_matcher,
+            commands::hooks::remove_hook_matcher,
+            commands::hooks::move_hook_matcher,
+            commands::hooks::add_hook_definition,
+            commands::hooks::update_hook_definition,
+            commands::hooks::remove_hook_definition,
+            commands::hook_runner::build_hook_payload,
+            commands::hook_runner::test_hook,
+
+            // Plugin management
+            commands::plugins::get_plugin_enabled_state,
+            commands::plugins::enable_plugin,
+            commands::plugins::disable_plugin,
+            commands::plugins::list_marketplaces,
+            commands::plugins::install_marketplace_plugin,
+            commands::plugins::uninstall_marketplace_plugin,
+            commands::plugin_authoring::create_plugin,
+            commands::plugin_authoring::validate_plugin,
+            commands::plugin_authoring::generate_marketplace_entry,
+
+            // MCP servers
+            commands::mcp_probe::probe_mcp_server,
+            commands::mcp_edit::copy_mcp_server,
+            commands::mcp_edit::add_mcp_server,
+            commands::mcp_edit::update_mcp_server,
+            commands::mcp_edit::remove_mcp_server,
+            commands::mcp_import::preview_mcp_import,
+            commands::mcp_import::apply_mcp_import,
+            commands::secret_scan::scan_secrets,
+            commands::secret_scan::extract_secret,
             
             // Analysis
             commands::find_duplicates,
             commands::check_symlink,
+            commands::frontmatter_lint::lint_frontmatter,
+            commands::health::run_health_checks,
+            commands::health::list_health_checks,
+            commands::fixes::preview_fix,
+            commands::fixes::apply_fixes,
 
             // Config state (AGENTS.md / CLAUDE.md consistency)
             commands::get_project_config_state,
             commands::fix_project_config,
+
+            // Permissions audit
+            commands::permissions::audit_permissions,
+            commands::permissions::apply_permission_fix,
+
+            // Structured settings editing
+            commands::settings_edit::add_permission_rule,
+            commands::settings_edit::remove_permission_rule,
+            commands::settings_edit::set_settings_env,
+            commands::settings_edit::unset_settings_env,
+            commands::settings_edit::set_settings_key,
+            commands::settings_edit::unset_settings_key,
             
             // File operations
             commands::read_file,
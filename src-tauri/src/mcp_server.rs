@@ -0,0 +1,494 @@
+// ============================================================================
+// Agent Studio - MCP Server Mode
+// Serve discovery, skills and entity creation as MCP tools over stdio, so a
+// coding agent can inspect and manage its own configuration
+// ============================================================================
+
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::hash::{BuildHasher, RandomState};
+use std::io::{BufRead, Write};
+use std::sync::{LazyLock, Mutex};
+
+use crate::commands::{self, mcp_edit, mcp_probe::PROTOCOL_VERSION, McpServerConfig};
+use crate::skills::{self, AgentId, InstallRequest, InstallScope};
+
+/// Revisions we can speak; a client asking for one of these gets it back
+const SUPPORTED_VERSIONS: &[&str] = &[PROTOCOL_VERSION, "2025-03-26", "2024-11-05"];
+
+const INSTRUCTIONS: &str = "Agent Studio manages Claude Code and OpenCode configuration: agents, skills, \
+commands, hooks, plugins and MCP servers. Tools that change files first only describe the change and return \
+a confirm_token; show the description to the user and, if they approve, call again with that token.";
+
+/// Tokens handed out with a plan and not yet used; each applies that exact plan once
+static ISSUED_TOKENS: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(Default::default);
+
+/// Keyed per process, so a token can't be worked out without asking for the plan
+static TOKEN_KEY: LazyLock<RandomState> = LazyLock::new(RandomState::new);
+
+/// Top-level keys of a DiscoveryResult that `entity_types` can select
+const ENTITY_KEYS: &[&str] = &[
+    "projects", "settings", "memory", "agents", "skills", "commands", "hooks", "plugins",
+    "mcp_servers", "lsp_servers", "duplicates", "symlinks", "hook_findings", "mcp_findings", "secret_findings", "health",
+];
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Debug, Clone, Default)]
+pub struct ServeOptions {
+    /// Hide the tools that write files or install anything
+    pub read_only: bool,
+}
+
+struct ToolSpec {
+    name: &'static str,
+    title: &'static str,
+    description: &'static str,
+    input_schema: Value,
+    mutating: bool,
+    /// Can overwrite or remove existing state, or run code it didn't write
+    destructive: bool,
+    open_world: bool,  // Talks to the network (skills.sh, npx)
+}
+
+/// Outcome of a tool call: a JSON payload, or a message the model should read as a failure
+type ToolResult = Result<Value, String>;
+
+// ============================================================================
+// Tool Definitions
+// ============================================================================
+
+fn confirm_schema() -> Value {
+    json!({
+        "type": "string",
+        "description": "The confirm_token from a previous call with the same arguments, once the user has approved its plan. \
+Without it the tool only describes what it would do.",
+    })
+}
+
+fn tool_specs() -> Vec<ToolSpec> {
+    vec![
+        ToolSpec {
+            name: "discover_all",
+            title: "Discover configuration",
+            description: "List agents, skills, commands, hooks, plugins, MCP servers and health findings for Claude Code and \
+OpenCode, globally and for the given projects (default: the current directory).",
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "project_paths": { "type": "array", "items": { "type": "string" } },
+                    "entity_types": {
+                        "type": "array",
+                        "items": { "type": "string", "enum": ENTITY_KEYS },
+                        "description": "Only return these sections, e.g. [\"skills\"]",
+                    },
+                    "include_content": { "type": "boolean", "description": "Include file contents (large)" },
+                },
+            }),
+            mutating: false,
+            destructive: false,
+            open_world: false,
+        },
+        ToolSpec {
+            name: "get_project_config_state",
+            title: "AGENTS.md / CLAUDE.md state",
+            description: "Whether a project's CLAUDE.md is a symlink to AGENTS.md, and whether that can be fixed automatically.",
+            input_schema: json!({
+                "type": "object",
+                "properties": { "project_path": { "type": "string" } },
+                "required": ["project_path"],
+            }),
+            mutating: false,
+            destructive: false,
+            open_world: false,
+        },
+        ToolSpec {
+            name: "find_duplicates",
+            title: "Find duplicates",
+            description: "Global agents, skills and commands, including those from installed plugins, that share a name.",
+            input_schema: json!({ "type": "object", "properties": {} }),
+            mutating: false,
+            destructive: false,
+            open_world: false,
+        },
+        ToolSpec {
+            name: "search_skills",
+            title: "Search skills.sh",
+            description: "Search the skills.sh directory for installable skills.",
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string" },
+                    "limit": { "type": "integer", "minimum": 1, "maximum": 100 },
+                },
+                "required": ["query"],
+            }),
+            mutating: false,
+            destructive: false,
+            open_world: true,
+        },
+        ToolSpec {
+            name: "list_installed_skills",
+            title: "Installed skills.sh skills",
+            description: "Skills installed through skills.sh, with their source and whether an update is available.",
+            input_schema: json!({ "type": "object", "properties": {} }),
+            mutating: false,
+            destructive: false,
+            open_world: false,
+        },
+        ToolSpec {
+            name: "install_skill",
+            title: "Install a skill",
+            description: "Install a skill from skills.sh (owner/repo or owner/repo/skill) with `npx skills add`. \
+Requires a confirm_token from a first call.",
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "source": { "type": "string" },
+                    "scope": { "type": "string", "enum": ["global", "project"] },
+                    "project_path": { "type": "string", "description": "Default: the current directory" },
+                    "agents": { "type": "array", "items": { "type": "string" }, "description": "Agent ids, e.g. claude-code, opencode" },
+                    "confirm_token": confirm_schema(),
+                },
+                "required": ["source", "scope"],
+            }),
+            mutating: true,
+            destructive: true,  // Runs `npx skills add`, i.e. code fetched from the network
+            open_world: true,
+        },
+        ToolSpec {
+            name: "create_entity",
+            title: "Create an agent, skill, command or memory file",
+            description: "Create a new agent, skill, command or memory (CLAUDE.md / AGENTS.md) file, from the given content \
+or a starter template. Refuses to overwrite an existing file. Requires a confirm_token from a first call.",
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "entity_type": { "type": "string", "enum": ["agent", "skill", "command", "memory"] },
+                    "name": { "type": "string" },
+                    "scope": { "type": "string", "enum": ["global", "project"] },
+                    "project_path": { "type": "string", "description": "Default: the current directory" },
+                    "content": { "type": "string", "description": "Full file content including frontmatter" },
+                    "tool": { "type": "string", "enum": ["claude", "opencode"] },
+                    "confirm_token": confirm_schema(),
+                },
+                "required": ["entity_type", "name", "scope"],
+            }),
+            mutating: true,
+            destructive: false,
+            open_world: false,
+        },
+        ToolSpec {
+            name: "add_mcp_server",
+            title: "Add an MCP server",
+            description: "Add an MCP server to Claude Code (user, project or local scope) or OpenCode (global or project). \
+Fails if the name is taken. Requires a confirm_token from a first call.",
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "tool": { "type": "string", "enum": ["claude", "opencode"] },
+                    "scope": { "type": "string", "enum": ["user", "project", "local", "global"] },
+                    "project_path": { "type": "string", "description": "Default: the current directory" },
+                    "name": { "type": "string" },
+                    "config": {
+                        "type": "object",
+                        "description": "{ type: stdio|http|sse, command, args, env } or { type, url, headers }; use ${VAR} for secrets",
+                    },
+                    "confirm_token": confirm_schema(),
+                },
+                "required": ["tool", "scope", "name", "config"],
+            }),
+            mutating: true,
+            destructive: false,
+            open_world: false,
+        },
+    ]
+}
+
+fn tool_listing(spec: &ToolSpec) -> Value {
+    json!({
+        "name": spec.name,
+        "title": spec.title,
+        "description": spec.description,
+        "inputSchema": spec.input_schema,
+        "annotations": {
+            "title": spec.title,
+            "readOnlyHint": !spec.mutating,
+            "destructiveHint": spec.destructive,
+            "idempotentHint": !spec.mutating,
+            "openWorldHint": spec.open_world,
+        },
+    })
+}
+
+// ============================================================================
+// Argument Helpers
+// ============================================================================
+
+fn str_arg(args: &Value, key: &str) -> Option<String> {
+    args.get(key).and_then(|v| v.as_str()).map(String::from)
+}
+
+fn required_str(args: &Value, key: &str) -> Result<String, String> {
+    str_arg(args, key).ok_or_else(|| format!("Missing required argument: {}", key))
+}
+
+/// `project_path`, or the directory the client started us in
+fn project_arg(args: &Value) -> Option<String> {
+    str_arg(args, "project_path").or_else(|| {
+        std::env::current_dir().ok().map(|d| d.to_string_lossy().to_string())
+    })
+}
+
+/// Gate a mutating call on its plan. Returns `None` when the call carries the
+/// token issued for exactly this plan (using it up), otherwise the reply to
+/// send instead: the plan with a fresh token, or an error for a stale token.
+fn confirmation(tool: &str, args: &Value, plan: &str, details: &Value) -> Option<ToolResult> {
+    let token = format!("{:016x}", TOKEN_KEY.hash_one((tool, plan, details.to_string())));
+    let mut issued = ISSUED_TOKENS.lock().unwrap_or_else(|e| e.into_inner());
+    match args.get("confirm_token").and_then(|v| v.as_str()) {
+        Some(given) if given == token && issued.remove(&token) => None,
+        Some(_) => Some(Err(
+            "confirm_token doesn't match this change: it was already used, or the arguments differ from the \
+             approved plan. Call again without confirm_token for a new plan."
+                .to_string(),
+        )),
+        None => {
+            issued.insert(token.clone());
+            Some(Ok(json!({
+                "applied": false,
+                "plan": plan,
+                "details": details,
+                "confirm_token": token,
+                "next": "Show this plan to the user; if they approve, call the tool again with the same arguments and this confirm_token",
+            })))
+        }
+    }
+}
+
+/// Drop `content` fields so discovery output stays small
+fn strip_content(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.remove("content");
+            map.values_mut().for_each(strip_content);
+        }
+        Value::Array(items) => items.iter_mut().for_each(strip_content),
+        _ => {}
+    }
+}
+
+// ============================================================================
+// Tool Implementations
+// ============================================================================
+
+fn discover_all(args: &Value) -> ToolResult {
+    let projects = match args.get("project_paths").and_then(|p| p.as_array()) {
+        Some(paths) => paths.iter().filter_map(|p| p.as_str().map(String::from)).collect(),
+        None => project_arg(args).into_iter().collect(),
+    };
+    let result = commands::discover_all(Some(projects))?;
+    let mut value = serde_json::to_value(&result).map_err(|e| e.to_string())?;
+    if let Some(types) = args.get("entity_types").and_then(|t| t.as_array()) {
+        let wanted: Vec<&str> = types.iter().filter_map(|t| t.as_str()).collect();
+        if let Some(unknown) = wanted.iter().find(|t| !ENTITY_KEYS.contains(t)) {
+            return Err(format!("Unknown entity type \"{}\"; expected one of {}", unknown, ENTITY_KEYS.join(", ")));
+        }
+        if let Value::Object(map) = &mut value {
+            map.retain(|key, _| wanted.contains(&key.as_str()));
+        }
+    }
+    if args.get("include_content").and_then(|v| v.as_bool()) != Some(true) {
+        strip_content(&mut value);
+    }
+    Ok(value)
+}
+
+fn install_skill(args: &Value, runtime: &tokio::runtime::Runtime) -> ToolResult {
+    let source = required_str(args, "source")?;
+    let global = match required_str(args, "scope")?.as_str() {
+        "global" => true,
+        "project" => false,
+        other => return Err(format!("Unknown scope \"{}\"", other)),
+    };
+    let agents = args.get("agents").and_then(|a| a.as_array()).into_iter().flatten()
+        .filter_map(|a| a.as_str())
+        .map(|name| AgentId::all().into_iter().find(|a| a.cli_name() == name).ok_or_else(|| format!("Unknown agent \"{}\"", name)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let project_path = if global { None } else { project_arg(args) };
+
+    let target = project_path.as_deref().map(|p| format!("project {}", p)).unwrap_or_else(|| "global scope".to_string());
+    let plan = format!("Install skill {} into {} with `npx skills add`", source, target);
+    let agent_names: Vec<&str> = agents.iter().map(|a| a.cli_name()).collect();
+    let details = json!({
+        "source": source,
+        "scope": if global { "global" } else { "project" },
+        "project_path": project_path,
+        "agents": agent_names,
+    });
+    if let Some(reply) = confirmation("install_skill", args, &plan, &details) {
+        return reply;
+    }
+    let request = InstallRequest {
+        skill_source: source,
+        scope: if global { InstallScope::Global } else { InstallScope::Project },
+        project_path,
+        agents,
+    };
+    let result = runtime.block_on(skills::install_skill(request))?;
+    if !result.success {
+        return Err(result.error.unwrap_or_else(|| "npx skills failed".to_string()));
+    }
+    Ok(json!({ "applied": true, "result": result }))
+}
+
+fn create_entity(args: &Value) -> ToolResult {
+    let entity_type = required_str(args, "entity_type")?;
+    let name = required_str(args, "name")?;
+    let scope = required_str(args, "scope")?;
+    let tool = str_arg(args, "tool").unwrap_or_else(|| "claude".to_string());
+    let project_path = (scope != "global").then(|| project_arg(args)).flatten();
+    let content = str_arg(args, "content");
+    if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+        return Err(format!("Invalid name \"{}\"", name));
+    }
+
+    let (path, file_content) = commands::entity_target(&entity_type, &name, &scope, project_path.as_deref(), content.clone(), &tool)?;
+    if path.exists() {
+        return Err(format!("{} already exists; edit it instead", path.display()));
+    }
+    let plan = format!("Create {} {} at {}", entity_type, name, path.display());
+    if let Some(reply) = confirmation("create_entity", args, &plan, &json!({ "path": path, "content": file_content })) {
+        return reply;
+    }
+    let created = commands::create_entity(entity_type, name, scope, project_path, content, Some(tool))?;
+    Ok(json!({ "applied": true, "path": created }))
+}
+
+fn add_mcp_server(args: &Value) -> ToolResult {
+    let tool = required_str(args, "tool")?;
+    let scope = required_str(args, "scope")?;
+    let name = required_str(args, "name")?;
+    let project_path = (scope != "user" && scope != "global").then(|| project_arg(args)).flatten();
+    let config: McpServerConfig = serde_json::from_value(args.get("config").cloned().unwrap_or_default())
+        .map_err(|e| format!("Invalid config: {}", e))?;
+
+    // Validate and convert up front so the plan shows exactly what will be written
+    mcp_edit::validate_server_name(&name)?;
+    let location = mcp_edit::mcp_location(&tool, &scope, project_path.as_deref())?;
+    let mut warnings = Vec::new();
+    let entry = mcp_edit::convert_config(&config, &mcp_edit::config_transport(&config), &tool, &mut warnings)?;
+    let plan = format!("Add MCP server \"{}\" to {}", name, location.file.display());
+    let details = json!({ "file": location.file, "entry": entry, "warnings": warnings });
+    if let Some(reply) = confirmation("add_mcp_server", args, &plan, &details) {
+        return reply;
+    }
+    let result = mcp_edit::add_mcp_server(tool, scope, project_path, name, config)?;
+    Ok(json!({ "applied": true, "result": result }))
+}
+
+fn to_value<T: serde::Serialize>(value: &T) -> ToolResult {
+    serde_json::to_value(value).map_err(|e| e.to_string())
+}
+
+fn call_tool(name: &str, args: &Value, runtime: &tokio::runtime::Runtime) -> ToolResult {
+    match name {
+        "discover_all" => discover_all(args),
+        "get_project_config_state" => to_value(&commands::get_project_config_state(required_str(args, "project_path")?)?),
+        "find_duplicates" => to_value(&commands::find_duplicates()?),
+        "search_skills" => {
+            let query = required_str(args, "query")?;
+            let limit = args.get("limit").and_then(|l| l.as_u64()).map(|l| l.min(100) as u32);
+            to_value(&runtime.block_on(skills::api::search_skills(&query, limit, None))?)
+        }
+        "list_installed_skills" => to_value(&skills::get_installed_skills()?),
+        "install_skill" => install_skill(args, runtime),
+        "create_entity" => create_entity(args),
+        "add_mcp_server" => add_mcp_server(args),
+        _ => Err(format!("Unknown tool: {}", name)),
+    }
+}
+
+// ============================================================================
+// JSON-RPC
+// ============================================================================
+
+fn tool_call_result(result: ToolResult) -> Value {
+    match result {
+        Ok(value) => {
+            let text = serde_json::to_string_pretty(&value).unwrap_or_default();
+            let mut response = json!({ "content": [{ "type": "text", "text": text }], "isError": false });
+            if value.is_object() {
+                response["structuredContent"] = value;
+            }
+            response
+        }
+        Err(message) => json!({ "content": [{ "type": "text", "text": message }], "isError": true }),
+    }
+}
+
+/// The reply to one request, as (result, error)
+fn handle_request(method: &str, params: &Value, options: &ServeOptions, runtime: &tokio::runtime::Runtime) -> Result<Value, (i64, String)> {
+    let visible = |spec: &ToolSpec| !(options.read_only && spec.mutating);
+    match method {
+        "initialize" => {
+            let requested = params.get("protocolVersion").and_then(|v| v.as_str()).unwrap_or(PROTOCOL_VERSION);
+            let version = if SUPPORTED_VERSIONS.contains(&requested) { requested } else { PROTOCOL_VERSION };
+            Ok(json!({
+                "protocolVersion": version,
+                "capabilities": { "tools": { "listChanged": false } },
+                "serverInfo": { "name": "agent-studio", "title": "Agent Studio", "version": env!("CARGO_PKG_VERSION") },
+                "instructions": INSTRUCTIONS,
+            }))
+        }
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tool_specs().iter().filter(|s| visible(s)).map(tool_listing).collect::<Vec<_>>() })),
+        "tools/call" => {
+            let name = params.get("name").and_then(|n| n.as_str()).ok_or((-32602, "Missing tool name".to_string()))?;
+            if !tool_specs().iter().any(|s| s.name == name && visible(s)) {
+                return Err((-32602, format!("Unknown tool: {}", name)));
+            }
+            let args = params.get("arguments").cloned().unwrap_or_else(|| json!({}));
+            Ok(tool_call_result(call_tool(name, &args, runtime)))
+        }
+        _ => Err((-32601, format!("Method not found: {}", method))),
+    }
+}
+
+fn handle_line(line: &str, options: &ServeOptions, runtime: &tokio::runtime::Runtime) -> Option<Value> {
+    let message: Value = match serde_json::from_str(line) {
+        Ok(message) => message,
+        Err(e) => return Some(json!({ "jsonrpc": "2.0", "id": null, "error": { "code": -32700, "message": format!("Parse error: {}", e) } })),
+    };
+    // Notifications (no id) and responses to requests we never send need no reply
+    let id = message.get("id")?.clone();
+    let method = message.get("method").and_then(|m| m.as_str())?;
+    let params = message.get("params").cloned().unwrap_or_else(|| json!({}));
+    Some(match handle_request(method, &params, options, runtime) {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } }),
+    })
+}
+
+/// Serve MCP over stdin/stdout (newline-delimited JSON-RPC) until stdin closes.
+/// Logs go to stderr; stdout carries protocol messages only.
+pub fn serve_stdio(options: ServeOptions) -> Result<(), String> {
+    let runtime = tokio::runtime::Runtime::new().map_err(|e| e.to_string())?;
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout();
+    eprintln!("[agent-studio] MCP server ready{}", if options.read_only { " (read-only)" } else { "" });
+
+    for line in stdin.lock().lines() {
+        let line = line.map_err(|e| format!("Failed to read stdin: {}", e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = handle_line(&line, &options, &runtime) {
+            let text = serde_json::to_string(&response).map_err(|e| e.to_string())?;
+            writeln!(stdout, "{}", text).and_then(|_| stdout.flush()).map_err(|e| format!("Failed to write stdout: {}", e))?;
+        }
+    }
+    Ok(())
+}
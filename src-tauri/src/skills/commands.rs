@@ -10,37 +10,37 @@ use super::lock_file;
 use super::types::{AgentId, AgentTarget, InstallRequest, InstallResult, InstalledSkill, PaginatedSkillsResponse, SkillSearchResult};
 
 /// Search for skills on skills.sh
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub async fn search_skills(query: String, limit: Option<u32>, offset: Option<u32>) -> Result<PaginatedSkillsResponse, String> {
     api::search_skills(&query, limit, offset).await
 }
 
 /// Get popular skills (sorted by install count)
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub async fn get_popular_skills(limit: Option<u32>, offset: Option<u32>) -> Result<PaginatedSkillsResponse, String> {
     api::get_popular_skills(limit, offset).await
 }
 
 /// Get skill details from skills.sh
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub async fn get_skill_details(skill_id: String) -> Result<SkillSearchResult, String> {
     api::get_skill_details(&skill_id).await
 }
 
 /// Get all installed skills from the lock file
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub fn get_installed_skills() -> Result<Vec<InstalledSkill>, String> {
     lock_file::get_installed_skills()
 }
 
 /// Check if a skill is installed
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub fn is_skill_installed(skill_name: String) -> Result<bool, String> {
     lock_file::is_skill_installed(&skill_name)
 }
 
 /// Get all supported agent targets
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub fn get_agent_targets() -> Vec<AgentTarget> {
     let home = dirs::home_dir().unwrap_or_default();
     let home_str = home.to_string_lossy();
@@ -57,7 +57,7 @@ pub fn get_agent_targets() -> Vec<AgentTarget> {
 }
 
 /// Install a skill using npx skills CLI
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub async fn install_skill(request: InstallRequest) -> Result<InstallResult, String> {
     // Parse skill_source - could be "owner/repo" or "owner/repo/skill-name"
     // or just "skill-name" for well-known skills
@@ -154,8 +154,8 @@ fn parse_skill_source(source: &str) -> (String, Option<String>) {
 }
 
 /// Remove a skill using npx skills CLI
-#[tauri::command]
-pub async fn remove_skill(skill_name: String, global: bool) -> Result<InstallResult, String> {
+#[cfg_attr(feature = "gui", tauri::command)]
+pub async fn remove_skill(skill_name: String, global: bool, project_path: Option<String>) -> Result<InstallResult, String> {
     let mut args = vec!["skills".to_string(), "remove".to_string(), skill_name.clone()];
 
     // Add --yes for non-interactive mode (CLI has its own confirmation prompt)
@@ -168,8 +168,14 @@ pub async fn remove_skill(skill_name: String, global: bool) -> Result<InstallRes
     // Log the command for debugging
     eprintln!("[remove_skill] Running: npx {}", args.join(" "));
 
-    let output = Command::new("npx")
-        .args(&args)
+    let mut command = Command::new("npx");
+    command.args(&args);
+    // Project skills are removed from the project they were installed into,
+    // not from wherever this process happens to be running
+    if let Some(project_path) = project_path.filter(|_| !global) {
+        command.current_dir(project_path);
+    }
+    let output = command
         .output()
         .map_err(|e| format!("Failed to execute npx skills: {}", e))?;
 
@@ -198,7 +204,7 @@ pub async fn remove_skill(skill_name: String, global: bool) -> Result<InstallRes
 }
 
 /// Update a skill using npx skills CLI
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 pub async fn update_skill(skill_name: String, global: bool) -> Result<InstallResult, String> {
     let mut args = vec!["skills".to_string(), "update".to_string(), skill_name.clone()];
 
@@ -0,0 +1,90 @@
+// ============================================================================
+// Agent Studio - Shared Test Helpers
+// The golden-file loop behind the parser corpora, and scratch directories.
+// ============================================================================
+
+// Each test crate uses a different subset of these
+#![allow(dead_code)]
+
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Check every `fixtures/<fixture_dir>/<name>.<extension>` against `<name>.json`.
+///
+/// The golden file holds the input's `summary` fields plus an `edits` list;
+/// each edit is passed to `run_edit` with the input text and should come back
+/// with its `result` filled in. With UPDATE_GOLDEN=1 the golden files are
+/// rewritten from the actual output instead (the `edits` inputs are kept).
+pub fn check_golden_corpus(
+    fixture_dir: &str,
+    extension: &str,
+    summary: impl Fn(&str) -> serde_json::Map<String, Value>,
+    run_edit: impl Fn(&str, &Value) -> Value,
+) {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures").join(fixture_dir);
+    let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+    let mut failures = Vec::new();
+
+    let mut inputs: Vec<_> = fs::read_dir(&dir).unwrap()
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == extension))
+        .collect();
+    inputs.sort();
+    assert!(!inputs.is_empty(), "no fixtures in {}", dir.display());
+
+    for input in inputs {
+        let content = fs::read_to_string(&input).unwrap();
+        let golden_path = input.with_extension("json");
+        let expected: Value = fs::read_to_string(&golden_path).ok()
+            .map(|text| serde_json::from_str(&text).unwrap())
+            .unwrap_or_else(|| json!({}));
+
+        let edits: Vec<Value> = expected.get("edits").and_then(Value::as_array).into_iter().flatten()
+            .map(|edit| run_edit(&content, edit))
+            .collect();
+        let mut actual = summary(&content);
+        actual.insert("edits".to_string(), Value::Array(edits));
+        let actual = Value::Object(actual);
+
+        if update {
+            fs::write(&golden_path, serde_json::to_string_pretty(&actual).unwrap() + "\n").unwrap();
+        } else if actual != expected {
+            failures.push(format!(
+                "{}\n  expected: {}\n  actual:   {}",
+                input.display(), expected, actual,
+            ));
+        }
+    }
+
+    assert!(failures.is_empty(), "golden mismatches:\n{}", failures.join("\n"));
+}
+
+/// An edit from a golden file with its outcome recorded as `result`
+pub fn with_result(edit: &Value, result: Result<String, String>) -> Value {
+    let mut edit = edit.clone();
+    edit["result"] = match result {
+        Ok(updated) => Value::String(updated),
+        Err(e) => json!({ "error": e }),
+    };
+    edit
+}
+
+/// A scratch directory removed when the test ends
+pub struct TempDir(pub PathBuf);
+
+impl TempDir {
+    pub fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("agent-studio-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        TempDir(dir)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
@@ -0,0 +1,109 @@
+// ============================================================================
+// Agent Studio - Fix Plan Transactions
+// apply_fixes either applies every plan or leaves the files as they were, and
+// preview_fix stays cheap on large files.
+// ============================================================================
+
+mod common;
+
+use agent_studio_lib::commands::fixes::{apply_fixes, preview_fix, FixOp};
+use agent_studio_lib::commands::health::HealthFix;
+use agent_studio_lib::commands::permissions::JsonPatchOp;
+use common::TempDir;
+use serde_json::json;
+use std::fs;
+use std::path::Path;
+
+fn path(p: &Path) -> String {
+    p.to_string_lossy().to_string()
+}
+
+fn fix(description: &str, ops: Vec<FixOp>) -> HealthFix {
+    HealthFix { description: description.to_string(), ops }
+}
+
+fn add_key(file: &Path, key: &str) -> FixOp {
+    FixOp::JsonPatch {
+        file: path(file),
+        ops: vec![JsonPatchOp::Add { path: format!("/{}", key), value: json!(true) }],
+    }
+}
+
+#[test]
+fn failed_write_restores_earlier_writes_and_renames() {
+    let dir = TempDir::new("fixes-rollback");
+    let settings = dir.0.join("a-settings.json");
+    let agent = dir.0.join("agent.md");
+    let moved = dir.0.join("moved");
+    let original = "{\n  // keep\n  \"model\": \"sonnet\"\n}\n";
+    fs::write(&settings, original).unwrap();
+    fs::write(&agent, "---\nname: agent\n---\n").unwrap();
+
+    // The rename makes `moved` a file, so writing beneath it fails after the
+    // rename and the settings write (which sorts first) have both happened
+    let err = apply_fixes(vec![
+        fix("edit settings", vec![add_key(&settings, "enabled")]),
+        fix("move agent", vec![FixOp::Rename { from: path(&agent), to: path(&moved) }]),
+        fix("write below", vec![FixOp::AppendLine { path: path(&moved.join("notes.txt")), line: "x".to_string() }]),
+    ])
+    .unwrap_err();
+
+    assert!(!err.contains("Rolling back also failed"), "{}", err);
+    assert_eq!(fs::read_to_string(&settings).unwrap(), original);
+    assert_eq!(fs::read_to_string(&agent).unwrap(), "---\nname: agent\n---\n");
+    assert!(!moved.exists());
+}
+
+#[test]
+fn stale_plan_changes_nothing() {
+    let dir = TempDir::new("fixes-stale");
+    let settings = dir.0.join("settings.json");
+    let original = "{ \"permissions\": { \"allow\": [\"Bash(ls)\"] } }\n";
+    fs::write(&settings, original).unwrap();
+    fs::write(dir.0.join("a.md"), "a\n").unwrap();
+    fs::write(dir.0.join("b.md"), "b\n").unwrap();
+
+    let stale_test = FixOp::JsonPatch {
+        file: path(&settings),
+        ops: vec![
+            JsonPatchOp::Test { path: "/permissions/allow/0".to_string(), value: json!("Bash(rm:*)") },
+            JsonPatchOp::Remove { path: "/permissions/allow/0".to_string() },
+        ],
+    };
+    let taken_target = FixOp::Rename { from: path(&dir.0.join("a.md")), to: path(&dir.0.join("b.md")) };
+
+    for later in [stale_test, taken_target] {
+        let result = apply_fixes(vec![fix("edit", vec![add_key(&settings, "enabled")]), fix("later", vec![later])]);
+        assert!(result.is_err());
+        assert_eq!(fs::read_to_string(&settings).unwrap(), original);
+        assert_eq!(fs::read_to_string(dir.0.join("a.md")).unwrap(), "a\n");
+        assert_eq!(fs::read_to_string(dir.0.join("b.md")).unwrap(), "b\n");
+    }
+}
+
+#[test]
+fn preview_of_large_files_stays_small() {
+    let dir = TempDir::new("fixes-preview");
+    let notes = dir.0.join("notes.txt");
+    let lines: Vec<String> = (0..20_000).map(|i| format!("line {}", i)).collect();
+    fs::write(&notes, lines.join("\n") + "\n").unwrap();
+
+    // One appended line: a single hunk with its context
+    let preview = preview_fix(fix("append", vec![FixOp::AppendLine { path: path(&notes), line: "added".to_string() }])).unwrap();
+    let diff = &preview.files[0].diff;
+    assert!(diff.contains("@@ -19998,3 +19998,4 @@\n line 19997\n line 19998\n line 19999\n+added\n"), "{}", diff);
+
+    // A rewrite of every line is too big to align and is shown as a replacement
+    let rewritten = dir.0.join("rewritten.json");
+    let values: Vec<String> = (0..5_000).map(|i| format!("  \"k{}\": {}", i, i)).collect();
+    fs::write(&rewritten, format!("{{\n{}\n}}\n", values.join(",\n"))).unwrap();
+    let preview = preview_fix(fix("rewrite", vec![FixOp::JsonPatch {
+        file: path(&rewritten),
+        ops: vec![JsonPatchOp::Add { path: String::new(), value: json!({ "only": 1 }) }],
+    }]))
+    .unwrap();
+    let diff = &preview.files[0].diff;
+    assert_eq!(diff.lines().filter(|l| l.starts_with('-') && !l.starts_with("---")).count(), 5_000);
+    assert!(diff.contains("@@ -1,5002 +1,3 @@\n {\n"), "{}", &diff[..200]);
+    assert!(diff.contains("+  \"only\": 1\n"), "{}", diff);
+}
@@ -0,0 +1,47 @@
+// ============================================================================
+// Agent Studio - Frontmatter Golden Corpus
+// How each fixtures/frontmatter/<name>.md splits into YAML and body, what the
+// YAML parses to, and how `set` / `remove` edits rewrite it.
+// ============================================================================
+
+mod common;
+
+use agent_studio_lib::commands::frontmatter;
+use serde_json::{json, Value};
+
+fn split_summary(content: &str) -> Value {
+    match frontmatter::split(content) {
+        Ok(Some(fm)) => json!({
+            "yaml": fm.yaml_str(),
+            "yaml_start": fm.yaml.start,
+            "body_offset": fm.body_offset,
+            "closer": fm.closer,
+            "newline": fm.newline,
+        }),
+        Ok(None) => Value::Null,
+        Err(e) => json!({ "error": e }),
+    }
+}
+
+fn summary(content: &str) -> serde_json::Map<String, Value> {
+    let (map, body) = frontmatter::parse_frontmatter(content);
+    let mut summary = serde_json::Map::new();
+    summary.insert("split".to_string(), split_summary(content));
+    summary.insert("frontmatter".to_string(), json!(map.map(|m| m.into_iter().collect::<std::collections::BTreeMap<_, _>>())));
+    summary.insert("body".to_string(), json!(body));
+    summary
+}
+
+fn run_edit(content: &str, edit: &Value) -> Value {
+    let result = match (edit.get("set").and_then(Value::as_str), edit.get("remove").and_then(Value::as_str)) {
+        (Some(key), _) => frontmatter::set(content, key, edit.get("value").unwrap_or(&Value::Null)),
+        (_, Some(key)) => frontmatter::remove(content, key),
+        _ => Err("edit needs `set` or `remove`".to_string()),
+    };
+    common::with_result(edit, result)
+}
+
+#[test]
+fn frontmatter_corpus() {
+    common::check_golden_corpus("frontmatter", "md", summary, run_edit);
+}
@@ -0,0 +1,105 @@
+// ============================================================================
+// Agent Studio - JSON Editing Golden Corpus
+// set / append / remove / move / get against the JSONC files in
+// fixtures/json_edit, plus the conflict-checked file writes.
+// ============================================================================
+
+mod common;
+
+use agent_studio_lib::commands::json_edit;
+use common::TempDir;
+use serde_json::{json, Value};
+use std::fs;
+
+fn run_edit(content: &str, edit: &Value) -> Value {
+    let pointer = |key: &str| edit.get(key).and_then(Value::as_str);
+    let value = edit.get("value").unwrap_or(&Value::Null);
+    let result = if let Some(p) = pointer("set") {
+        json_edit::set(content, p, value)
+    } else if let Some(p) = pointer("append") {
+        json_edit::append(content, p, value)
+    } else if let Some(p) = pointer("remove") {
+        json_edit::remove(content, p)
+    } else if let Some(p) = pointer("move") {
+        let index = |key: &str| edit.get(key).and_then(Value::as_u64).unwrap_or(0) as usize;
+        json_edit::move_element(content, p, index("from"), index("to"))
+    } else if let Some(p) = pointer("get") {
+        json_edit::get(content, p).map(|v| serde_json::to_string(&v).unwrap())
+    } else {
+        Err("edit needs `set`, `append`, `remove`, `move` or `get`".to_string())
+    };
+
+    // Whatever the golden output says, an edit must never produce unparseable text
+    if let (Ok(updated), None) = (&result, pointer("get")) {
+        if let Err(e) = json_edit::to_value(updated) {
+            panic!("edit {} produced invalid JSON ({}):\n{}", edit, e, updated);
+        }
+    }
+
+    common::with_result(edit, result)
+}
+
+fn summary(content: &str) -> serde_json::Map<String, Value> {
+    let value = json_edit::to_value(content).unwrap_or_else(|e| json!({ "error": e }));
+    serde_json::Map::from_iter([("value".to_string(), value)])
+}
+
+#[test]
+fn json_edit_corpus() {
+    common::check_golden_corpus("json_edit", "jsonc", summary, run_edit);
+}
+
+#[test]
+fn write_if_unchanged_detects_conflicts() {
+    let dir = TempDir::new("json-edit");
+    let path = dir.0.join("settings.json");
+
+    // A missing file is created only when the caller also saw it missing
+    json_edit::write_if_unchanged(&path, None, "{}\n").unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "{}\n");
+    let err = json_edit::write_if_unchanged(&path, None, "{ \"a\": 1 }\n").unwrap_err();
+    assert!(err.contains("modified by another process"), "{}", err);
+
+    // Someone else changed the file since it was read
+    fs::write(&path, "{ \"b\": 2 }\n").unwrap();
+    let err = json_edit::write_if_unchanged(&path, Some("{}\n"), "{ \"a\": 1 }\n").unwrap_err();
+    assert!(err.contains("modified by another process"), "{}", err);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "{ \"b\": 2 }\n");
+
+    // Up to date: written, with no temp file left behind
+    json_edit::write_if_unchanged(&path, Some("{ \"b\": 2 }\n"), "{ \"a\": 1 }\n").unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "{ \"a\": 1 }\n");
+    let leftovers: Vec<_> = fs::read_dir(&dir.0).unwrap().flatten().map(|e| e.file_name()).collect();
+    assert_eq!(leftovers, ["settings.json"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn write_if_unchanged_writes_through_symlinks() {
+    let dir = TempDir::new("json-edit-link");
+    let target = dir.0.join("real.json");
+    let link = dir.0.join("settings.json");
+    fs::write(&target, "{}\n").unwrap();
+    std::os::unix::fs::symlink(&target, &link).unwrap();
+
+    json_edit::write_if_unchanged(&link, Some("{}\n"), "{ \"a\": 1 }\n").unwrap();
+    assert!(fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+    assert_eq!(fs::read_to_string(&target).unwrap(), "{ \"a\": 1 }\n");
+}
+
+#[test]
+fn edit_file_keeps_comments_and_refuses_invalid_results() {
+    let dir = TempDir::new("json-edit-file");
+    let path = dir.0.join("opencode.jsonc");
+    let original = "{\n  // Theme for the TUI\n  \"theme\": \"dark\",\n}\n";
+    fs::write(&path, original).unwrap();
+
+    json_edit::edit_file(&path, |text| json_edit::set(text, "/model", &json!("anthropic/claude-sonnet-4"))).unwrap();
+    let updated = fs::read_to_string(&path).unwrap();
+    assert!(updated.contains("// Theme for the TUI"), "{}", updated);
+    assert_eq!(json_edit::get(&updated, "/model").unwrap(), Some(json!("anthropic/claude-sonnet-4")));
+
+    let err = json_edit::edit_file(&path, |text| Ok(format!("{}}}", text))).unwrap_err();
+    assert!(err.contains("invalid JSON"), "{}", err);
+    assert_eq!(fs::read_to_string(&path).unwrap(), updated);
+}
@@ -0,0 +1,102 @@
+// ============================================================================
+// Agent Studio - MCP Probe Against the Stdio Fixture
+// Runs probe_mcp_server against fixtures/mcp-stdio-server.mjs, healthy and
+// with each of its failure flags. Skipped when `node` isn't installed.
+// ============================================================================
+
+use agent_studio_lib::commands::mcp_probe::{probe_mcp_server, McpProbeResult, McpProbeStage};
+use agent_studio_lib::commands::McpServerEntity;
+use serde_json::json;
+use std::path::Path;
+
+fn node_available() -> bool {
+    let found = std::process::Command::new("node").arg("--version").output().is_ok_and(|o| o.status.success());
+    if !found {
+        eprintln!("skipping: node is not installed");
+    }
+    found
+}
+
+fn fixture_server(flag: Option<&str>) -> McpServerEntity {
+    let script = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/mcp-stdio-server.mjs");
+    let mut args = vec![script.to_string_lossy().to_string()];
+    args.extend(flag.map(String::from));
+    serde_json::from_value(json!({
+        "id": "mcp_fixture",
+        "type": "mcp",
+        "name": "fixture",
+        "scope": "project",
+        "transport": "stdio",
+        "config": { "type": "stdio", "command": "node", "args": args, "url": null, "env": null, "headers": null },
+        "source_path": ".mcp.json",
+        "is_from_plugin": false,
+        "plugin_name": null,
+        "tool": "claude",
+    }))
+    .unwrap()
+}
+
+async fn probe(flag: Option<&str>, timeout_ms: u64) -> McpProbeResult {
+    probe_mcp_server(fixture_server(flag), None, Some(timeout_ms)).await.unwrap()
+}
+
+#[tokio::test]
+async fn probe_lists_everything() {
+    if !node_available() {
+        return;
+    }
+    let result = probe(None, 10_000).await;
+    assert!(result.ok, "probe failed: {:?} / {}", result.error, result.stderr);
+    assert_eq!(result.transport, "stdio");
+    assert_eq!(result.server_info.as_ref().and_then(|i| i.get("name")), Some(&json!("agent-studio-fixture")));
+    assert_eq!(result.instructions.as_deref(), Some("Fixture server for probe testing"));
+    // The fixture splits its tools over two pages
+    let tools: Vec<&str> = result.tools.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(tools, ["echo", "add"]);
+    assert_eq!(result.prompts.len(), 1);
+    assert_eq!(result.prompts[0].arguments[0].name, "path");
+    assert!(result.prompts[0].arguments[0].required);
+    assert_eq!(result.resources[0].uri, "file:///fixture/readme.md");
+    assert!(result.warnings.is_empty(), "unexpected warnings: {:?}", result.warnings);
+}
+
+#[tokio::test]
+async fn probe_reports_a_crash_with_its_stderr() {
+    if !node_available() {
+        return;
+    }
+    let result = probe(Some("--crash"), 10_000).await;
+    assert!(!result.ok);
+    assert_eq!(result.failed_stage, Some(McpProbeStage::Initialize));
+    assert_eq!(result.exit_code, Some(1));
+    assert!(result.stderr.contains("missing API_KEY"), "stderr: {:?}", result.stderr);
+}
+
+#[tokio::test]
+async fn probe_times_out_on_a_hung_server() {
+    if !node_available() {
+        return;
+    }
+    let started = std::time::Instant::now();
+    let result = probe(Some("--hang"), 1_500).await;
+    assert!(!result.ok);
+    assert_eq!(result.failed_stage, Some(McpProbeStage::Initialize));
+    assert!(result.error.as_deref().is_some_and(|e| e.to_lowercase().contains("timed out")), "error: {:?}", result.error);
+    assert!(started.elapsed().as_secs() < 10, "probe took {:?}", started.elapsed());
+    assert!(result.stderr.contains("fixture: started"), "stderr: {:?}", result.stderr);
+}
+
+#[tokio::test]
+async fn probe_captures_noise_as_warnings_and_stderr() {
+    if !node_available() {
+        return;
+    }
+    let result = probe(Some("--noisy"), 10_000).await;
+    assert!(result.ok, "probe failed: {:?} / {}", result.error, result.stderr);
+    assert!(
+        result.warnings.iter().any(|w| w.contains("non-JSON output") && w.contains("fixture: handling request")),
+        "warnings: {:?}",
+        result.warnings,
+    );
+    assert!(result.stderr.contains("fixture: started"), "stderr: {:?}", result.stderr);
+}
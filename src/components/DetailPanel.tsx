@@ -14,6 +14,8 @@ import {
   renameEntity, 
   duplicateEntity,
   openInFinder,
+  enablePlugin,
+  disablePlugin,
 } from '../lib/api';
 import { useGlobalShortcuts } from '../hooks/useKeyboardNavigation';
 import { Panel } from './ui/Panel';
@@ -129,7 +131,8 @@ export function DetailPanel() {
         content: selectedEntity.content,
         isSymlink: selectedEntity.is_symlink,
         symlinkTarget: selectedEntity.symlink_target,
-        isDeletable: ['agent', 'skill', 'command'].includes(selectedEntity.type),
+        isDeletable: !selectedEntity.read_only && ['agent', 'skill', 'command'].includes(selectedEntity.type),
+        isReadOnly: selectedEntity.read_only,
         isSkill: selectedEntity.type === 'skill',
         skillDir: selectedEntity.type === 'skill' ? (selectedEntity as any).skill_dir : undefined,
         isPlugin: false,
@@ -147,6 +150,7 @@ export function DetailPanel() {
         content: JSON.stringify(selectedEntity, null, 2),
         isSymlink: false,
         isDeletable: false,
+        isReadOnly: selectedEntity.read_only,
         isSkill: false,
         isPlugin: false,
         tool: selectedEntity.tool,
@@ -187,7 +191,7 @@ export function DetailPanel() {
   
   // Save handler
   const handleSave = useCallback(async () => {
-    if (!entityInfo || !hasChanges) return;
+    if (!entityInfo || !hasChanges || entityInfo.isReadOnly) return;
     
     try {
       await writeFile(entityInfo.path, content);
@@ -461,7 +465,7 @@ export function DetailPanel() {
         badgeColor={badgeColor}
         tool={entityInfo.tool}
         hasChanges={hasChanges}
-        onSave={handleSave}
+        onSave={entityInfo.isReadOnly ? undefined : handleSave}
         onDelete={entityInfo.isDeletable ? () => setShowDeleteConfirm(true) : undefined}
         entityType={entityInfo.type}
         entityScope={entityInfo.scope as 'global' | 'project'}
@@ -589,6 +593,7 @@ export function DetailPanel() {
                 onChange={(value) => setContent(value || '')}
                 options={{
                   minimap: { enabled: false },
+                  readOnly: !!entityInfo.isReadOnly,
                   fontSize: 13,
                   fontFamily: '"SF Mono", "Fira Code", "Fira Mono", monospace',
                   lineNumbers: 'on',
@@ -793,6 +798,27 @@ function McpDetailView({ entity }: { entity: DisplayableEntity }) {
               <span className="text-xs text-[var(--color-text-tertiary)]">Scope</span>
               <p className="text-sm text-[var(--color-text-primary)]">{entity.scope}</p>
             </div>
+            {entity.project_path && (
+              <div>
+                <span className="text-xs text-[var(--color-text-tertiary)]">Project</span>
+                <p className="text-sm text-[var(--color-text-secondary)] font-mono">{entity.project_path}</p>
+              </div>
+            )}
+            {entity.approval && (
+              <div>
+                <span className="text-xs text-[var(--color-text-tertiary)]">Status</span>
+                <p className="text-sm text-[var(--color-text-primary)]">
+                  <span className={clsx(
+                    'inline-block px-1.5 py-0.5 rounded text-[10px] uppercase',
+                    entity.approval === 'approved' && 'bg-[var(--color-success-soft)] text-[var(--color-success)]',
+                    entity.approval === 'rejected' && 'bg-[var(--color-error-soft)] text-[var(--color-error)]',
+                    entity.approval === 'pending' && 'bg-[var(--color-warning-soft)] text-[var(--color-warning)]',
+                  )}>
+                    {entity.approval === 'pending' ? 'awaiting approval' : entity.approval}
+                  </span>
+                </p>
+              </div>
+            )}
             {entity.is_from_plugin && (
               <div>
                 <span className="text-xs text-[var(--color-text-tertiary)]">Plugin</span>
@@ -846,9 +872,34 @@ function McpDetailView({ entity }: { entity: DisplayableEntity }) {
 
 // Plugin detail view
 function PluginDetailView({ entity }: { entity: DisplayableEntity }) {
+  const lspServers = useAppStore(state => state.lspServers);
+  const refreshDiscovery = useAppStore(state => state.refreshDiscovery);
+  const addToast = useAppStore(state => state.addToast);
+  const [isToggling, setIsToggling] = useState(false);
   if (!isPluginEntity(entity)) return null;
   
+  // Toggle in the layer matching where the plugin is installed
+  const handleToggleEnabled = async () => {
+    if (!entity.enabled_state) return;
+    const layer = entity.scope === 'project' || entity.scope === 'local' ? entity.scope : 'user';
+    setIsToggling(true);
+    try {
+      const toggle = entity.enabled ? disablePlugin : enablePlugin;
+      await toggle(entity.enabled_state.plugin, layer, entity.project_path ?? undefined);
+      await refreshDiscovery();
+    } catch (err) {
+      addToast({
+        type: 'error',
+        title: 'Plugin Update Failed',
+        message: err instanceof Error ? err.message : String(err),
+      });
+    } finally {
+      setIsToggling(false);
+    }
+  };
+  
   const manifest = entity.manifest;
+  const pluginLspServers = lspServers.filter(l => l.source_path.startsWith(entity.plugin_dir + '/'));
   const components = [
     { name: 'Commands', has: entity.has_commands, icon: '⌘' },
     { name: 'Agents', has: entity.has_agents, icon: '🤖' },
@@ -904,6 +955,28 @@ function PluginDetailView({ entity }: { entity: DisplayableEntity }) {
                 <p className="text-sm text-[var(--color-text-secondary)] font-mono">{entity.project_path}</p>
               </div>
             )}
+            {entity.enabled_state && (
+              <div>
+                <span className="text-xs text-[var(--color-text-tertiary)]">Status</span>
+                <div className="flex items-center gap-2">
+                  <p className="text-sm text-[var(--color-text-primary)]">
+                    {entity.enabled ? 'Enabled' : 'Disabled'}
+                    {entity.enabled_state.layers.length > 0 && (
+                      <span className="text-xs text-[var(--color-text-tertiary)]">
+                        {' '}(set in {entity.enabled_state.layers[entity.enabled_state.layers.length - 1].layer} settings)
+                      </span>
+                    )}
+                  </p>
+                  <button
+                    onClick={handleToggleEnabled}
+                    disabled={isToggling}
+                    className="px-2 py-1 text-xs rounded-md bg-[var(--color-bg-tertiary)] text-[var(--color-text-secondary)] hover:text-[var(--color-text-primary)] disabled:opacity-50"
+                  >
+                    {entity.enabled ? 'Disable' : 'Enable'}
+                  </button>
+                </div>
+              </div>
+            )}
           </div>
         </div>
         
@@ -927,6 +1000,30 @@ function PluginDetailView({ entity }: { entity: DisplayableEntity }) {
           </div>
         )}
         
+        {/* LSP Servers */}
+        {pluginLspServers.length > 0 && (
+          <div className="bg-[var(--color-bg-secondary)] rounded-lg border border-[var(--color-border)] p-4">
+            <h3 className="text-xs font-semibold uppercase tracking-wider text-[var(--color-text-tertiary)] mb-3">
+              LSP Servers ({pluginLspServers.length})
+            </h3>
+            <div className="space-y-2">
+              {pluginLspServers.map((lsp) => (
+                <div key={lsp.id}>
+                  <p className="text-sm text-[var(--color-text-primary)] font-medium">{lsp.name}</p>
+                  <code className="text-xs font-mono text-[var(--color-text-secondary)]">
+                    {lsp.command} {lsp.args.join(' ')}
+                  </code>
+                  {Object.keys(lsp.extension_to_language).length > 0 && (
+                    <p className="text-xs text-[var(--color-text-tertiary)]">
+                      {Object.keys(lsp.extension_to_language).join(', ')}
+                    </p>
+                  )}
+                </div>
+              ))}
+            </div>
+          </div>
+        )}
+        
         {/* Author Info */}
         {manifest?.author && (
           <div className="bg-[var(--color-bg-secondary)] rounded-lg border border-[var(--color-border)] p-4">
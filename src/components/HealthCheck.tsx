@@ -4,8 +4,9 @@
 
 import { useMemo, useState, useCallback } from 'react';
 import { useAppStore } from '../store/appStore';
-import { deleteEntity, renameEntity } from '../lib/api';
-import type { HealthIssue, HealthIssueSeverity, DuplicateGroup, EntityType } from '../lib/types';
+import { deleteEntity, renameEntity, previewFix, applyFixes } from '../lib/api';
+import { useHealthIssues } from '../hooks/useHealthIssues';
+import type { HealthIssue, HealthIssueSeverity, HealthFix, FixPreview, DuplicateGroup, EntityType } from '../lib/types';
 import { RenameDialog } from './ui/RenameDialog';
 import { ConfirmDialog } from './ui/ConfirmDialog';
 import { 
@@ -25,6 +26,7 @@ import {
   Trash2,
   Pencil,
   Check,
+  Wrench,
 } from 'lucide-react';
 import { clsx } from 'clsx';
 
@@ -58,125 +60,12 @@ export function HealthCheck() {
     target: string;
   } | null>(null);
   
-  // Analyze and generate health issues
-  const issues = useMemo(() => {
-    const result: HealthIssue[] = [];
-    
-    // Check for broken symlinks
-    symlinks?.forEach(symlink => {
-      if (!symlink.target_exists) {
-        result.push({
-          id: `broken-symlink-${symlink.path}`,
-          severity: 'error',
-          category: 'Broken Symlinks',
-          title: 'Broken symlink detected',
-          description: `Symlink target does not exist`,
-          path: symlink.path,
-          entityType: symlink.entity_type as any,
-          entityId: symlink.entity_id || undefined,
-          suggestion: `Remove the symlink or restore the target at: ${symlink.target}`,
-        });
-      }
-    });
-    
-    // Check for duplicates
-    duplicates?.forEach(dup => {
-      result.push({
-        id: `duplicate-${dup.entity_type}-${dup.name}`,
-        severity: 'warning',
-        category: 'Duplicates',
-        title: `Duplicate ${dup.entity_type}: ${dup.name}`,
-        description: `Found ${dup.entities.length} definitions with the same name`,
-        entityType: dup.entity_type as any,
-        suggestion: `Only one definition will be active. Consider removing duplicates or renaming.`,
-      });
-    });
-    
-    // Check agents for missing descriptions
-    agents?.forEach(agent => {
-      if (!agent.frontmatter?.description) {
-        result.push({
-          id: `agent-no-desc-${agent.id}`,
-          severity: 'info',
-          category: 'Best Practices',
-          title: `Agent "${agent.name}" has no description`,
-          description: 'Adding a description helps with agent discoverability',
-          path: agent.path,
-          entityType: 'agent',
-          entityId: agent.id,
-          suggestion: 'Add a description field to the YAML frontmatter',
-        });
-      }
-      
-      // Check for bypassPermissions mode
-      if (agent.frontmatter?.permissionMode === 'bypassPermissions') {
-        result.push({
-          id: `agent-bypass-${agent.id}`,
-          severity: 'warning',
-          category: 'Security',
-          title: `Agent "${agent.name}" bypasses permissions`,
-          description: 'This agent runs with elevated permissions which could be risky',
-          path: agent.path,
-          entityType: 'agent',
-          entityId: agent.id,
-          suggestion: 'Consider using "acceptEdits" or "default" permission mode unless bypass is necessary',
-        });
-      }
-    });
-    
-    // Check skills for missing descriptions
-    skills?.forEach(skill => {
-      if (!skill.frontmatter?.description) {
-        result.push({
-          id: `skill-no-desc-${skill.id}`,
-          severity: 'info',
-          category: 'Best Practices',
-          title: `Skill "${skill.name}" has no description`,
-          description: 'Adding a description helps Claude understand when to use this skill',
-          path: skill.path,
-          entityType: 'skill',
-          entityId: skill.id,
-          suggestion: 'Add a description field to the YAML frontmatter',
-        });
-      }
-    });
-    
-    // Check commands for missing descriptions
-    commands?.forEach(cmd => {
-      if (!cmd.frontmatter?.description) {
-        result.push({
-          id: `cmd-no-desc-${cmd.id}`,
-          severity: 'info',
-          category: 'Best Practices',
-          title: `Command "${cmd.name}" has no description`,
-          description: 'Adding a description helps users understand what the command does',
-          path: cmd.path,
-          entityType: 'command',
-          entityId: cmd.id,
-          suggestion: 'Add a description field to the YAML frontmatter',
-        });
-      }
-    });
-    
-    // Check for empty CLAUDE.md files
-    settings?.forEach(setting => {
-      if (!setting.content || setting.content.trim().length < 10) {
-        result.push({
-          id: `empty-settings-${setting.id}`,
-          severity: 'info',
-          category: 'Empty Files',
-          title: `Settings file appears empty`,
-          description: `${setting.path} has minimal or no content`,
-          path: setting.path,
-          entityType: 'settings',
-          entityId: setting.id,
-          suggestion: 'Add configuration or remove the file if not needed',
-        });
-      }
-    });
-    
-    return result;
-  }, [duplicates, symlinks, agents, skills, commands, settings]);
+  const { issues } = useHealthIssues();
+  
+  // State for machine-applicable fixes
+  const [fixPreview, setFixPreview] = useState<{ issueId: string; preview: FixPreview } | null>(null);
+  const [isFixing, setIsFixing] = useState(false);
+  const fixableIssues = useMemo(() => issues.filter(issue => issue.fix), [issues]);
   
   // Group issues by severity
   const groupedIssues = useMemo(() => {
@@ -210,6 +99,7 @@ export function HealthCheck() {
       case 'Best Practices':
         return <Sparkles className="w-4 h-4" />;
       case 'Empty Files':
+      case 'Frontmatter':
         return <FileWarning className="w-4 h-4" />;
       default:
         return <Info className="w-4 h-4" />;
@@ -347,6 +237,48 @@ export function HealthCheck() {
     }
   }, [selectedSymlink, addToast, refreshDiscovery]);
   
+  // Toggle the diff for an issue's fix
+  const handlePreviewFix = useCallback(async (issue: HealthIssue) => {
+    if (!issue.fix) return;
+    if (fixPreview?.issueId === issue.id) {
+      setFixPreview(null);
+      return;
+    }
+    try {
+      const preview = await previewFix(issue.fix);
+      setFixPreview({ issueId: issue.id, preview });
+    } catch (err) {
+      addToast({
+        type: 'error',
+        title: 'Preview Failed',
+        message: err instanceof Error ? err.message : String(err),
+      });
+    }
+  }, [fixPreview, addToast]);
+  
+  // Apply one or more fixes; the backend rolls back if any of them fails
+  const handleApplyFixes = useCallback(async (fixes: HealthFix[]) => {
+    setIsFixing(true);
+    try {
+      const result = await applyFixes(fixes);
+      addToast({
+        type: 'success',
+        title: result.applied === 1 ? 'Fix Applied' : `${result.applied} Fixes Applied`,
+        message: `${result.files.length} file${result.files.length === 1 ? '' : 's'} changed`,
+      });
+      setFixPreview(null);
+      await refreshDiscovery();
+    } catch (err) {
+      addToast({
+        type: 'error',
+        title: 'Fix Failed',
+        message: `No files were changed: ${err instanceof Error ? err.message : String(err)}`,
+      });
+    } finally {
+      setIsFixing(false);
+    }
+  }, [addToast, refreshDiscovery]);
+  
   // Find broken symlink info for an issue
   const getBrokenSymlinkInfo = (issue: HealthIssue) => {
     if (issue.category !== 'Broken Symlinks') return null;
@@ -357,13 +289,12 @@ export function HealthCheck() {
   
   // Find duplicate info for an issue
   const getDuplicateInfo = (issue: HealthIssue) => {
-    if (issue.category !== 'Duplicates') return null;
-    const match = issue.id.match(/^duplicate-(\w+)-(.+)$/);
-    if (!match) return null;
-    return duplicates.find(d => d.entity_type === match[1] && d.name === match[2]);
+    if (!issue.checkId.startsWith('duplicates.')) return null;
+    const entityType = issue.checkId.slice('duplicates.'.length);
+    return duplicates.find(d => d.entity_type === entityType && d.entities[0]?.path === issue.path);
   };
   
-  const formatPath = (path?: string) => {
+  const formatPath = (path?: string | null) => {
     if (!path) return '';
     return path.replace(/^\/Users\/[^/]+/, '~');
   };
@@ -375,6 +306,16 @@ export function HealthCheck() {
         <p className="health-check-subtitle">
           Analyzing your Claude Code configuration for potential issues
         </p>
+        {fixableIssues.length > 0 && (
+          <button
+            className="health-fix-all"
+            onClick={() => handleApplyFixes(fixableIssues.map(issue => issue.fix!))}
+            disabled={isFixing}
+          >
+            <Wrench className="w-3.5 h-3.5" />
+            Fix All ({fixableIssues.length})
+          </button>
+        )}
       </div>
       
       {/* Summary Cards */}
@@ -451,6 +392,10 @@ export function HealthCheck() {
                       getCategoryIcon={getCategoryIcon}
                       getEntityIcon={getEntityIcon}
                       formatPath={formatPath}
+                      fixPreview={fixPreview?.issueId === issue.id ? fixPreview.preview : null}
+                      onPreviewFix={() => handlePreviewFix(issue)}
+                      onApplyFix={() => issue.fix && handleApplyFixes([issue.fix])}
+                      isFixing={isFixing}
                       isBrokenSymlink={!!symlinkInfo}
                       symlinkTarget={symlinkInfo?.target}
                       onDeleteSymlink={(path, target) => {
@@ -482,6 +427,10 @@ export function HealthCheck() {
                       getCategoryIcon={getCategoryIcon}
                       getEntityIcon={getEntityIcon}
                       formatPath={formatPath}
+                      fixPreview={fixPreview?.issueId === issue.id ? fixPreview.preview : null}
+                      onPreviewFix={() => handlePreviewFix(issue)}
+                      onApplyFix={() => issue.fix && handleApplyFixes([issue.fix])}
+                      isFixing={isFixing}
                       duplicateInfo={dupInfo || undefined}
                       isExpanded={expandedDuplicate === issue.id}
                       onToggleExpand={() => setExpandedDuplicate(
@@ -518,6 +467,10 @@ export function HealthCheck() {
                     getCategoryIcon={getCategoryIcon}
                     getEntityIcon={getEntityIcon}
                     formatPath={formatPath}
+                    fixPreview={fixPreview?.issueId === issue.id ? fixPreview.preview : null}
+                    onPreviewFix={() => handlePreviewFix(issue)}
+                    onApplyFix={() => issue.fix && handleApplyFixes([issue.fix])}
+                    isFixing={isFixing}
                   />
                 ))}
               </div>
@@ -590,6 +543,11 @@ interface IssueCardProps {
   getCategoryIcon: (category: string) => React.ReactNode;
   getEntityIcon: (type?: string) => React.ReactNode;
   formatPath: (path?: string) => string;
+  // For machine-applicable fixes
+  fixPreview?: FixPreview | null;
+  onPreviewFix?: () => void;
+  onApplyFix?: () => void;
+  isFixing?: boolean;
   // For duplicate resolution
   duplicateInfo?: DuplicateGroup;
   isExpanded?: boolean;
@@ -608,6 +566,10 @@ function IssueCard({
   getCategoryIcon,
   getEntityIcon,
   formatPath,
+  fixPreview,
+  onPreviewFix,
+  onApplyFix,
+  isFixing,
   duplicateInfo,
   isExpanded,
   onToggleExpand,
@@ -650,6 +612,23 @@ function IssueCard({
           {issue.suggestion && !isDuplicate && !isBrokenSymlink && (
             <p className="health-issue-suggestion">{issue.suggestion}</p>
           )}
+          {/* Fix action */}
+          {issue.fix && !isBrokenSymlink && (
+            <div className="health-fix-actions">
+              <span className="health-fix-description">{issue.fix.description}</span>
+              <button
+                className="health-fix-action"
+                onClick={(e) => {
+                  e.stopPropagation();
+                  onPreviewFix?.();
+                }}
+                title="Show the changes this fix makes"
+              >
+                <Wrench className="w-3.5 h-3.5" />
+                {fixPreview ? 'Hide Fix' : 'Preview Fix'}
+              </button>
+            </div>
+          )}
           {/* Broken symlink action */}
           {isBrokenSymlink && issue.path && (
             <div className="health-symlink-actions">
@@ -680,6 +659,45 @@ function IssueCard({
         ) : null}
       </button>
       
+      {/* Fix diff */}
+      {fixPreview && (
+        <div className="health-fix-preview">
+          {fixPreview.files.map(file => (
+            <div key={file.path} className="health-fix-file">
+              <code className="health-fix-file-path">
+                {formatPath(file.path)}
+                {file.before !== file.after && ` (${file.before} → ${file.after})`}
+              </code>
+              <pre className="health-fix-diff">
+                {file.diff.split('\n').filter(line => line && !line.startsWith('---') && !line.startsWith('+++')).map((line, idx) => (
+                  <span
+                    key={idx}
+                    className={clsx(
+                      line.startsWith('+') && 'added',
+                      line.startsWith('-') && 'removed',
+                      line.startsWith('@@') && 'hunk',
+                    )}
+                  >
+                    {line}
+                    {'\n'}
+                  </span>
+                ))}
+              </pre>
+            </div>
+          ))}
+          <div className="health-fix-preview-actions">
+            <button
+              className="health-fix-action apply"
+              onClick={onApplyFix}
+              disabled={isFixing}
+            >
+              <Check className="w-3.5 h-3.5" />
+              Apply Fix
+            </button>
+          </div>
+        </div>
+      )}
+      
       {/* Duplicate entities list */}
       {isDuplicate && isExpanded && duplicateInfo && (
         <div className="health-duplicate-list">
@@ -707,6 +725,13 @@ function IssueCard({
                     {formatPath(entity.path)}
                   </code>
                 </div>
+                {entity.read_only ? (
+                  <span className="health-duplicate-item-readonly" title={entity.json_pointer
+                    ? `Defined at ${entity.json_pointer} in this config file; edit it there`
+                    : 'Provided by a plugin; changes would be lost on update'}>
+                    Read-only
+                  </span>
+                ) : (
                 <div className="health-duplicate-item-actions">
                   <button
                     className="health-duplicate-action rename"
@@ -741,6 +766,7 @@ function IssueCard({
                     Delete
                   </button>
                 </div>
+                )}
               </div>
             ))}
         </div>
@@ -348,7 +348,11 @@ export function SkillDetailPanel({
   const handleRemove = useCallback(async () => {
     setIsRemoving(true);
     try {
-      const result = await removeSkill(skill.name, installScope === 'global');
+      const result = await removeSkill(
+        skill.name,
+        installScope === 'global',
+        installScope === 'project' ? selectedProject ?? undefined : undefined,
+      );
       if (result.success) {
         onRemoveComplete();
       }
@@ -358,7 +362,7 @@ export function SkillDetailPanel({
       setIsRemoving(false);
       setShowRemoveConfirm(false);
     }
-  }, [skill.name, installScope, onRemoveComplete]);
+  }, [skill.name, installScope, selectedProject, onRemoveComplete]);
 
   const handleUpdate = useCallback(async () => {
     setIsUpdating(true);
@@ -18,8 +18,34 @@ import type {
   ProjectInfo,
   DuplicateGroup,
   SymlinkInfo,
+  FrontmatterFinding,
+  HealthReport,
+  HealthCheckInfo,
+  HealthFix,
+  FixPreview,
+  ApplyFixesResult,
   EntityType,
   ConfigState,
+  PermissionFinding,
+  PermissionFix,
+  HookDefinition,
+  HookMatcher,
+  HookValidationError,
+  HookTestReport,
+  PluginEnabledState,
+  MarketplaceInfo,
+  PluginInstallation,
+  PluginValidationReport,
+  MarketplaceEntry,
+  McpProbeResult,
+  McpWriteResult,
+  McpImportSource,
+  McpImportCandidate,
+  McpImportPreview,
+  SecretFinding,
+  SecretFixResult,
+  McpServerConfig,
+  ToolType,
 } from './types';
 
 // ============================================================================
@@ -76,10 +102,281 @@ export async function extractHooks(settingsPath: string): Promise<HookEntity[]>
   return invoke('extract_hooks', { settingsPath });
 }
 
+// ============================================================================
+// Hook Editing API
+// Indices refer to positions in the settings file's hooks.<event> array
+// ============================================================================
+
+export async function validateHook(event: string, matcher: HookMatcher): Promise<HookValidationError[]> {
+  return invoke('validate_hook', { event, matcher });
+}
+
+export async function addHookMatcher(settingsPath: string, event: string, matcher: HookMatcher): Promise<void> {
+  return invoke('add_hook_matcher', { settingsPath, event, matcher });
+}
+
+export async function updateHookMatcher(
+  settingsPath: string,
+  event: string,
+  index: number,
+  matcher: HookMatcher
+): Promise<void> {
+  return invoke('update_hook_matcher', { settingsPath, event, index, matcher });
+}
+
+export async function removeHookMatcher(settingsPath: string, event: string, index: number): Promise<void> {
+  return invoke('remove_hook_matcher', { settingsPath, event, index });
+}
+
+export async function moveHookMatcher(settingsPath: string, event: string, from: number, to: number): Promise<void> {
+  return invoke('move_hook_matcher', { settingsPath, event, from, to });
+}
+
+export async function addHookDefinition(
+  settingsPath: string,
+  event: string,
+  matcherIndex: number,
+  hook: HookDefinition
+): Promise<void> {
+  return invoke('add_hook_definition', { settingsPath, event, matcherIndex, hook });
+}
+
+export async function updateHookDefinition(
+  settingsPath: string,
+  event: string,
+  matcherIndex: number,
+  hookIndex: number,
+  hook: HookDefinition
+): Promise<void> {
+  return invoke('update_hook_definition', { settingsPath, event, matcherIndex, hookIndex, hook });
+}
+
+export async function removeHookDefinition(
+  settingsPath: string,
+  event: string,
+  matcherIndex: number,
+  hookIndex: number
+): Promise<void> {
+  return invoke('remove_hook_definition', { settingsPath, event, matcherIndex, hookIndex });
+}
+
+/**
+ * Build the sample stdin payload a hook receives for an event and matcher
+ */
+export async function buildHookPayload(
+  event: string,
+  matcher?: string,
+  projectPath?: string
+): Promise<Record<string, unknown>> {
+  return invoke('build_hook_payload', { event, matcher, projectPath });
+}
+
+/**
+ * Run a hook's commands against a payload and report how Claude Code would react.
+ * Fails when no OS sandbox is available unless allowUnsandboxed is set.
+ */
+export async function testHook(
+  hook: HookEntity,
+  payload?: Record<string, unknown>,
+  projectPath?: string,
+  allowUnsandboxed?: boolean
+): Promise<HookTestReport> {
+  return invoke('test_hook', { hook, payload, projectPath, allowUnsandboxed });
+}
+
+// ============================================================================
+// Plugin Management API
+// Plugins are identified as name@marketplace
+// ============================================================================
+
+export type PluginSettingsLayer = 'user' | 'project' | 'local';
+
+export async function getPluginEnabledState(plugin: string, projectPath?: string): Promise<PluginEnabledState> {
+  return invoke('get_plugin_enabled_state', { plugin, projectPath });
+}
+
+export async function enablePlugin(
+  plugin: string,
+  layer: PluginSettingsLayer,
+  projectPath?: string
+): Promise<PluginEnabledState> {
+  return invoke('enable_plugin', { plugin, layer, projectPath });
+}
+
+export async function disablePlugin(
+  plugin: string,
+  layer: PluginSettingsLayer,
+  projectPath?: string
+): Promise<PluginEnabledState> {
+  return invoke('disable_plugin', { plugin, layer, projectPath });
+}
+
+export async function listMarketplaces(): Promise<MarketplaceInfo[]> {
+  return invoke('list_marketplaces');
+}
+
+/**
+ * Install a plugin whose marketplace source is a local directory or git checkout
+ */
+export async function installMarketplacePlugin(
+  marketplace: string,
+  plugin: string,
+  scope: PluginSettingsLayer,
+  projectPath?: string
+): Promise<PluginInstallation> {
+  return invoke('install_marketplace_plugin', { marketplace, plugin, scope, projectPath });
+}
+
+export async function uninstallMarketplacePlugin(
+  plugin: string,
+  scope: PluginSettingsLayer,
+  projectPath?: string
+): Promise<void> {
+  return invoke('uninstall_marketplace_plugin', { plugin, scope, projectPath });
+}
+
+/**
+ * Scaffold a plugin at parentDir/name. Agents, commands and skills listed in
+ * entityPaths and the given settings hooks are moved into the new plugin.
+ * Returns the plugin directory.
+ */
+export async function createPlugin(
+  parentDir: string,
+  name: string,
+  description: string,
+  options: { author?: string; entityPaths?: string[]; hooks?: HookEntity[] } = {}
+): Promise<string> {
+  return invoke('create_plugin', { parentDir, name, description, ...options });
+}
+
+export async function validatePlugin(pluginDir: string): Promise<PluginValidationReport> {
+  return invoke('validate_plugin', { pluginDir });
+}
+
+/** Source is relative to marketplaceDir when given, otherwise absolute */
+export async function generateMarketplaceEntry(pluginDir: string, marketplaceDir?: string): Promise<MarketplaceEntry> {
+  return invoke('generate_marketplace_entry', { pluginDir, marketplaceDir });
+}
+
+// ============================================================================
+// MCP Server API
+// ============================================================================
+
+/**
+ * Launch (stdio) or connect to (http/sse) an MCP server, run the initialize
+ * handshake and list its tools, prompts and resources. Startup failures come
+ * back in the result with the failed stage and captured stderr.
+ */
+export async function probeMcpServer(
+  server: McpServerEntity,
+  projectPath?: string,
+  timeoutMs?: number
+): Promise<McpProbeResult> {
+  return invoke('probe_mcp_server', { server, projectPath, timeoutMs });
+}
+
+/** Scopes an MCP server can be written to: Claude Code uses user/project/local, OpenCode global/project */
+export type McpScope = 'user' | 'project' | 'local' | 'global';
+
+/**
+ * Copy an MCP server into another tool and/or scope, converting between the
+ * Claude Code (.mcp.json, ~/.claude.json) and OpenCode (opencode.json) schemas.
+ * Rejects if the target already has a server with that name unless overwrite is set.
+ */
+export async function copyMcpServer(
+  server: McpServerEntity,
+  targetTool: ToolType,
+  targetScope: McpScope,
+  options: { projectPath?: string; newName?: string; overwrite?: boolean } = {}
+): Promise<McpWriteResult> {
+  return invoke('copy_mcp_server', { server, targetTool, targetScope, ...options });
+}
+
+/** Add a server to a tool's config for the given scope; rejects if the name is taken */
+export async function addMcpServer(
+  tool: ToolType,
+  scope: McpScope,
+  name: string,
+  config: McpServerConfig,
+  projectPath?: string
+): Promise<McpWriteResult> {
+  return invoke('add_mcp_server', { tool, scope, projectPath, name, config });
+}
+
+/**
+ * Replace a discovered server's config, optionally renaming it. Only that entry
+ * is rewritten; rejects if it changed on disk since discovery.
+ */
+export async function updateMcpServer(
+  server: McpServerEntity,
+  config: McpServerConfig,
+  newName?: string
+): Promise<McpWriteResult> {
+  return invoke('update_mcp_server', { server, config, newName });
+}
+
+/** Remove a discovered server; rejects if it changed on disk since discovery */
+export async function removeMcpServer(server: McpServerEntity): Promise<void> {
+  return invoke('remove_mcp_server', { server });
+}
+
+/**
+ * Read MCP servers from Claude Desktop, VS Code and Cursor configs and preview
+ * importing them into a tool/scope, with name conflicts marked
+ */
+export async function previewMcpImport(
+  targetTool: ToolType,
+  targetScope: McpScope,
+  projectPath?: string,
+  sources?: McpImportSource[]
+): Promise<McpImportPreview> {
+  return invoke('preview_mcp_import', { targetTool, targetScope, projectPath, sources });
+}
+
+/**
+ * Import the selected candidates in a single write; rejects without writing
+ * anything if a name is taken in the target (unless overwrite is set)
+ */
+export async function applyMcpImport(
+  candidates: McpImportCandidate[],
+  targetTool: ToolType,
+  targetScope: McpScope,
+  options: { projectPath?: string; overwrite?: boolean } = {}
+): Promise<McpWriteResult[]> {
+  return invoke('apply_mcp_import', { candidates, targetTool, targetScope, ...options });
+}
+
+/** Scan MCP servers and settings for literal secrets in git-tracked (or trackable) files */
+export async function scanSecrets(
+  servers: McpServerEntity[],
+  settings: SettingsEntity[]
+): Promise<SecretFinding[]> {
+  return invoke('scan_secrets', { servers, settings });
+}
+
+/**
+ * Move a detected secret into .claude/settings.local.json (Claude Code) or .env
+ * (OpenCode), replacing it with a ${VAR} / {env:VAR} reference. The receiving
+ * file is added to .gitignore if needed.
+ */
+export async function extractSecret(finding: SecretFinding, varName?: string): Promise<SecretFixResult> {
+  return invoke('extract_secret', { finding, varName });
+}
+
 // ============================================================================
 // Analysis API
 // ============================================================================
 
+/** Lint agent, skill and command frontmatter; `tools` are checked against the given MCP servers */
+export async function lintFrontmatter(
+  agents: AgentEntity[],
+  skills: SkillEntity[],
+  commands: CommandEntity[],
+  mcpServers: McpServerEntity[]
+): Promise<FrontmatterFinding[]> {
+  return invoke('lint_frontmatter', { agents, skills, commands, mcpServers });
+}
+
 export async function findDuplicates(): Promise<DuplicateGroup[]> {
   return invoke('find_duplicates');
 }
@@ -88,6 +385,32 @@ export async function checkSymlink(path: string): Promise<SymlinkInfo | null> {
   return invoke('check_symlink', { path });
 }
 
+/**
+ * Run every registered health check. Checks listed under `health.suppress`
+ * in a project's .agent-studio.json are returned in `suppressed`.
+ */
+export async function runHealthChecks(projectPaths?: string[]): Promise<HealthReport> {
+  return invoke('run_health_checks', { projectPaths });
+}
+
+export async function listHealthChecks(): Promise<HealthCheckInfo[]> {
+  return invoke('list_health_checks');
+}
+
+/**
+ * Show the exact diff a fix would make, without writing anything
+ */
+export async function previewFix(fix: HealthFix): Promise<FixPreview> {
+  return invoke('preview_fix', { fix });
+}
+
+/**
+ * Apply fixes as one transaction; if any step fails, nothing is changed
+ */
+export async function applyFixes(fixes: HealthFix[]): Promise<ApplyFixesResult> {
+  return invoke('apply_fixes', { fixes });
+}
+
 // ============================================================================
 // Config State API (AGENTS.md / CLAUDE.md consistency)
 // ============================================================================
@@ -110,6 +433,80 @@ export async function fixProjectConfig(projectPath: string): Promise<string> {
   return invoke('fix_project_config', { projectPath });
 }
 
+// ============================================================================
+// Permissions Audit API
+// ============================================================================
+
+/**
+ * Audit permission rules in global settings and, optionally, a project's
+ * settings.json / settings.local.json
+ */
+export async function auditPermissions(projectPath?: string): Promise<PermissionFinding[]> {
+  return invoke('audit_permissions', { projectPath });
+}
+
+/**
+ * Apply the suggested fix of a permission finding
+ */
+export async function applyPermissionFix(fix: PermissionFix): Promise<void> {
+  return invoke('apply_permission_fix', { fix });
+}
+
+// ============================================================================
+// Structured Settings API
+// Surgical edits that preserve key order, indentation and unknown keys
+// ============================================================================
+
+export type SettingsLayer = 'global' | 'project' | 'local';
+export type PermissionList = 'allow' | 'deny' | 'ask';
+
+export async function addPermissionRule(
+  layer: SettingsLayer,
+  list: PermissionList,
+  rule: string,
+  projectPath?: string
+): Promise<void> {
+  return invoke('add_permission_rule', { layer, projectPath, list, rule });
+}
+
+export async function removePermissionRule(
+  layer: SettingsLayer,
+  list: PermissionList,
+  rule: string,
+  projectPath?: string
+): Promise<void> {
+  return invoke('remove_permission_rule', { layer, projectPath, list, rule });
+}
+
+export async function setSettingsEnv(
+  layer: SettingsLayer,
+  name: string,
+  value: string,
+  projectPath?: string
+): Promise<void> {
+  return invoke('set_settings_env', { layer, projectPath, name, value });
+}
+
+export async function unsetSettingsEnv(layer: SettingsLayer, name: string, projectPath?: string): Promise<void> {
+  return invoke('unset_settings_env', { layer, projectPath, name });
+}
+
+/**
+ * Set a top-level settings key such as `model`
+ */
+export async function setSettingsKey(
+  layer: SettingsLayer,
+  key: string,
+  value: unknown,
+  projectPath?: string
+): Promise<void> {
+  return invoke('set_settings_key', { layer, projectPath, key, value });
+}
+
+export async function unsetSettingsKey(layer: SettingsLayer, key: string, projectPath?: string): Promise<void> {
+  return invoke('unset_settings_key', { layer, projectPath, key });
+}
+
 // ============================================================================
 // File Operations
 // ============================================================================
@@ -88,8 +88,8 @@ export async function installSkill(request: InstallRequest): Promise<InstallResu
 /**
  * Remove a skill using npx skills CLI
  */
-export async function removeSkill(skillName: string, global: boolean): Promise<InstallResult> {
-  return invoke('remove_skill', { skillName, global });
+export async function removeSkill(skillName: string, global: boolean, projectPath?: string): Promise<InstallResult> {
+  return invoke('remove_skill', { skillName, global, projectPath });
 }
 
 /**
@@ -46,6 +46,9 @@ export interface BaseEntityFields {
   content: string | null;
   last_modified: number;
   tool: ToolType;  // Which tool this entity belongs to
+  is_from_plugin: boolean;
+  plugin_name: string | null;
+  read_only: boolean;  // Plugin-provided entities are overwritten on plugin update; inline opencode.json entries are edited in the config
 }
 
 /**
@@ -191,7 +194,7 @@ export interface OpenCodeCommandConfig {
 
 export interface MemoryEntity extends BaseEntityFields {
   type: 'memory';
-  variant: 'root' | 'dotclaude' | 'dotopencode' | 'global';
+  variant: 'root' | 'dotclaude' | 'dotopencode' | 'global' | 'instructions';  // instructions: listed in opencode.json
 }
 
 // ============================================================================
@@ -201,6 +204,7 @@ export interface MemoryEntity extends BaseEntityFields {
 export interface AgentEntity extends BaseEntityFields {
   type: 'agent';
   frontmatter: AgentFrontmatter | null;
+  json_pointer: string | null;  // Set for agents defined inline in opencode.json (`path` is the config file)
 }
 
 export interface AgentFrontmatter {
@@ -249,6 +253,7 @@ export interface CommandEntity extends BaseEntityFields {
   type: 'command';
   namespace: string | null;
   frontmatter: CommandFrontmatter | null;
+  json_pointer: string | null;  // Set for commands defined inline in opencode.json (`path` is the config file)
 }
 
 export interface CommandFrontmatter {
@@ -273,9 +278,12 @@ export interface HookEntity {
   event: HookEventType;
   matcher: string | null;
   hooks: HookDefinition[];
-  source: 'global' | 'project' | 'local';
+  source: 'global' | 'project' | 'local' | 'plugin';
   source_path: string;
   tool: ToolType;  // Which tool this entity belongs to
+  is_from_plugin: boolean;
+  plugin_name: string | null;
+  read_only: boolean;
 }
 
 export type HookEventType = 
@@ -306,6 +314,155 @@ export interface HookMatcher {
 
 export type HooksConfig = Partial<Record<HookEventType, HookMatcher[]>>;
 
+/** Validation error for a hook block, e.g. path "hooks.PreToolUse[0].hooks[1].timeout" */
+export interface HookValidationError {
+  path: string;
+  message: string;
+}
+
+export type HookDecision =
+  | 'allow'
+  | 'block'
+  | 'ask'
+  | 'inject_context'
+  | 'stop_session'
+  | 'non_blocking_error'
+  | 'skipped';
+
+export interface HookTestResult {
+  hook_index: number;
+  hook_type: string;
+  command?: string;
+  exit_code?: number;
+  stdout: string;
+  stderr: string;
+  timed_out: boolean;
+  duration_ms: number;
+  decision: HookDecision;
+  reason?: string;
+  additional_context?: string;
+  system_message?: string;
+}
+
+/** How a hook under test was isolated; unsandboxed means it ran with full file and network access */
+export type HookSandbox = 'bwrap' | 'sandbox_exec' | 'unsandboxed';
+
+export interface HookTestReport {
+  event: string;
+  payload: Record<string, unknown>;
+  results: HookTestResult[];
+  sandbox: HookSandbox;
+}
+
+export type HookFindingKind =
+  | 'remote_code_execution'
+  | 'recursive_delete'
+  | 'write_outside_project'
+  | 'missing_script'
+  | 'script_not_executable'
+  | 'command_not_found';
+
+/** Result of statically auditing a hook command (settings or plugin hooks.json) */
+export interface HookFinding {
+  id: string;
+  kind: HookFindingKind;
+  severity: HealthIssueSeverity;
+  title: string;
+  description: string;
+  file: string;
+  event: string;
+  matcher?: string;
+  hook_id: string;
+  hook_index: number;
+  command: string;
+  source: 'global' | 'project' | 'local' | 'plugin';
+  plugin_name?: string;
+}
+
+export type McpFindingKind =
+  | 'command_not_found'
+  | 'command_not_executable'
+  | 'unset_env_var'
+  | 'unknown_transport'
+  | 'incomplete_config';
+
+/** Result of checking that an MCP server's command and env placeholders resolve */
+export interface McpFinding {
+  id: string;
+  kind: McpFindingKind;
+  severity: HealthIssueSeverity;
+  title: string;
+  description: string;
+  file: string;
+  server_id: string;
+  server_name: string;
+  scope: string;
+  tool: ToolType;
+  field?: string;  // e.g. "args[2]", "env.GITHUB_TOKEN"
+  plugin_name?: string;
+}
+
+/** A literal credential in MCP env / headers or settings env, in a file git tracks or would pick up */
+export interface SecretFinding {
+  id: string;
+  kind: 'known_token' | 'high_entropy';
+  severity: HealthIssueSeverity;
+  title: string;
+  description: string;
+  file: string;
+  pointer: string;  // JSON pointer of the value in file
+  field: string;  // e.g. "env.GITHUB_TOKEN", "headers.Authorization"
+  source: 'mcp' | 'settings';
+  entity_id: string;
+  server_name?: string;
+  tool: ToolType;
+  scope: string;
+  project_path?: string;
+  token_type: string;
+  preview: string;  // Masked, e.g. "ghp_…9f"
+  git_status: 'tracked' | 'untracked';
+  suggested_var: string;
+  fixable: boolean;
+}
+
+/** Result of moving a secret into an untracked local file */
+export interface SecretFixResult {
+  variable: string;
+  source_file: string;
+  secret_file: string;  // .claude/settings.local.json or .env
+  reference?: string;  // Value left in the source file; absent when a settings env key was moved
+  gitignore_updated?: string;
+  warnings: string[];
+}
+
+export type FrontmatterFindingKind =
+  | 'yaml_error'
+  | 'unclosed_frontmatter'
+  | 'not_a_mapping'
+  | 'missing_key'
+  | 'name_mismatch'
+  | 'unknown_key'
+  | 'unknown_tool'
+  | 'unknown_model';
+
+/** A problem in the YAML frontmatter of an agent, skill or command file */
+export interface FrontmatterFinding {
+  id: string;
+  kind: FrontmatterFindingKind;
+  severity: HealthIssueSeverity;
+  title: string;
+  description: string;
+  file: string;
+  entity_type: 'agent' | 'skill' | 'command';
+  entity_id: string;
+  entity_name: string;
+  tool: ToolType;
+  key: string | null;
+  line: number | null;  // 1-based, in the file
+  column: number | null;
+  plugin_name: string | null;
+}
+
 // ============================================================================
 // Plugin Entity
 // ============================================================================
@@ -320,6 +477,87 @@ export interface PluginEntity extends BaseEntityFields {
   has_hooks: boolean;
   has_mcp: boolean;
   has_lsp: boolean;
+  marketplace: string | null;
+  enabled: boolean;  // Effective state across settings layers
+  enabled_state: PluginEnabledState | null;  // null for plugins outside a marketplace
+  load_error: string | null;  // Its agents, commands, skills or hooks couldn't be read
+}
+
+/** An explicit enabledPlugins entry in one settings layer */
+export interface PluginEnablement {
+  layer: 'user' | 'project' | 'local';
+  settings_path: string;
+  enabled: boolean;
+}
+
+export interface PluginEnabledState {
+  plugin: string;  // "name@marketplace"
+  enabled: boolean;
+  layers: PluginEnablement[];
+}
+
+/** A plugin offered by a marketplace (.claude-plugin/marketplace.json) */
+export interface MarketplacePlugin {
+  name: string;
+  full_name: string;  // "name@marketplace"
+  description: string | null;
+  version: string | null;
+  category: string | null;
+  author: string | null;
+  source: string | Record<string, unknown>;
+  source_kind: 'local' | 'github' | 'git' | 'url' | string;
+  installable: boolean;  // Local sources only; remote ones go through the Claude CLI
+  installed_scopes: string[];
+}
+
+/** A marketplace from ~/.claude/plugins/known_marketplaces.json */
+export interface MarketplaceInfo {
+  name: string;
+  source: Record<string, unknown> | null;
+  install_location: string;
+  last_updated: string | null;
+  description: string | null;
+  owner: string | null;
+  plugins: MarketplacePlugin[];
+  error: string | null;
+}
+
+/** An entry of installed_plugins.json (camelCase, as written by the CLI) */
+export interface PluginInstallation {
+  scope: 'user' | 'project' | 'local';
+  projectPath?: string;
+  installPath: string;
+  version: string;
+  installedAt: string;
+  lastUpdated: string;
+  gitCommitSha?: string;
+  isLocal: boolean;
+}
+
+export interface PluginValidationIssue {
+  severity: HealthIssueSeverity;
+  path: string;
+  message: string;
+}
+
+export interface PluginValidationReport {
+  plugin_dir: string;
+  name?: string;
+  valid: boolean;
+  issues: PluginValidationIssue[];
+}
+
+/** A plugins[] entry for .claude-plugin/marketplace.json */
+export interface MarketplaceEntry {
+  name: string;
+  source: string;
+  description?: string;
+  version?: string;
+  author?: string | { name: string; email?: string; url?: string };
+  homepage?: string;
+  repository?: string;
+  license?: string;
+  keywords?: string[];
 }
 
 export interface PluginManifest {
@@ -354,6 +592,113 @@ export interface McpServerEntity {
   is_from_plugin: boolean;
   plugin_name: string | null;
   tool: ToolType;  // Which tool this entity belongs to
+  project_path?: string | null;  // Project for project- and local-scope servers
+  approval?: 'approved' | 'rejected' | 'pending' | null;  // .mcp.json servers only
+}
+
+/** Where an MCP probe stopped */
+export type McpProbeStage = 'connect' | 'initialize' | 'list_tools' | 'list_prompts' | 'list_resources';
+
+export interface McpToolInfo {
+  name: string;
+  title?: string;
+  description?: string;
+  input_schema?: Record<string, unknown>;
+}
+
+export interface McpPromptInfo {
+  name: string;
+  description?: string;
+  arguments: { name: string; description?: string; required: boolean }[];
+}
+
+export interface McpResourceInfo {
+  uri: string;
+  name?: string;
+  description?: string;
+  mime_type?: string;
+}
+
+/** Result of launching (or connecting to) an MCP server and listing its capabilities */
+export interface McpProbeResult {
+  server_name: string;
+  transport: 'stdio' | 'http' | 'sse';
+  ok: boolean;
+  failed_stage?: McpProbeStage;
+  error?: string;
+  protocol_version?: string;
+  server_info?: { name: string; version: string; title?: string };
+  capabilities?: Record<string, unknown>;
+  instructions?: string;
+  tools: McpToolInfo[];
+  prompts: McpPromptInfo[];
+  resources: McpResourceInfo[];
+  stderr: string;
+  exit_code?: number;
+  unresolved_vars: string[];
+  warnings: string[];
+  initialize_ms?: number;
+  duration_ms: number;
+}
+
+/** Result of writing an MCP server entry into a Claude Code or OpenCode config */
+export interface McpWriteResult {
+  name: string;
+  tool: ToolType;
+  scope: string;
+  file: string;
+  config: Record<string, unknown>;  // The entry as written, in the target tool's schema
+  warnings: string[];
+}
+
+/** App whose MCP config can be imported */
+export type McpImportSource = 'claude_desktop' | 'vscode' | 'cursor';
+
+/** An existing server with the same name as an import candidate */
+export interface McpImportConflict {
+  server_id: string;
+  tool: ToolType;
+  scope: string;
+  source_path: string;
+  same_config: boolean;  // Already runs the same command / url
+}
+
+/** A server found in another app's config, mapped onto McpServerConfig */
+export interface McpImportCandidate {
+  source: McpImportSource;
+  source_path: string;
+  name: string;  // Name to import under; may be edited before applying
+  original_name: string;
+  transport: string;
+  config: McpServerConfig;
+  exists_in_target: boolean;
+  conflicts: McpImportConflict[];
+  warnings: string[];
+}
+
+/** What an import into one tool/scope would add */
+export interface McpImportPreview {
+  target_tool: ToolType;
+  target_scope: string;
+  target_file: string;
+  candidates: McpImportCandidate[];
+  scanned_files: string[];
+  errors: string[];
+}
+
+/** Language server shipped by a plugin (.lsp.json or plugin.json lspServers) */
+export interface LspServerEntity {
+  id: string;
+  type: 'lsp';
+  name: string;
+  scope: string;
+  command: string | null;
+  args: string[];
+  extension_to_language: Record<string, string>;
+  config: Record<string, unknown>;
+  source_path: string;
+  plugin_name: string;
+  tool: ToolType;
 }
 
 export interface McpServerConfig {
@@ -384,6 +729,8 @@ export interface DuplicateEntity {
   scope: string;
   project_path: string | null;
   precedence: number;
+  read_only: boolean;
+  json_pointer: string | null;
 }
 
 export interface SymlinkInfo {
@@ -400,16 +747,102 @@ export interface SymlinkInfo {
 
 export type HealthIssueSeverity = 'error' | 'warning' | 'info';
 
+/** One step of a fix plan; paths are absolute */
+export type FixOp =
+  | { op: 'create_symlink'; path: string; target: string }
+  | { op: 'delete_link'; path: string }
+  | { op: 'rename'; from: string; to: string }
+  | { op: 'json_patch'; file: string; ops: JsonPatchOp[] }
+  | { op: 'append_line'; path: string; line: string }
+  | { op: 'set_frontmatter_key'; path: string; key: string; value: unknown | null }  // null value removes the key
+  | { op: 'extract_secret'; finding: SecretFinding; var_name: string | null };
+
+/** A fix the backend can apply; preview it with previewFix before applying */
+export interface HealthFix {
+  description: string;
+  ops: FixOp[];
+}
+
+export interface FileDiff {
+  path: string;
+  before: 'file' | 'symlink' | 'missing';
+  after: 'file' | 'symlink' | 'missing';
+  diff: string;  // Unified diff; a symlink reads as a one-line file holding its target
+}
+
+export interface FixPreview {
+  description: string;
+  files: FileDiff[];
+}
+
+export interface ApplyFixesResult {
+  applied: number;
+  files: string[];
+}
+
 export interface HealthIssue {
   id: string;
+  checkId: string;  // Registry id plus kind, e.g. "hooks.recursive_delete"
   severity: HealthIssueSeverity;
   category: string;
   title: string;
   description: string;
-  path?: string;
-  entityType?: EntityType;
-  entityId?: string;
-  suggestion?: string;
+  path?: string | null;
+  entityType?: EntityType | null;
+  entityId?: string | null;
+  projectPath?: string | null;
+  suggestion?: string | null;
+  fix?: HealthFix | null;
+}
+
+/** Result of run_health_checks; issues suppressed by .agent-studio.json are kept separately */
+export interface HealthReport {
+  issues: HealthIssue[];
+  suppressed: HealthIssue[];
+  configErrors: string[];
+}
+
+export interface HealthCheckInfo {
+  id: string;
+  category: string;
+  description: string;
+}
+
+// ============================================================================
+// Permissions Audit Types
+// ============================================================================
+
+/** RFC 6902 style operation against a settings file */
+export type JsonPatchOp =
+  | { op: 'test'; path: string; value: unknown }
+  | { op: 'add'; path: string; value: unknown }
+  | { op: 'remove'; path: string }
+  | { op: 'replace'; path: string; value: unknown };
+
+export interface PermissionFix {
+  description: string;
+  file: string;
+  ops: JsonPatchOp[];
+}
+
+export type PermissionFindingKind =
+  | 'over_broad_allow'
+  | 'bypass_mode'
+  | 'shadowed_allow'
+  | 'unreachable_rule'
+  | 'duplicate_rule';
+
+export interface PermissionFinding {
+  id: string;
+  kind: PermissionFindingKind;
+  severity: HealthIssueSeverity;
+  title: string;
+  description: string;
+  file: string;
+  layer: 'global' | 'project' | 'local';
+  pointer: string;  // JSON pointer into the settings file
+  rule: string | null;
+  fix: PermissionFix | null;
 }
 
 // ============================================================================
@@ -448,6 +881,16 @@ export interface ProjectInfo {
   has_opencode_json: boolean;  // OpenCode: opencode.json config
   entity_counts: EntityCounts;
   config_state: ConfigState | null;
+  local_state?: ProjectLocalState | null;
+}
+
+/** Per-project state from projects["/abs/path"] in ~/.claude.json */
+export interface ProjectLocalState {
+  mcp_servers: string[];  // Local-scope server names
+  enabled_mcpjson_servers: string[];
+  disabled_mcpjson_servers: string[];
+  enable_all_project_mcp_servers: boolean;
+  allowed_tools: string[];
 }
 
 export interface EntityCounts {
@@ -476,8 +919,14 @@ export interface DiscoveryResult {
   hooks: HookEntity[];
   plugins: PluginEntity[];
   mcp_servers: McpServerEntity[];
+  lsp_servers: LspServerEntity[];
   duplicates: DuplicateGroup[];
   symlinks: SymlinkInfo[];
+  hook_findings: HookFinding[];
+  mcp_findings: McpFinding[];
+  secret_findings: SecretFinding[];
+  frontmatter_findings: FrontmatterFinding[];
+  health: HealthReport;
   discovered_at: number;
 }
 
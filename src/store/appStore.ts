@@ -17,6 +17,12 @@ import type {
   ProjectInfo,
   DuplicateGroup,
   SymlinkInfo,
+  LspServerEntity,
+  HookFinding,
+  McpFinding,
+  SecretFinding,
+  FrontmatterFinding,
+  HealthReport,
   Toast,
   ViewType,
   FilterScope,
@@ -54,10 +60,16 @@ interface AppState {
   hooks: HookEntity[];
   plugins: PluginEntity[];
   mcpServers: McpServerEntity[];
+  lspServers: LspServerEntity[];
   
   // === Analysis ===
   duplicates: DuplicateGroup[];
   symlinks: SymlinkInfo[];
+  hookFindings: HookFinding[];
+  mcpFindings: McpFinding[];
+  secretFindings: SecretFinding[];
+  frontmatterFindings: FrontmatterFinding[];
+  health: HealthReport;
   
   // === UI State ===
   activeView: ViewType;
@@ -263,7 +275,10 @@ function buildSections(state: AppState): CommandPaletteSection[] {
     const items: CommandPaletteItem[] = filteredMemory.map(m => ({
       id: m.id,
       name: m.name,
-      description: m.variant === 'root' ? 'Root Memory' : m.variant === 'dotopencode' ? '.opencode Memory' : '.claude Memory',
+      description: m.variant === 'root' ? 'Root Memory'
+        : m.variant === 'dotopencode' ? '.opencode Memory'
+        : m.variant === 'instructions' ? 'opencode.json Instructions'
+        : '.claude Memory',
       scope: m.scope as 'global' | 'project',
       projectName: m.project_path?.split('/').pop(),
       entityType: 'memory' as EntityType,
@@ -443,9 +458,15 @@ export const useAppStore = create<AppState>((set, get) => ({
   hooks: [],
   plugins: [],
   mcpServers: [],
+  lspServers: [],
   
   duplicates: [],
   symlinks: [],
+  hookFindings: [],
+  mcpFindings: [],
+  secretFindings: [],
+  frontmatterFindings: [],
+  health: { issues: [], suppressed: [], configErrors: [] },
   
   activeView: 'dashboard',
   selectedEntity: null,
@@ -570,8 +591,14 @@ export const useAppStore = create<AppState>((set, get) => ({
         hooks: result.hooks,
         plugins: result.plugins,
         mcpServers: result.mcp_servers,
+        lspServers: result.lsp_servers,
         duplicates: result.duplicates,
         symlinks: result.symlinks,
+        hookFindings: result.hook_findings,
+        mcpFindings: result.mcp_findings,
+        secretFindings: result.secret_findings,
+        frontmatterFindings: result.frontmatter_findings,
+        health: result.health,
         previousItemIds: newPreviousIds,
         _cachedSections: null, // Invalidate cache
       });
@@ -876,6 +903,11 @@ export const selectFilterTool = (state: AppState) => state.filterTool;
 export const selectToasts = (state: AppState) => state.toasts;
 export const selectDuplicates = (state: AppState) => state.duplicates;
 export const selectSymlinks = (state: AppState) => state.symlinks;
+export const selectHookFindings = (state: AppState) => state.hookFindings;
+export const selectMcpFindings = (state: AppState) => state.mcpFindings;
+export const selectSecretFindings = (state: AppState) => state.secretFindings;
+export const selectFrontmatterFindings = (state: AppState) => state.frontmatterFindings;
+export const selectHealth = (state: AppState) => state.health;
 export const selectProjects = (state: AppState) => state.projects;
 
 // Entity selectors
@@ -887,3 +919,4 @@ export const selectCommands = (state: AppState) => state.commands;
 export const selectHooks = (state: AppState) => state.hooks;
 export const selectPlugins = (state: AppState) => state.plugins;
 export const selectMcpServers = (state: AppState) => state.mcpServers;
+export const selectLspServers = (state: AppState) => state.lspServers;